@@ -0,0 +1,155 @@
+// Bitcoin Hashes Library
+// Written in 2019 by
+//   The rust-bitcoin developers
+//
+// To the extent possible under law, the author(s) have dedicated all
+// copyright and related and neighboring rights to this software to
+// the public domain worldwide. This software is distributed without
+// any warranty.
+//
+// You should have received a copy of the CC0 Public Domain Dedication
+// along with this software.
+// If not, see <http://creativecommons.org/publicdomain/zero/1.0/>.
+//
+
+//! # Bitcoin Hashes Macros
+//!
+//! Procedural macros for `bitcoin_hashes`. Currently this is `#[derive(Tag)]`,
+//! which implements `sha256t::Tag` for a unit struct from a `#[tag = "..."]`
+//! attribute. The tag prefix is hashed when the crate is compiled, so the
+//! resulting `engine()` only has to load a constant midstate.
+//!
+//! ```
+//! #[macro_use] extern crate bitcoin_hashes_macros;
+//! extern crate bitcoin_hashes;
+//!
+//! use bitcoin_hashes::{sha256t, Hash};
+//!
+//! #[derive(Tag)]
+//! #[tag = "BIP0340/challenge"]
+//! pub struct Challenge;
+//!
+//! # fn main() {
+//! let hash = sha256t::Hash::<Challenge>::hash(b"message");
+//! # let _ = hash;
+//! # }
+//! ```
+
+// Coding conventions
+#![deny(non_upper_case_globals)]
+#![deny(non_camel_case_types)]
+#![deny(non_snake_case)]
+#![deny(unused_mut)]
+#![deny(missing_docs)]
+
+extern crate bitcoin_hashes;
+extern crate proc_macro;
+
+use bitcoin_hashes::{sha256t, HashEngine};
+use proc_macro::{Delimiter, TokenStream, TokenTree};
+
+/// Implements `sha256t::Tag` for a unit struct, using the tag given by its
+/// `#[tag = "..."]` attribute and the name of the struct as the tag name
+#[proc_macro_derive(Tag, attributes(tag))]
+pub fn derive_tag(input: TokenStream) -> TokenStream {
+    let code = match parse_tag_struct(input) {
+        Ok((name, tag)) => tag_impl(&name, &tag),
+        Err(msg) => format!("compile_error!({:?});", msg),
+    };
+    code.parse().expect("generated code is valid")
+}
+
+/// Extracts the struct name and the tag string from the derive input
+fn parse_tag_struct(input: TokenStream) -> Result<(String, String), String> {
+    let mut tag = None;
+    let mut name = None;
+
+    let mut tokens = input.into_iter();
+    while let Some(token) = tokens.next() {
+        match token {
+            TokenTree::Group(ref group) if group.delimiter() == Delimiter::Bracket => {
+                let attr: Vec<TokenTree> = group.stream().into_iter().collect();
+                match attr.get(0) {
+                    Some(&TokenTree::Ident(ref ident)) if ident.to_string() == "tag" => {}
+                    _ => continue,
+                }
+                if tag.is_some() {
+                    return Err("duplicate `tag` attribute".to_owned());
+                }
+                tag = Some(parse_tag_attr(&attr)?);
+            }
+            TokenTree::Ident(ref ident) if ident.to_string() == "struct" => {
+                match tokens.next() {
+                    Some(TokenTree::Ident(ident)) => name = Some(ident.to_string()),
+                    _ => return Err("expected struct name".to_owned()),
+                }
+                match tokens.next() {
+                    Some(TokenTree::Punct(ref punct)) if punct.as_char() == ';' => {}
+                    _ => return Err("`Tag` can only be derived for unit structs".to_owned()),
+                }
+            }
+            TokenTree::Ident(ref ident) if ident.to_string() == "enum" || ident.to_string() == "union" => {
+                return Err("`Tag` can only be derived for unit structs".to_owned());
+            }
+            _ => {}
+        }
+    }
+
+    match (name, tag) {
+        (Some(name), Some(tag)) => Ok((name, tag)),
+        (Some(_), None) => Err("missing `#[tag = \"...\"]` attribute".to_owned()),
+        (None, _) => Err("`Tag` can only be derived for unit structs".to_owned()),
+    }
+}
+
+/// Parses the tokens `tag = "..."` of a tag attribute
+fn parse_tag_attr(attr: &[TokenTree]) -> Result<String, String> {
+    const USAGE: &str = "expected `#[tag = \"...\"]`";
+
+    if attr.len() != 3 {
+        return Err(USAGE.to_owned());
+    }
+    match attr[1] {
+        TokenTree::Punct(ref punct) if punct.as_char() == '=' => {}
+        _ => return Err(USAGE.to_owned()),
+    }
+    let lit = match attr[2] {
+        TokenTree::Literal(ref lit) => lit.to_string(),
+        _ => return Err(USAGE.to_owned()),
+    };
+    // Only plain string literals are accepted; escapes would have to be
+    // interpreted by hand since `proc_macro` gives no access to the value
+    if lit.len() < 2 || !lit.starts_with('"') || !lit.ends_with('"') {
+        return Err(USAGE.to_owned());
+    }
+    let tag = &lit[1..lit.len() - 1];
+    if tag.contains('\\') {
+        return Err("escape sequences are not supported in tags".to_owned());
+    }
+    Ok(tag.to_owned())
+}
+
+/// Generates the `Tag` impl with the tag prefix midstate precomputed
+fn tag_impl(name: &str, tag: &str) -> String {
+    let midstate = sha256t::tag_engine(tag).midstate();
+    let bytes: Vec<String> = midstate[..].iter().map(|b| format!("{:#04x}", b)).collect();
+    format!(
+        "impl ::bitcoin_hashes::sha256t::Tag for {name} {{
+            fn engine() -> ::bitcoin_hashes::sha256::HashEngine {{
+                // Midstate of SHA256({tag:?}) || SHA256({tag:?})
+                const MIDSTATE: [u8; 32] = [{bytes}];
+                ::bitcoin_hashes::sha256::HashEngine::from_midstate(
+                    ::bitcoin_hashes::sha256::Midstate(MIDSTATE),
+                    64,
+                ).expect(\"the tag prefix is one block\")
+            }}
+
+            fn name() -> &'static str {{
+                {name:?}
+            }}
+        }}",
+        name = name,
+        tag = tag,
+        bytes = bytes.join(", "),
+    )
+}
@@ -0,0 +1,45 @@
+#[macro_use] extern crate bitcoin_hashes_macros;
+extern crate bitcoin_hashes;
+
+use bitcoin_hashes::{sha256, sha256t, Hash, HashEngine};
+use bitcoin_hashes::sha256t::Tag;
+
+#[derive(Tag)]
+#[tag = "BIP0340/challenge"]
+pub struct Challenge;
+
+/// A doc comment and other attributes are skipped over
+#[derive(Tag)]
+#[allow(dead_code)]
+#[tag = "TapLeaf"]
+struct TapLeaf;
+
+#[derive(Tag)]
+#[tag = ""]
+struct Empty;
+
+#[test]
+fn midstate_matches_runtime() {
+    assert_eq!(Challenge::engine().midstate(), sha256t::tag_engine("BIP0340/challenge").midstate());
+    assert_eq!(TapLeaf::engine().midstate(), sha256t::tag_engine("TapLeaf").midstate());
+    assert_eq!(Empty::engine().midstate(), sha256t::tag_engine("").midstate());
+}
+
+#[test]
+fn tagged_hash() {
+    let mut engine = sha256t::tag_engine("BIP0340/challenge");
+    engine.input(b"message");
+    let expected = sha256::Hash::from_engine(engine);
+
+    let hash = sha256t::Hash::<Challenge>::hash(b"message");
+    assert_eq!(&hash[..], &expected[..]);
+}
+
+#[test]
+fn tag_name() {
+    assert_eq!(Challenge::name(), "Challenge");
+    assert_eq!(TapLeaf::name(), "TapLeaf");
+
+    let hash = sha256t::Hash::<Challenge>::hash(b"message");
+    assert_eq!(format!("{:?}", hash), format!("Challenge({})", hash));
+}
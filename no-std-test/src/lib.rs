@@ -0,0 +1,56 @@
+// Bitcoin Hashes Library
+// Written in 2019 by
+//   The rust-bitcoin developers
+//
+// To the extent possible under law, the author(s) have dedicated all
+// copyright and related and neighboring rights to this software to
+// the public domain worldwide. This software is distributed without
+// any warranty.
+//
+// You should have received a copy of the CC0 Public Domain Dedication
+// along with this software.
+// If not, see <http://creativecommons.org/publicdomain/zero/1.0/>.
+//
+
+//! Hashes some data in a `no_std` crate, so that the build fails if anything
+//! in the library depends on `std`
+
+#![no_std]
+
+extern crate bitcoin_hashes;
+
+use core::panic::PanicInfo;
+
+use bitcoin_hashes::hex::FromHex;
+use bitcoin_hashes::{sha256, sha256d, Hash, HashEngine, Hmac, HmacEngine};
+
+/// Writes the SHA256d of `len` bytes at `data` to the 32 bytes at `out`,
+/// returning whether its hex encoding matched `expected`
+#[no_mangle]
+pub unsafe extern "C" fn bitcoin_hashes_no_std_test(
+    data: *const u8,
+    len: usize,
+    expected: *const u8,
+    out: *mut u8,
+) -> bool {
+    let data = core::slice::from_raw_parts(data, len);
+    let expected = core::slice::from_raw_parts(expected, 64);
+    let out = core::slice::from_raw_parts_mut(out, 32);
+
+    let mut engine = HmacEngine::<sha256::Hash>::new(b"key");
+    engine.input(data);
+    let hmac = Hmac::<sha256::Hash>::from_engine(engine);
+
+    let hash = sha256d::Hash::hash(&hmac[..]);
+    out.copy_from_slice(&hash[..]);
+
+    match core::str::from_utf8(expected).map(sha256d::Hash::from_hex) {
+        Ok(Ok(parsed)) => parsed == hash,
+        _ => false,
+    }
+}
+
+#[panic_handler]
+fn panic(_info: &PanicInfo) -> ! {
+    loop {}
+}
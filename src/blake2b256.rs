@@ -0,0 +1,255 @@
+// Bitcoin Hashes Library
+// Written in 2019 by
+//   The rust-bitcoin developers
+//
+// To the extent possible under law, the author(s) have dedicated all
+// copyright and related and neighboring rights to this software to
+// the public domain worldwide. This software is distributed without
+// any warranty.
+//
+// You should have received a copy of the CC0 Public Domain Dedication
+// along with this software.
+// If not, see <http://creativecommons.org/publicdomain/zero/1.0/>.
+//
+
+//! # BLAKE2b-256
+//!
+//! BLAKE2b with a 32-byte digest. Note that the digest length is part of the
+//! BLAKE2b parameter block, so this is *not* a truncation of BLAKE2b-512.
+
+use blake2b512;
+use HashEngine as EngineTrait;
+use Hash as HashTrait;
+use Error;
+
+/// Engine to compute BLAKE2b-256 hash function
+#[derive(Clone)]
+pub struct HashEngine(blake2b512::HashEngine);
+
+write_impl!(HashEngine);
+extend_impl!(HashEngine, Hash);
+engine_default_impl!(HashEngine, Hash);
+zeroize_impl!(HashEngine);
+
+// The inner engine zeroizes itself when dropped
+#[cfg(feature = "zeroize")]
+impl ::zeroize::ZeroizeOnDrop for HashEngine {}
+
+impl HashEngine {
+    /// Construct a new engine with a 16-byte personalization string mixed
+    /// into its parameter block, for domain separation between protocols
+    pub fn with_personalization(personal: &[u8; 16]) -> HashEngine {
+        HashEngine(blake2b512::HashEngine::with_params(32, personal))
+    }
+}
+
+impl EngineTrait for HashEngine {
+    type MidState = [u8; 64];
+
+    fn midstate(&self) -> [u8; 64] {
+        self.0.midstate()
+    }
+
+    const BLOCK_SIZE: usize = 128;
+
+    fn input(&mut self, data: &[u8]) {
+        self.0.input(data)
+    }
+}
+
+/// Output of the BLAKE2b-256 hash function
+#[derive(Copy, Clone, PartialEq, Eq, Default, PartialOrd, Ord, Hash)]
+pub struct Hash([u8; 32]);
+
+hex_fmt_impl!(Debug, Hash);
+hex_fmt_impl!(Display, Hash);
+hex_fmt_impl!(LowerHex, Hash);
+hex_fmt_impl!(UpperHex, Hash);
+index_impl!(Hash);
+serde_impl!(Hash, 32);
+borrow_slice_impl!(Hash);
+from_str_impl!(Hash);
+try_from_impl!(Hash);
+from_bytes_impl!(Hash, 32);
+into_iter_impl!(Hash);
+eq_bytes_impl!(Hash, 32);
+deref_impl!(Hash, 32);
+inner_ref_impl!(Hash, 32);
+bitops_impl!(Hash);
+ct_eq_impl!(Hash);
+zeroize_impl!(Hash);
+rand_impl!(Hash);
+proptest_impl!(Hash);
+quickcheck_impl!(Hash);
+
+impl HashTrait for Hash {
+    type Engine = HashEngine;
+    type Inner = [u8; 32];
+
+    fn engine() -> HashEngine {
+        HashEngine(blake2b512::HashEngine::with_params(32, &[0; 16]))
+    }
+
+    fn from_engine(e: HashEngine) -> Hash {
+        let mut ret = [0; 32];
+        ret.copy_from_slice(&e.0.finalize()[..32]);
+        Hash(ret)
+    }
+
+    const LEN: usize = 32;
+
+    fn from_slice(sl: &[u8]) -> Result<Hash, Error> {
+        if sl.len() != 32 {
+            Err(Error::InvalidLength(Self::LEN, sl.len()))
+        } else {
+            let mut ret = [0; 32];
+            ret.copy_from_slice(sl);
+            Ok(Hash(ret))
+        }
+    }
+
+    fn from_byte_array(bytes: Self::Inner) -> Self {
+        Hash(bytes)
+    }
+
+    fn to_byte_array(self) -> Self::Inner {
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use blake2b256;
+    use hex::{FromHex, ToHex};
+    use {Hash, HashEngine};
+
+    #[derive(Clone)]
+    struct Test {
+        input: Vec<u8>,
+        output: Vec<u8>,
+        output_str: &'static str,
+    }
+
+    #[test]
+    fn test() {
+        let tests = vec![
+            // Test vectors computed with Python's `hashlib.blake2b`
+            Test {
+                input: vec![],
+                output: vec![
+                    0x0e, 0x57, 0x51, 0xc0, 0x26, 0xe5, 0x43, 0xb2,
+                    0xe8, 0xab, 0x2e, 0xb0, 0x60, 0x99, 0xda, 0xa1,
+                    0xd1, 0xe5, 0xdf, 0x47, 0x77, 0x8f, 0x77, 0x87,
+                    0xfa, 0xab, 0x45, 0xcd, 0xf1, 0x2f, 0xe3, 0xa8,
+                ],
+                output_str: "0e5751c026e543b2e8ab2eb06099daa1d1e5df47778f7787faab45cdf12fe3a8",
+            },
+            Test {
+                input: b"abc".to_vec(),
+                output: vec![
+                    0xbd, 0xdd, 0x81, 0x3c, 0x63, 0x42, 0x39, 0x72,
+                    0x31, 0x71, 0xef, 0x3f, 0xee, 0x98, 0x57, 0x9b,
+                    0x94, 0x96, 0x4e, 0x3b, 0xb1, 0xcb, 0x3e, 0x42,
+                    0x72, 0x62, 0xc8, 0xc0, 0x68, 0xd5, 0x23, 0x19,
+                ],
+                output_str: "bddd813c634239723171ef3fee98579b94964e3bb1cb3e427262c8c068d52319",
+            },
+            Test {
+                input: (0..128).collect(),
+                output: vec![
+                    0xc3, 0x58, 0x2f, 0x71, 0xeb, 0xb2, 0xbe, 0x66,
+                    0xfa, 0x5d, 0xd7, 0x50, 0xf8, 0x0b, 0xaa, 0xe9,
+                    0x75, 0x54, 0xf3, 0xb0, 0x15, 0x66, 0x3c, 0x8b,
+                    0xe3, 0x77, 0xcf, 0xcb, 0x24, 0x88, 0xc1, 0xd1,
+                ],
+                output_str: "c3582f71ebb2be66fa5dd750f80baae97554f3b015663c8be377cfcb2488c1d1",
+            },
+        ];
+
+        for test in tests {
+            // Hash through high-level API, check hex encoding/decoding
+            let hash = blake2b256::Hash::hash(&test.input);
+            assert_eq!(hash, blake2b256::Hash::from_hex(test.output_str).expect("parse hex"));
+            assert_eq!(&hash[..], &test.output[..]);
+            assert_eq!(&hash.to_hex(), &test.output_str);
+
+            // Hash through engine, checking that we can input byte by byte
+            let mut engine = blake2b256::Hash::engine();
+            for ch in &test.input {
+                engine.write_all(&[*ch]).expect("write to engine");
+            }
+            let manual_hash = blake2b256::Hash::from_engine(engine);
+            assert_eq!(hash, manual_hash);
+            assert_eq!(hash.to_byte_array()[..].as_ref(), test.output.as_slice());
+        }
+    }
+
+    #[test]
+    fn personalization() {
+        let mut engine = blake2b256::HashEngine::with_personalization(b"ZcashPoW\0\0\0\0\0\0\0\0");
+        engine.input(b"abc");
+        assert_eq!(
+            blake2b256::Hash::from_engine(engine).to_hex(),
+            "6469eaf47a1c918c8bd02cebc0e5063c66a7dbfc6d0308309f08ba90cdc1bdcb",
+        );
+    }
+
+    #[cfg(feature="serde")]
+    #[test]
+    fn blake2b256_serde() {
+        use serde_test::{Configure, Token, assert_tokens};
+
+        static HASH_BYTES: [u8; 32] = [
+            0xbd, 0xdd, 0x81, 0x3c, 0x63, 0x42, 0x39, 0x72,
+            0x31, 0x71, 0xef, 0x3f, 0xee, 0x98, 0x57, 0x9b,
+            0x94, 0x96, 0x4e, 0x3b, 0xb1, 0xcb, 0x3e, 0x42,
+            0x72, 0x62, 0xc8, 0xc0, 0x68, 0xd5, 0x23, 0x19,
+        ];
+
+        let hash = blake2b256::Hash::from_slice(&HASH_BYTES).expect("right number of bytes");
+        assert_tokens(&hash.compact(), &[Token::BorrowedBytes(&HASH_BYTES[..])]);
+        assert_tokens(&hash.readable(), &[Token::Str("bddd813c634239723171ef3fee98579b94964e3bb1cb3e427262c8c068d52319")]);
+    }
+}
+
+#[cfg(all(test, feature="unstable"))]
+mod benches {
+    use std::io::Write;
+    use test::Bencher;
+
+    use blake2b256;
+    use Hash;
+
+    #[bench]
+    pub fn blake2b256_10(bh: & mut Bencher) {
+        let mut engine = blake2b256::Hash::engine();
+        let bytes = [1u8; 10];
+        bh.iter( || {
+            engine.write_all(&bytes).expect("write");
+        });
+        bh.bytes = bytes.len() as u64;
+    }
+
+    #[bench]
+    pub fn blake2b256_1k(bh: & mut Bencher) {
+        let mut engine = blake2b256::Hash::engine();
+        let bytes = [1u8; 1024];
+        bh.iter( || {
+            engine.write_all(&bytes).expect("write");
+        });
+        bh.bytes = bytes.len() as u64;
+    }
+
+    #[bench]
+    pub fn blake2b256_64k(bh: & mut Bencher) {
+        let mut engine = blake2b256::Hash::engine();
+        let bytes = [1u8; 65536];
+        bh.iter( || {
+            engine.write_all(&bytes).expect("write");
+        });
+        bh.bytes = bytes.len() as u64;
+    }
+
+}
@@ -0,0 +1,451 @@
+// Bitcoin Hashes Library
+// Written in 2019 by
+//   The rust-bitcoin developers
+//
+// To the extent possible under law, the author(s) have dedicated all
+// copyright and related and neighboring rights to this software to
+// the public domain worldwide. This software is distributed without
+// any warranty.
+//
+// You should have received a copy of the CC0 Public Domain Dedication
+// along with this software.
+// If not, see <http://creativecommons.org/publicdomain/zero/1.0/>.
+//
+
+//! # BLAKE2b-512
+//!
+//! Unkeyed BLAKE2b as specified in RFC 7693, with the full 64-byte digest.
+
+use core::hash;
+
+use byteorder::{ByteOrder, LittleEndian};
+
+use HashEngine as EngineTrait;
+use Hash as HashTrait;
+use Error;
+
+const BLOCK_SIZE: usize = 128;
+
+const IV: [u64; 8] = [
+    0x6a09e667f3bcc908, 0xbb67ae8584caa73b, 0x3c6ef372fe94f82b, 0xa54ff53a5f1d36f1,
+    0x510e527fade682d1, 0x9b05688c2b3e6c1f, 0x1f83d9abfb41bd6b, 0x5be0cd19137e2179,
+];
+
+const SIGMA: [[usize; 16]; 12] = [
+    [ 0,  1,  2,  3,  4,  5,  6,  7,  8,  9, 10, 11, 12, 13, 14, 15],
+    [14, 10,  4,  8,  9, 15, 13,  6,  1, 12,  0,  2, 11,  7,  5,  3],
+    [11,  8, 12,  0,  5,  2, 15, 13, 10, 14,  3,  6,  7,  1,  9,  4],
+    [ 7,  9,  3,  1, 13, 12, 11, 14,  2,  6,  5, 10,  4,  0, 15,  8],
+    [ 9,  0,  5,  7,  2,  4, 10, 15, 14,  1, 11, 12,  6,  8,  3, 13],
+    [ 2, 12,  6, 10,  0, 11,  8,  3,  4, 13,  7,  5, 15, 14,  1,  9],
+    [12,  5,  1, 15, 14, 13,  4, 10,  0,  7,  6,  3,  9,  2,  8, 11],
+    [13, 11,  7, 14, 12,  1,  3,  9,  5,  0, 15,  4,  8,  6,  2, 10],
+    [ 6, 15, 14,  9, 11,  3,  0,  8, 12,  2, 13,  7,  1,  4, 10,  5],
+    [10,  2,  8,  4,  7,  6,  1,  5, 15, 11,  9, 14,  3, 12, 13,  0],
+    [ 0,  1,  2,  3,  4,  5,  6,  7,  8,  9, 10, 11, 12, 13, 14, 15],
+    [14, 10,  4,  8,  9, 15, 13,  6,  1, 12,  0,  2, 11,  7,  5,  3],
+];
+
+/// Engine to compute BLAKE2b-512 hash function
+pub struct HashEngine {
+    h: [u64; 8],
+    length: usize,
+    buffer: [u8; BLOCK_SIZE],
+}
+
+write_impl!(HashEngine);
+extend_impl!(HashEngine, Hash);
+engine_default_impl!(HashEngine, Hash);
+zeroize_engine_impl!(HashEngine);
+
+impl Clone for HashEngine {
+    fn clone(&self) -> HashEngine {
+        HashEngine {
+            h: self.h,
+            length: self.length,
+            buffer: self.buffer,
+        }
+    }
+}
+
+impl HashEngine {
+    /// Construct a new engine with a 16-byte personalization string mixed
+    /// into its parameter block, for domain separation between protocols
+    pub fn with_personalization(personal: &[u8; 16]) -> HashEngine {
+        HashEngine::with_params(64, personal)
+    }
+
+    /// Construct an unkeyed engine for a digest of `outlen` bytes
+    pub(crate) fn with_params(outlen: u8, personal: &[u8; 16]) -> HashEngine {
+        debug_assert!(outlen > 0 && outlen <= 64);
+
+        // Parameter block: digest length, no key, fanout 1, depth 1; the
+        // personalization occupies the last two words
+        let mut h = IV;
+        h[0] ^= 0x01010000 ^ outlen as u64;
+        h[6] ^= LittleEndian::read_u64(&personal[..8]);
+        h[7] ^= LittleEndian::read_u64(&personal[8..]);
+
+        HashEngine {
+            h,
+            length: 0,
+            buffer: [0; BLOCK_SIZE],
+        }
+    }
+
+    /// Compress the final block and output the full 64-byte digest
+    pub(crate) fn finalize(mut self) -> [u8; 64] {
+        let buf_idx = self.length % BLOCK_SIZE;
+        if buf_idx != 0 {
+            for b in self.buffer[buf_idx..].iter_mut() {
+                *b = 0;
+            }
+        }
+        self.compress(true);
+
+        let mut ret = [0; 64];
+        LittleEndian::write_u64_into(&self.h, &mut ret);
+        ret
+    }
+}
+
+impl EngineTrait for HashEngine {
+    type MidState = [u8; 64];
+
+    fn midstate(&self) -> [u8; 64] {
+        let mut ret = [0; 64];
+        LittleEndian::write_u64_into(&self.h, &mut ret);
+        ret
+    }
+
+    const BLOCK_SIZE: usize = 128;
+
+    fn input(&mut self, mut inp: &[u8]) {
+        while !inp.is_empty() {
+            // Unlike the SHA2 engines we cannot compress a full buffer right
+            // away, since the last block is compressed differently; only do
+            // so once we know that more data follows it.
+            let buf_idx = self.length % BLOCK_SIZE;
+            if buf_idx == 0 && self.length > 0 {
+                self.compress(false);
+            }
+
+            let write_len = ::core::cmp::min(BLOCK_SIZE - buf_idx, inp.len());
+            self.buffer[buf_idx..buf_idx + write_len].copy_from_slice(&inp[..write_len]);
+            self.length += write_len;
+            inp = &inp[write_len..];
+        }
+    }
+}
+
+/// Output of the BLAKE2b-512 hash function
+pub struct Hash([u8; 64]);
+
+impl Copy for Hash {}
+
+impl Clone for Hash {
+    fn clone(&self) -> Hash {
+        *self
+    }
+}
+
+impl PartialEq for Hash {
+    fn eq(&self, other: &Hash) -> bool {
+        self.0[..] == other.0[..]
+    }
+}
+
+impl Eq for Hash {}
+
+impl Default for Hash {
+    fn default() -> Hash {
+        Hash([0; 64])
+    }
+}
+
+use core::cmp::Ordering;
+
+impl PartialOrd for Hash {
+    fn partial_cmp(&self, other: &Hash) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Hash {
+    fn cmp(&self, other: &Hash) -> Ordering {
+        self.0[..].cmp(&other.0[..])
+    }
+}
+
+impl hash::Hash for Hash {
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        self.0[..].hash(state)
+    }
+}
+
+hex_fmt_impl!(Debug, Hash);
+hex_fmt_impl!(Display, Hash);
+hex_fmt_impl!(LowerHex, Hash);
+hex_fmt_impl!(UpperHex, Hash);
+index_impl!(Hash);
+serde_impl!(Hash, 64);
+borrow_slice_impl!(Hash);
+from_str_impl!(Hash);
+try_from_impl!(Hash);
+from_bytes_impl!(Hash, 64);
+into_iter_impl!(Hash);
+eq_bytes_impl!(Hash, 64);
+deref_impl!(Hash, 64);
+inner_ref_impl!(Hash, 64);
+bitops_impl!(Hash);
+ct_eq_impl!(Hash);
+zeroize_impl!(Hash);
+rand_impl!(Hash);
+proptest_impl!(Hash);
+quickcheck_impl!(Hash);
+
+impl HashTrait for Hash {
+    type Engine = HashEngine;
+    type Inner = [u8; 64];
+
+    fn engine() -> HashEngine {
+        HashEngine::with_params(64, &[0; 16])
+    }
+
+    fn from_engine(e: HashEngine) -> Hash {
+        Hash(e.finalize())
+    }
+
+    const LEN: usize = 64;
+
+    fn from_slice(sl: &[u8]) -> Result<Hash, Error> {
+        if sl.len() != 64 {
+            Err(Error::InvalidLength(Self::LEN, sl.len()))
+        } else {
+            let mut ret = [0; 64];
+            ret.copy_from_slice(sl);
+            Ok(Hash(ret))
+        }
+    }
+
+    fn from_byte_array(bytes: Self::Inner) -> Self {
+        Hash(bytes)
+    }
+
+    fn to_byte_array(self) -> Self::Inner {
+        self.0
+    }
+}
+
+macro_rules! g(
+    ($v:expr, $a:expr, $b:expr, $c:expr, $d:expr, $x:expr, $y:expr) => (
+        $v[$a] = $v[$a].wrapping_add($v[$b]).wrapping_add($x);
+        $v[$d] = ($v[$d] ^ $v[$a]).rotate_right(32);
+        $v[$c] = $v[$c].wrapping_add($v[$d]);
+        $v[$b] = ($v[$b] ^ $v[$c]).rotate_right(24);
+        $v[$a] = $v[$a].wrapping_add($v[$b]).wrapping_add($y);
+        $v[$d] = ($v[$d] ^ $v[$a]).rotate_right(16);
+        $v[$c] = $v[$c].wrapping_add($v[$d]);
+        $v[$b] = ($v[$b] ^ $v[$c]).rotate_right(63);
+    )
+);
+
+impl HashEngine {
+    // Compression function F from RFC 7693, section 3.2
+    fn compress(&mut self, last: bool) {
+        let mut m = [0u64; 16];
+        LittleEndian::read_u64_into(&self.buffer, &mut m);
+
+        let mut v = [0u64; 16];
+        v[..8].copy_from_slice(&self.h);
+        v[8..].copy_from_slice(&IV);
+        // The high word of the 128-bit byte counter is always zero for
+        // lengths that fit in a `usize`
+        v[12] ^= self.length as u64;
+        if last {
+            v[14] = !v[14];
+        }
+
+        for s in SIGMA.iter() {
+            g!(v, 0, 4,  8, 12, m[s[ 0]], m[s[ 1]]);
+            g!(v, 1, 5,  9, 13, m[s[ 2]], m[s[ 3]]);
+            g!(v, 2, 6, 10, 14, m[s[ 4]], m[s[ 5]]);
+            g!(v, 3, 7, 11, 15, m[s[ 6]], m[s[ 7]]);
+            g!(v, 0, 5, 10, 15, m[s[ 8]], m[s[ 9]]);
+            g!(v, 1, 6, 11, 12, m[s[10]], m[s[11]]);
+            g!(v, 2, 7,  8, 13, m[s[12]], m[s[13]]);
+            g!(v, 3, 4,  9, 14, m[s[14]], m[s[15]]);
+        }
+
+        for (i, h) in self.h.iter_mut().enumerate() {
+            *h ^= v[i] ^ v[i + 8];
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use blake2b512;
+    use hex::{FromHex, ToHex};
+    use {Hash, HashEngine};
+
+    #[derive(Clone)]
+    struct Test {
+        input: Vec<u8>,
+        output: Vec<u8>,
+        output_str: &'static str,
+    }
+
+    #[test]
+    fn test() {
+        let tests = vec![
+            // Test vectors computed with Python's `hashlib.blake2b`
+            Test {
+                input: vec![],
+                output: vec![
+                    0x78, 0x6a, 0x02, 0xf7, 0x42, 0x01, 0x59, 0x03,
+                    0xc6, 0xc6, 0xfd, 0x85, 0x25, 0x52, 0xd2, 0x72,
+                    0x91, 0x2f, 0x47, 0x40, 0xe1, 0x58, 0x47, 0x61,
+                    0x8a, 0x86, 0xe2, 0x17, 0xf7, 0x1f, 0x54, 0x19,
+                    0xd2, 0x5e, 0x10, 0x31, 0xaf, 0xee, 0x58, 0x53,
+                    0x13, 0x89, 0x64, 0x44, 0x93, 0x4e, 0xb0, 0x4b,
+                    0x90, 0x3a, 0x68, 0x5b, 0x14, 0x48, 0xb7, 0x55,
+                    0xd5, 0x6f, 0x70, 0x1a, 0xfe, 0x9b, 0xe2, 0xce,
+                ],
+                output_str: "786a02f742015903c6c6fd852552d272912f4740e15847618a86e217f71f5419d25e1031afee585313896444934eb04b903a685b1448b755d56f701afe9be2ce",
+            },
+            // Example from RFC 7693, appendix A
+            Test {
+                input: b"abc".to_vec(),
+                output: vec![
+                    0xba, 0x80, 0xa5, 0x3f, 0x98, 0x1c, 0x4d, 0x0d,
+                    0x6a, 0x27, 0x97, 0xb6, 0x9f, 0x12, 0xf6, 0xe9,
+                    0x4c, 0x21, 0x2f, 0x14, 0x68, 0x5a, 0xc4, 0xb7,
+                    0x4b, 0x12, 0xbb, 0x6f, 0xdb, 0xff, 0xa2, 0xd1,
+                    0x7d, 0x87, 0xc5, 0x39, 0x2a, 0xab, 0x79, 0x2d,
+                    0xc2, 0x52, 0xd5, 0xde, 0x45, 0x33, 0xcc, 0x95,
+                    0x18, 0xd3, 0x8a, 0xa8, 0xdb, 0xf1, 0x92, 0x5a,
+                    0xb9, 0x23, 0x86, 0xed, 0xd4, 0x00, 0x99, 0x23,
+                ],
+                output_str: "ba80a53f981c4d0d6a2797b69f12f6e94c212f14685ac4b74b12bb6fdbffa2d17d87c5392aab792dc252d5de4533cc9518d38aa8dbf1925ab92386edd4009923",
+            },
+            // Exactly one block, which must be compressed as the final one
+            Test {
+                input: (0..128).collect(),
+                output: vec![
+                    0x23, 0x19, 0xe3, 0x78, 0x9c, 0x47, 0xe2, 0xda,
+                    0xa5, 0xfe, 0x80, 0x7f, 0x61, 0xbe, 0xc2, 0xa1,
+                    0xa6, 0x53, 0x7f, 0xa0, 0x3f, 0x19, 0xff, 0x32,
+                    0xe8, 0x7e, 0xec, 0xbf, 0xd6, 0x4b, 0x7e, 0x0e,
+                    0x8c, 0xcf, 0xf4, 0x39, 0xac, 0x33, 0x3b, 0x04,
+                    0x0f, 0x19, 0xb0, 0xc4, 0xdd, 0xd1, 0x1a, 0x61,
+                    0xe2, 0x4a, 0xc1, 0xfe, 0x0f, 0x10, 0xa0, 0x39,
+                    0x80, 0x6c, 0x5d, 0xcc, 0x0d, 0xa3, 0xd1, 0x15,
+                ],
+                output_str: "2319e3789c47e2daa5fe807f61bec2a1a6537fa03f19ff32e87eecbfd64b7e0e8ccff439ac333b040f19b0c4ddd11a61e24ac1fe0f10a039806c5dcc0da3d115",
+            },
+        ];
+
+        for test in tests {
+            // Hash through high-level API, check hex encoding/decoding
+            let hash = blake2b512::Hash::hash(&test.input);
+            assert_eq!(hash, blake2b512::Hash::from_hex(test.output_str).expect("parse hex"));
+            assert_eq!(&hash[..], &test.output[..]);
+            assert_eq!(&hash.to_hex(), &test.output_str);
+
+            // Hash through engine, checking that we can input byte by byte
+            let mut engine = blake2b512::Hash::engine();
+            for ch in &test.input {
+                engine.write_all(&[*ch]).expect("write to engine");
+            }
+            let manual_hash = blake2b512::Hash::from_engine(engine);
+            assert_eq!(hash, manual_hash);
+            assert_eq!(hash.to_byte_array()[..].as_ref(), test.output.as_slice());
+        }
+    }
+
+    #[test]
+    fn personalization() {
+        let mut engine = blake2b512::HashEngine::with_personalization(b"ZcashPoW\0\0\0\0\0\0\0\0");
+        engine.input(b"abc");
+        assert_eq!(
+            blake2b512::Hash::from_engine(engine).to_hex(),
+            "8d130e6ceda98fb59a78051fe5fd1f43143be70c4fbc30c82124138ea686695a\
+             35f296d74ed898cd6d63f92aa1f0bfd94f45baeca82d61f9df82c0f5569b0808",
+        );
+
+        // An all-zero personalization is the same as none at all
+        let mut engine = blake2b512::HashEngine::with_personalization(&[0; 16]);
+        engine.input(b"abc");
+        assert_eq!(blake2b512::Hash::from_engine(engine), blake2b512::Hash::hash(b"abc"));
+    }
+
+    #[cfg(feature="serde")]
+    #[test]
+    fn blake2b512_serde() {
+        use serde_test::{Configure, Token, assert_tokens};
+
+        static HASH_BYTES: [u8; 64] = [
+            0xba, 0x80, 0xa5, 0x3f, 0x98, 0x1c, 0x4d, 0x0d,
+            0x6a, 0x27, 0x97, 0xb6, 0x9f, 0x12, 0xf6, 0xe9,
+            0x4c, 0x21, 0x2f, 0x14, 0x68, 0x5a, 0xc4, 0xb7,
+            0x4b, 0x12, 0xbb, 0x6f, 0xdb, 0xff, 0xa2, 0xd1,
+            0x7d, 0x87, 0xc5, 0x39, 0x2a, 0xab, 0x79, 0x2d,
+            0xc2, 0x52, 0xd5, 0xde, 0x45, 0x33, 0xcc, 0x95,
+            0x18, 0xd3, 0x8a, 0xa8, 0xdb, 0xf1, 0x92, 0x5a,
+            0xb9, 0x23, 0x86, 0xed, 0xd4, 0x00, 0x99, 0x23,
+        ];
+
+        let hash = blake2b512::Hash::from_slice(&HASH_BYTES).expect("right number of bytes");
+        assert_tokens(&hash.compact(), &[Token::BorrowedBytes(&HASH_BYTES[..])]);
+        assert_tokens(
+            &hash.readable(),
+            &[Token::Str(
+                "ba80a53f981c4d0d6a2797b69f12f6e94c212f14685ac4b74b12bb6fdbffa2d1\
+                 7d87c5392aab792dc252d5de4533cc9518d38aa8dbf1925ab92386edd4009923"
+            )],
+        );
+    }
+}
+
+#[cfg(all(test, feature="unstable"))]
+mod benches {
+    use std::io::Write;
+    use test::Bencher;
+
+    use blake2b512;
+    use Hash;
+
+    #[bench]
+    pub fn blake2b512_10(bh: & mut Bencher) {
+        let mut engine = blake2b512::Hash::engine();
+        let bytes = [1u8; 10];
+        bh.iter( || {
+            engine.write_all(&bytes).expect("write");
+        });
+        bh.bytes = bytes.len() as u64;
+    }
+
+    #[bench]
+    pub fn blake2b512_1k(bh: & mut Bencher) {
+        let mut engine = blake2b512::Hash::engine();
+        let bytes = [1u8; 1024];
+        bh.iter( || {
+            engine.write_all(&bytes).expect("write");
+        });
+        bh.bytes = bytes.len() as u64;
+    }
+
+    #[bench]
+    pub fn blake2b512_64k(bh: & mut Bencher) {
+        let mut engine = blake2b512::Hash::engine();
+        let bytes = [1u8; 65536];
+        bh.iter( || {
+            engine.write_all(&bytes).expect("write");
+        });
+        bh.bytes = bytes.len() as u64;
+    }
+
+}
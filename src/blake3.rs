@@ -0,0 +1,574 @@
+// Bitcoin Hashes Library
+// Written in 2019 by
+//   The rust-bitcoin developers
+//
+// To the extent possible under law, the author(s) have dedicated all
+// copyright and related and neighboring rights to this software to
+// the public domain worldwide. This software is distributed without
+// any warranty.
+//
+// You should have received a copy of the CC0 Public Domain Dedication
+// along with this software.
+// If not, see <http://creativecommons.org/publicdomain/zero/1.0/>.
+//
+
+//! # BLAKE3
+//!
+//! BLAKE3 with its default 32-byte output, including the keyed hash and key
+//! derivation modes. Input is split into 1 KiB chunks which form the leaves
+//! of a binary tree; since data is input sequentially, the engine keeps the
+//! chaining values of completed subtrees on a stack and merges them as soon
+//! as their sibling is complete.
+
+use byteorder::{ByteOrder, LittleEndian};
+
+use HashEngine as EngineTrait;
+use Hash as HashTrait;
+use Error;
+
+const BLOCK_LEN: usize = 64;
+const CHUNK_LEN: usize = 1024;
+// Enough for 2^54 chunks, i.e. 2^64 bytes of input
+const MAX_DEPTH: usize = 54;
+
+const CHUNK_START: u32 = 1 << 0;
+const CHUNK_END: u32 = 1 << 1;
+const PARENT: u32 = 1 << 2;
+const ROOT: u32 = 1 << 3;
+const KEYED_HASH: u32 = 1 << 4;
+const DERIVE_KEY_CONTEXT: u32 = 1 << 5;
+const DERIVE_KEY_MATERIAL: u32 = 1 << 6;
+
+const IV: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a,
+    0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+const MSG_PERMUTATION: [usize; 16] = [2, 6, 3, 10, 7, 0, 4, 13, 1, 11, 12, 5, 9, 14, 15, 8];
+
+/// The quarter-round mixing function
+fn g(state: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize, mx: u32, my: u32) {
+    state[a] = state[a].wrapping_add(state[b]).wrapping_add(mx);
+    state[d] = (state[d] ^ state[a]).rotate_right(16);
+    state[c] = state[c].wrapping_add(state[d]);
+    state[b] = (state[b] ^ state[c]).rotate_right(12);
+    state[a] = state[a].wrapping_add(state[b]).wrapping_add(my);
+    state[d] = (state[d] ^ state[a]).rotate_right(8);
+    state[c] = state[c].wrapping_add(state[d]);
+    state[b] = (state[b] ^ state[c]).rotate_right(7);
+}
+
+fn round(state: &mut [u32; 16], m: &[u32; 16]) {
+    // Columns
+    g(state, 0, 4, 8, 12, m[0], m[1]);
+    g(state, 1, 5, 9, 13, m[2], m[3]);
+    g(state, 2, 6, 10, 14, m[4], m[5]);
+    g(state, 3, 7, 11, 15, m[6], m[7]);
+    // Diagonals
+    g(state, 0, 5, 10, 15, m[8], m[9]);
+    g(state, 1, 6, 11, 12, m[10], m[11]);
+    g(state, 2, 7, 8, 13, m[12], m[13]);
+    g(state, 3, 4, 9, 14, m[14], m[15]);
+}
+
+/// The compression function, returning the full 16-word state
+fn compress(cv: &[u32; 8], block: &[u8; BLOCK_LEN], counter: u64, block_len: u32, flags: u32) -> [u32; 16] {
+    let mut m = [0; 16];
+    LittleEndian::read_u32_into(block, &mut m);
+
+    let mut state = [
+        cv[0], cv[1], cv[2], cv[3], cv[4], cv[5], cv[6], cv[7],
+        IV[0], IV[1], IV[2], IV[3],
+        counter as u32, (counter >> 32) as u32, block_len, flags,
+    ];
+    for i in 0..7 {
+        round(&mut state, &m);
+        if i < 6 {
+            let mut permuted = [0; 16];
+            for (p, &idx) in permuted.iter_mut().zip(MSG_PERMUTATION.iter()) {
+                *p = m[idx];
+            }
+            m = permuted;
+        }
+    }
+    for i in 0..8 {
+        state[i] ^= state[i + 8];
+        state[i + 8] ^= cv[i];
+    }
+    state
+}
+
+fn first_8_words(words: [u32; 16]) -> [u32; 8] {
+    let mut ret = [0; 8];
+    ret.copy_from_slice(&words[..8]);
+    ret
+}
+
+/// The inputs of a compression producing a chaining value, or the output
+/// when it is the root of the tree
+struct Output {
+    cv: [u32; 8],
+    block: [u8; BLOCK_LEN],
+    counter: u64,
+    block_len: u32,
+    flags: u32,
+}
+
+impl Output {
+    fn chaining_value(&self) -> [u32; 8] {
+        first_8_words(compress(&self.cv, &self.block, self.counter, self.block_len, self.flags))
+    }
+
+    fn root_bytes(&self) -> [u8; 32] {
+        let words = compress(&self.cv, &self.block, 0, self.block_len, self.flags | ROOT);
+        let mut ret = [0; 32];
+        LittleEndian::write_u32_into(&words[..8], &mut ret);
+        ret
+    }
+}
+
+fn parent_output(left: &[u32; 8], right: &[u32; 8], key: &[u32; 8], flags: u32) -> Output {
+    let mut block = [0; BLOCK_LEN];
+    LittleEndian::write_u32_into(left, &mut block[..32]);
+    LittleEndian::write_u32_into(right, &mut block[32..]);
+    Output {
+        cv: *key,
+        block,
+        counter: 0,
+        block_len: BLOCK_LEN as u32,
+        flags: PARENT | flags,
+    }
+}
+
+/// State of the chunk currently being input
+struct ChunkState {
+    cv: [u32; 8],
+    counter: u64,
+    buffer: [u8; BLOCK_LEN],
+    buffer_len: usize,
+    blocks_compressed: usize,
+    flags: u32,
+}
+
+impl Clone for ChunkState {
+    fn clone(&self) -> ChunkState {
+        ChunkState {
+            cv: self.cv,
+            counter: self.counter,
+            buffer: self.buffer,
+            buffer_len: self.buffer_len,
+            blocks_compressed: self.blocks_compressed,
+            flags: self.flags,
+        }
+    }
+}
+
+impl ChunkState {
+    fn new(key: &[u32; 8], counter: u64, flags: u32) -> ChunkState {
+        ChunkState {
+            cv: *key,
+            counter,
+            buffer: [0; BLOCK_LEN],
+            buffer_len: 0,
+            blocks_compressed: 0,
+            flags,
+        }
+    }
+
+    fn len(&self) -> usize {
+        BLOCK_LEN * self.blocks_compressed + self.buffer_len
+    }
+
+    fn start_flag(&self) -> u32 {
+        if self.blocks_compressed == 0 { CHUNK_START } else { 0 }
+    }
+
+    fn input(&mut self, mut inp: &[u8]) {
+        while !inp.is_empty() {
+            // The last block of a chunk is compressed differently, so a full
+            // buffer is only compressed once more input arrives
+            if self.buffer_len == BLOCK_LEN {
+                let flags = self.flags | self.start_flag();
+                self.cv = first_8_words(compress(&self.cv, &self.buffer, self.counter, BLOCK_LEN as u32, flags));
+                self.blocks_compressed += 1;
+                self.buffer = [0; BLOCK_LEN];
+                self.buffer_len = 0;
+            }
+
+            let write_len = ::core::cmp::min(BLOCK_LEN - self.buffer_len, inp.len());
+            self.buffer[self.buffer_len..self.buffer_len + write_len].copy_from_slice(&inp[..write_len]);
+            self.buffer_len += write_len;
+            inp = &inp[write_len..];
+        }
+    }
+
+    fn output(&self) -> Output {
+        Output {
+            cv: self.cv,
+            block: self.buffer,
+            counter: self.counter,
+            block_len: self.buffer_len as u32,
+            flags: self.flags | self.start_flag() | CHUNK_END,
+        }
+    }
+}
+
+/// Engine to compute BLAKE3 hash function
+pub struct HashEngine {
+    key: [u32; 8],
+    chunk: ChunkState,
+    cv_stack: [[u32; 8]; MAX_DEPTH],
+    cv_stack_len: usize,
+    flags: u32,
+}
+
+write_impl!(HashEngine);
+extend_impl!(HashEngine, Hash);
+engine_default_impl!(HashEngine, Hash);
+
+impl Clone for HashEngine {
+    fn clone(&self) -> HashEngine {
+        HashEngine {
+            key: self.key,
+            chunk: self.chunk.clone(),
+            cv_stack: self.cv_stack,
+            cv_stack_len: self.cv_stack_len,
+            flags: self.flags,
+        }
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl ::zeroize::Zeroize for HashEngine {
+    fn zeroize(&mut self) {
+        ::zeroize::Zeroize::zeroize(&mut self.key);
+        ::zeroize::Zeroize::zeroize(&mut self.chunk.cv);
+        ::zeroize::Zeroize::zeroize(&mut self.chunk.buffer[..]);
+        for cv in self.cv_stack.iter_mut() {
+            ::zeroize::Zeroize::zeroize(cv);
+        }
+        ::zeroize::Zeroize::zeroize(&mut self.cv_stack_len);
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl Drop for HashEngine {
+    fn drop(&mut self) {
+        ::zeroize::Zeroize::zeroize(self);
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl ::zeroize::ZeroizeOnDrop for HashEngine {}
+
+impl HashEngine {
+    fn with_key_words(key: [u32; 8], flags: u32) -> HashEngine {
+        HashEngine {
+            key,
+            chunk: ChunkState::new(&key, 0, flags),
+            cv_stack: [[0; 8]; MAX_DEPTH],
+            cv_stack_len: 0,
+            flags,
+        }
+    }
+
+    /// Construct a new engine for the keyed hash mode, which can be used as
+    /// a MAC or PRF
+    pub fn new_keyed(key: &[u8; 32]) -> HashEngine {
+        let mut key_words = [0; 8];
+        LittleEndian::read_u32_into(key, &mut key_words);
+        HashEngine::with_key_words(key_words, KEYED_HASH)
+    }
+
+    /// Construct a new engine for the key derivation mode with the given
+    /// context string, which should be hardcoded, globally unique and
+    /// application-specific. The hash of the key material then is the key.
+    pub fn new_derive_key(context: &str) -> HashEngine {
+        let mut context_engine = HashEngine::with_key_words(IV, DERIVE_KEY_CONTEXT);
+        context_engine.input(context.as_bytes());
+        let context_key = context_engine.finalize();
+
+        let mut key_words = [0; 8];
+        LittleEndian::read_u32_into(&context_key, &mut key_words);
+        HashEngine::with_key_words(key_words, DERIVE_KEY_MATERIAL)
+    }
+
+    fn push_chunk_cv(&mut self, mut cv: [u32; 8], total_chunks: u64) {
+        // Each trailing zero bit of the chunk count is a subtree completed
+        // by this chunk, whose left half is on top of the stack
+        let mut total_chunks = total_chunks;
+        while total_chunks & 1 == 0 {
+            self.cv_stack_len -= 1;
+            let left = self.cv_stack[self.cv_stack_len];
+            cv = parent_output(&left, &cv, &self.key, self.flags).chaining_value();
+            total_chunks >>= 1;
+        }
+        self.cv_stack[self.cv_stack_len] = cv;
+        self.cv_stack_len += 1;
+    }
+
+    fn finalize(&self) -> [u8; 32] {
+        let mut output = self.chunk.output();
+        for left in self.cv_stack[..self.cv_stack_len].iter().rev() {
+            output = parent_output(left, &output.chaining_value(), &self.key, self.flags);
+        }
+        output.root_bytes()
+    }
+}
+
+impl EngineTrait for HashEngine {
+    type MidState = [u8; 32];
+
+    /// The chaining value of the current chunk
+    fn midstate(&self) -> [u8; 32] {
+        let mut ret = [0; 32];
+        LittleEndian::write_u32_into(&self.chunk.cv, &mut ret);
+        ret
+    }
+
+    const BLOCK_SIZE: usize = BLOCK_LEN;
+
+    fn input(&mut self, mut inp: &[u8]) {
+        while !inp.is_empty() {
+            // As within a chunk, the last chunk is treated differently so a
+            // full chunk is only finished once more input arrives
+            if self.chunk.len() == CHUNK_LEN {
+                let cv = self.chunk.output().chaining_value();
+                let total_chunks = self.chunk.counter + 1;
+                self.push_chunk_cv(cv, total_chunks);
+                self.chunk = ChunkState::new(&self.key, total_chunks, self.flags);
+            }
+
+            let write_len = ::core::cmp::min(CHUNK_LEN - self.chunk.len(), inp.len());
+            self.chunk.input(&inp[..write_len]);
+            inp = &inp[write_len..];
+        }
+    }
+}
+
+/// Output of the BLAKE3 hash function
+#[derive(Copy, Clone, PartialEq, Eq, Default, PartialOrd, Ord, Hash)]
+pub struct Hash([u8; 32]);
+
+hex_fmt_impl!(Debug, Hash);
+hex_fmt_impl!(Display, Hash);
+hex_fmt_impl!(LowerHex, Hash);
+hex_fmt_impl!(UpperHex, Hash);
+index_impl!(Hash);
+serde_impl!(Hash, 32);
+borrow_slice_impl!(Hash);
+from_str_impl!(Hash);
+try_from_impl!(Hash);
+from_bytes_impl!(Hash, 32);
+into_iter_impl!(Hash);
+eq_bytes_impl!(Hash, 32);
+deref_impl!(Hash, 32);
+inner_ref_impl!(Hash, 32);
+bitops_impl!(Hash);
+ct_eq_impl!(Hash);
+zeroize_impl!(Hash);
+rand_impl!(Hash);
+proptest_impl!(Hash);
+quickcheck_impl!(Hash);
+
+impl HashTrait for Hash {
+    type Engine = HashEngine;
+    type Inner = [u8; 32];
+
+    fn engine() -> HashEngine {
+        HashEngine::with_key_words(IV, 0)
+    }
+
+    fn from_engine(e: HashEngine) -> Hash {
+        Hash(e.finalize())
+    }
+
+    const LEN: usize = 32;
+
+    fn from_slice(sl: &[u8]) -> Result<Hash, Error> {
+        if sl.len() != 32 {
+            Err(Error::InvalidLength(Self::LEN, sl.len()))
+        } else {
+            let mut ret = [0; 32];
+            ret.copy_from_slice(sl);
+            Ok(Hash(ret))
+        }
+    }
+
+    fn from_byte_array(bytes: Self::Inner) -> Self {
+        Hash(bytes)
+    }
+
+    fn to_byte_array(self) -> Self::Inner {
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use blake3;
+    use hex::{FromHex, ToHex};
+    use {Hash, HashEngine};
+
+    #[derive(Clone)]
+    struct Test {
+        input_len: usize,
+        hash: &'static str,
+        keyed_hash: &'static str,
+        derive_key: &'static str,
+    }
+
+    #[test]
+    fn test() {
+        // Test vectors from the official BLAKE3 `test_vectors.json`, whose
+        // inputs are the repeating sequence 0, 1, ..., 250
+        let key = b"whats the Elvish word for friend";
+        let context = "BLAKE3 2019-12-27 16:29:52 test vectors context";
+        let tests = vec![
+            Test {
+                input_len: 0,
+                hash: "af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262",
+                keyed_hash: "92b2b75604ed3c761f9d6f62392c8a9227ad0ea3f09573e783f1498a4ed60d26",
+                derive_key: "2cc39783c223154fea8dfb7c1b1660f2ac2dcbd1c1de8277b0b0dd39b7e50d7d",
+            },
+            Test {
+                input_len: 1,
+                hash: "2d3adedff11b61f14c886e35afa036736dcd87a74d27b5c1510225d0f592e213",
+                keyed_hash: "6d7878dfff2f485635d39013278ae14f1454b8c0a3a2d34bc1ab38228a80c95b",
+                derive_key: "b3e2e340a117a499c6cf2398a19ee0d29cca2bb7404c73063382693bf66cb06c",
+            },
+            Test {
+                input_len: 1023,
+                hash: "10108970eeda3eb932baac1428c7a2163b0e924c9a9e25b35bba72b28f70bd11",
+                keyed_hash: "c951ecdf03288d0fcc96ee3413563d8a6d3589547f2c2fb36d9786470f1b9d6e",
+                derive_key: "74a16c1c3d44368a86e1ca6df64be6a2f64cce8f09220787450722d85725dea5",
+            },
+            Test {
+                input_len: 1024,
+                hash: "42214739f095a406f3fc83deb889744ac00df831c10daa55189b5d121c855af7",
+                keyed_hash: "75c46f6f3d9eb4f55ecaaee480db732e6c2105546f1e675003687c31719c7ba4",
+                derive_key: "7356cd7720d5b66b6d0697eb3177d9f8d73a4a5c5e968896eb6a689684302706",
+            },
+            Test {
+                input_len: 1025,
+                hash: "d00278ae47eb27b34faecf67b4fe263f82d5412916c1ffd97c8cb7fb814b8444",
+                keyed_hash: "357dc55de0c7e382c900fd6e320acc04146be01db6a8ce7210b7189bd664ea69",
+                derive_key: "effaa245f065fbf82ac186839a249707c3bddf6d3fdda22d1b95a3c970379bcb",
+            },
+            Test {
+                input_len: 2049,
+                hash: "5f4d72f40d7a5f82b15ca2b2e44b1de3c2ef86c426c95c1af0b6879522563030",
+                keyed_hash: "9f29700902f7c86e514ddc4df1e3049f258b2472b6dd5267f61bf13983b78dd5",
+                derive_key: "2ea477c5515cc3dd606512ee72bb3e0e758cfae7232826f35fb98ca1bcbdf273",
+            },
+            Test {
+                input_len: 3073,
+                hash: "7124b49501012f81cc7f11ca069ec9226cecb8a2c850cfe644e327d22d3e1cd3",
+                keyed_hash: "68dede9bef00ba89e43f31a6825f4cf433389fedae75c04ee9f0cf16a427c95a",
+                derive_key: "72613c9ec9ff7e40f8f5c173784c532ad852e827dba2bf85b2ab4b76f7079081",
+            },
+            Test {
+                input_len: 8192,
+                hash: "aae792484c8efe4f19e2ca7d371d8c467ffb10748d8a5a1ae579948f718a2a63",
+                keyed_hash: "dc9637c8845a770b4cbf76b8daec0eebf7dc2eac11498517f08d44c8fc00d58a",
+                derive_key: "ad01d7ae4ad059b0d33baa3c01319dcf8088094d0359e5fd45d6aeaa8b2d0c3d",
+            },
+            Test {
+                input_len: 102400,
+                hash: "bc3e3d41a1146b069abffad3c0d44860cf664390afce4d9661f7902e7943e085",
+                keyed_hash: "1c35d1a5811083fd7119f5d5d1ba027b4d01c0c6c49fb6ff2cf75393ea5db4a7",
+                derive_key: "4652cff7a3f385a6103b5c260fc1593e13c778dbe608efb092fe7ee69df6e9c6",
+            },
+        ];
+
+        for test in tests {
+            let input: Vec<u8> = (0..test.input_len).map(|i| (i % 251) as u8).collect();
+
+            // Hash through high-level API, check hex encoding/decoding
+            let hash = blake3::Hash::hash(&input);
+            assert_eq!(hash, blake3::Hash::from_hex(test.hash).expect("parse hex"));
+            assert_eq!(&hash.to_hex(), &test.hash);
+
+            // Hash through engine, checking that we can input in odd pieces
+            let mut engine = blake3::Hash::engine();
+            for chunk in input.chunks(37) {
+                engine.write_all(chunk).expect("write to engine");
+            }
+            assert_eq!(blake3::Hash::from_engine(engine), hash);
+
+            let mut engine = blake3::HashEngine::new_keyed(key);
+            engine.input(&input);
+            assert_eq!(blake3::Hash::from_engine(engine).to_hex(), test.keyed_hash);
+
+            let mut engine = blake3::HashEngine::new_derive_key(context);
+            engine.input(&input);
+            assert_eq!(blake3::Hash::from_engine(engine).to_hex(), test.derive_key);
+        }
+    }
+
+    #[test]
+    fn abc() {
+        assert_eq!(
+            blake3::Hash::hash(b"abc").to_hex(),
+            "6437b3ac38465133ffb63b75273a8db548c558465d79db03fd359c6cd5bd9d85",
+        );
+    }
+
+    #[cfg(feature="serde")]
+    #[test]
+    fn blake3_serde() {
+        use serde_test::{Configure, Token, assert_tokens};
+
+        static HASH_BYTES: [u8; 32] = [
+            0x64, 0x37, 0xb3, 0xac, 0x38, 0x46, 0x51, 0x33,
+            0xff, 0xb6, 0x3b, 0x75, 0x27, 0x3a, 0x8d, 0xb5,
+            0x48, 0xc5, 0x58, 0x46, 0x5d, 0x79, 0xdb, 0x03,
+            0xfd, 0x35, 0x9c, 0x6c, 0xd5, 0xbd, 0x9d, 0x85,
+        ];
+
+        let hash = blake3::Hash::from_slice(&HASH_BYTES).expect("right number of bytes");
+        assert_tokens(&hash.compact(), &[Token::BorrowedBytes(&HASH_BYTES[..])]);
+        assert_tokens(&hash.readable(), &[Token::Str("6437b3ac38465133ffb63b75273a8db548c558465d79db03fd359c6cd5bd9d85")]);
+    }
+}
+
+#[cfg(all(test, feature="unstable"))]
+mod benches {
+    use std::io::Write;
+    use test::Bencher;
+
+    use blake3;
+    use Hash;
+
+    #[bench]
+    pub fn blake3_10(bh: & mut Bencher) {
+        let mut engine = blake3::Hash::engine();
+        let bytes = [1u8; 10];
+        bh.iter( || {
+            engine.write_all(&bytes).expect("write");
+        });
+        bh.bytes = bytes.len() as u64;
+    }
+
+    #[bench]
+    pub fn blake3_1k(bh: & mut Bencher) {
+        let mut engine = blake3::Hash::engine();
+        let bytes = [1u8; 1024];
+        bh.iter( || {
+            engine.write_all(&bytes).expect("write");
+        });
+        bh.bytes = bytes.len() as u64;
+    }
+
+    #[bench]
+    pub fn blake3_64k(bh: & mut Bencher) {
+        let mut engine = blake3::Hash::engine();
+        let bytes = [1u8; 65536];
+        bh.iter( || {
+            engine.write_all(&bytes).expect("write");
+        });
+        bh.bytes = bytes.len() as u64;
+    }
+}
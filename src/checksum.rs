@@ -0,0 +1,211 @@
+// Bitcoin Hashes Library
+// Written in 2019 by
+//   The rust-bitcoin developers
+//
+// To the extent possible under law, the author(s) have dedicated all
+// copyright and related and neighboring rights to this software to
+// the public domain worldwide. This software is distributed without
+// any warranty.
+//
+// You should have received a copy of the CC0 Public Domain Dedication
+// along with this software.
+// If not, see <http://creativecommons.org/publicdomain/zero/1.0/>.
+//
+
+//! # Checksums
+//!
+//! Non-cryptographic checksums for detecting accidental corruption. These
+//! are computed with the same engine API as hashes, but deliberately do not
+//! implement `Hash`: they are trivial to forge and must not be used where
+//! an adversary can choose the data.
+
+use core::fmt;
+
+use HashEngine as EngineTrait;
+
+/// A non-cryptographic checksum
+pub trait Checksum: Copy + Clone + PartialEq + Eq + Default +
+    fmt::Debug + fmt::Display + fmt::LowerHex + fmt::UpperHex
+{
+    /// Engine which data is input into
+    type Engine: EngineTrait;
+
+    /// The value that represents the checksum internally
+    type Inner;
+
+    /// Length of the checksum, in bytes
+    const LEN: usize;
+
+    /// Construct a new engine
+    fn engine() -> Self::Engine;
+
+    /// Produces a checksum from the current state of a given engine
+    fn from_engine(e: Self::Engine) -> Self;
+
+    /// Computes the checksum of some data
+    fn checksum(data: &[u8]) -> Self {
+        let mut engine = Self::engine();
+        engine.input(data);
+        Self::from_engine(engine)
+    }
+
+    /// Returns the underlying value of the checksum
+    fn to_inner(self) -> Self::Inner;
+}
+
+// CRC32 of each nibble, for the reflected polynomial 0xedb88320
+const CRC32_TABLE: [u32; 16] = [
+    0x00000000, 0x1db71064, 0x3b6e20c8, 0x26d930ac, 0x76dc4190, 0x6b6b51f4, 0x4db26158, 0x5005713c,
+    0xedb88320, 0xf00f9344, 0xd6d6a3e8, 0xcb61b38c, 0x9b64c2b0, 0x86d3d2d4, 0xa00ae278, 0xbdbdf21c,
+];
+
+/// Engine to compute a CRC32 checksum
+#[derive(Clone)]
+pub struct Crc32Engine {
+    crc: u32,
+}
+
+impl Default for Crc32Engine {
+    fn default() -> Self {
+        Crc32Engine {
+            crc: 0xffffffff,
+        }
+    }
+}
+
+impl EngineTrait for Crc32Engine {
+    type MidState = u32;
+
+    fn midstate(&self) -> u32 {
+        self.crc
+    }
+
+    const BLOCK_SIZE: usize = 1;
+
+    fn input(&mut self, data: &[u8]) {
+        for byte in data {
+            let mut crc = self.crc ^ u32::from(*byte);
+            crc = (crc >> 4) ^ CRC32_TABLE[(crc & 0xf) as usize];
+            crc = (crc >> 4) ^ CRC32_TABLE[(crc & 0xf) as usize];
+            self.crc = crc;
+        }
+    }
+}
+
+write_impl!(Crc32Engine);
+
+/// The CRC32 checksum used by zlib, gzip, PNG and Ethernet
+#[derive(Copy, Clone, PartialEq, Eq, Default, PartialOrd, Ord, Hash)]
+pub struct Crc32(u32);
+
+impl Crc32 {
+    /// Constructs a checksum from its numeric value
+    pub fn from_u32(crc: u32) -> Crc32 {
+        Crc32(crc)
+    }
+
+    /// Returns the numeric value of the checksum
+    pub fn to_u32(self) -> u32 {
+        self.0
+    }
+}
+
+impl Checksum for Crc32 {
+    type Engine = Crc32Engine;
+    type Inner = u32;
+
+    const LEN: usize = 4;
+
+    fn engine() -> Crc32Engine {
+        Default::default()
+    }
+
+    fn from_engine(e: Crc32Engine) -> Crc32 {
+        Crc32(!e.crc)
+    }
+
+    fn to_inner(self) -> u32 {
+        self.0
+    }
+}
+
+impl fmt::LowerHex for Crc32 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:08x}", self.0)
+    }
+}
+
+impl fmt::UpperHex for Crc32 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:08X}", self.0)
+    }
+}
+
+impl fmt::Display for Crc32 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::LowerHex::fmt(self, f)
+    }
+}
+
+impl fmt::Debug for Crc32 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::LowerHex::fmt(self, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use checksum::{Checksum, Crc32};
+
+    #[derive(Clone)]
+    struct Test {
+        input: Vec<u8>,
+        output: u32,
+        output_str: &'static str,
+    }
+
+    #[test]
+    fn crc32() {
+        let tests = vec![
+            // Test vectors computed with Python's `zlib.crc32`
+            Test {
+                input: vec![],
+                output: 0x00000000,
+                output_str: "00000000",
+            },
+            Test {
+                input: b"123456789".to_vec(),
+                output: 0xcbf43926,
+                output_str: "cbf43926",
+            },
+            Test {
+                input: b"The quick brown fox jumps over the lazy dog".to_vec(),
+                output: 0x414fa339,
+                output_str: "414fa339",
+            },
+            Test {
+                input: (0..=255).collect(),
+                output: 0x29058c73,
+                output_str: "29058c73",
+            },
+        ];
+
+        for test in tests {
+            let crc = Crc32::checksum(&test.input);
+            assert_eq!(crc, Crc32::from_u32(test.output));
+            assert_eq!(crc.to_u32(), test.output);
+            assert_eq!(crc.to_inner(), test.output);
+            assert_eq!(crc.to_string(), test.output_str);
+            assert_eq!(format!("{:X}", crc), test.output_str.to_uppercase());
+
+            // Input byte by byte through `io::Write`
+            let mut engine = Crc32::engine();
+            for ch in &test.input {
+                engine.write_all(&[*ch]).expect("write to engine");
+            }
+            assert_eq!(Crc32::from_engine(engine), crc);
+        }
+    }
+}
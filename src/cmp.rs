@@ -1,5 +1,55 @@
 //! Useful comparison functions.
 
+use core::{cmp, fmt};
+
+use Hash;
+
+/// Wrapper around a hash which orders hashes by their display order.
+///
+/// The `Ord` implementations of the hash types compare bytes in their
+/// internal order. For hashes which are displayed backward, such as
+/// `sha256d::Hash`, this differs from the order of their hex strings and of
+/// the little-endian numbers they encode, which is how Bitcoin compares
+/// block hashes against difficulty targets. `DisplayOrdered` compares the
+/// bytes from last to first for such hashes, and like the plain hash
+/// otherwise.
+#[derive(Copy, Clone, PartialEq, Eq, Default, Hash)]
+pub struct DisplayOrdered<H: Hash>(pub H);
+
+impl<H: Hash> PartialOrd for DisplayOrdered<H> {
+    fn partial_cmp(&self, other: &DisplayOrdered<H>) -> Option<cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<H: Hash> Ord for DisplayOrdered<H> {
+    fn cmp(&self, other: &DisplayOrdered<H>) -> cmp::Ordering {
+        if H::DISPLAY_BACKWARD {
+            self.0[..].iter().rev().cmp(other.0[..].iter().rev())
+        } else {
+            self.0.cmp(&other.0)
+        }
+    }
+}
+
+impl<H: Hash> From<H> for DisplayOrdered<H> {
+    fn from(hash: H) -> DisplayOrdered<H> {
+        DisplayOrdered(hash)
+    }
+}
+
+impl<H: Hash> fmt::Debug for DisplayOrdered<H> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&self.0, f)
+    }
+}
+
+impl<H: Hash> fmt::Display for DisplayOrdered<H> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
 /// Compare two slices for equality in fixed time. Panics if the slices are of non-equal length.
 ///
 /// This works by XOR'ing each byte of the two inputs together and keeping an OR counter of the
@@ -20,26 +70,26 @@ pub fn fixed_time_eq(a: &[u8], b: &[u8]) -> bool {
 
     let mut r: u8 = 0;
     for i in 0..count {
-        let mut rs = unsafe { ::std::ptr::read_volatile(&r) };
+        let mut rs = unsafe { ::core::ptr::read_volatile(&r) };
         rs |= lhs[i] ^ rhs[i];
-        unsafe { ::std::ptr::write_volatile(&mut r, rs); }
+        unsafe { ::core::ptr::write_volatile(&mut r, rs); }
     }
     {
-        let mut t = unsafe { ::std::ptr::read_volatile(&r) };
+        let mut t = unsafe { ::core::ptr::read_volatile(&r) };
         t |= t >> 4;
-        unsafe { ::std::ptr::write_volatile(&mut r, t); }
+        unsafe { ::core::ptr::write_volatile(&mut r, t); }
     }
     {
-        let mut t = unsafe { ::std::ptr::read_volatile(&r) };
+        let mut t = unsafe { ::core::ptr::read_volatile(&r) };
         t |= t >> 2;
-        unsafe { ::std::ptr::write_volatile(&mut r, t); }
+        unsafe { ::core::ptr::write_volatile(&mut r, t); }
     }
     {
-        let mut t = unsafe { ::std::ptr::read_volatile(&r) };
+        let mut t = unsafe { ::core::ptr::read_volatile(&r) };
         t |= t >> 1;
-        unsafe { ::std::ptr::write_volatile(&mut r, t); }
+        unsafe { ::core::ptr::write_volatile(&mut r, t); }
     }
-    unsafe { (::std::ptr::read_volatile(&r) & 1) == 0 }
+    unsafe { (::core::ptr::read_volatile(&r) & 1) == 0 }
 }
 
 #[test]
@@ -79,6 +129,29 @@ fn eq_test() {
     assert!(!fixed_time_eq(&[0b00000000, 0b00000000], &[0b00000001, 0b00000001]));
 }
 
+#[test]
+fn display_ordered_test() {
+    use hex::FromHex;
+    use {sha256, sha256d};
+
+    // Internally these differ in their first byte, when displayed in their last
+    let a = sha256d::Hash::from_hex("0000000000000000000000000000000000000000000000000000000000000102").unwrap();
+    let b = sha256d::Hash::from_hex("0100000000000000000000000000000000000000000000000000000000000001").unwrap();
+    assert!(a > b);
+    assert!(DisplayOrdered(a) < DisplayOrdered(b));
+    assert_eq!(a.to_string() < b.to_string(), DisplayOrdered(a) < DisplayOrdered(b));
+
+    let mut hashes = vec![DisplayOrdered(b), DisplayOrdered(a)];
+    hashes.sort();
+    assert_eq!(hashes, vec![DisplayOrdered(a), DisplayOrdered(b)]);
+
+    // Forward-displayed hashes keep their usual order
+    let a = sha256::Hash::from_hex("0000000000000000000000000000000000000000000000000000000000000102").unwrap();
+    let b = sha256::Hash::from_hex("0100000000000000000000000000000000000000000000000000000000000001").unwrap();
+    assert!(a < b);
+    assert!(DisplayOrdered(a) < DisplayOrdered(b));
+}
+
 #[cfg(all(test, feature="unstable"))]
 mod benches {
     use test::Bencher;
@@ -0,0 +1,72 @@
+// Bitcoin Hashes Library
+// Written in 2019 by
+//   The rust-bitcoin developers
+//
+// To the extent possible under law, the author(s) have dedicated all
+// copyright and related and neighboring rights to this software to
+// the public domain worldwide. This software is distributed without
+// any warranty.
+//
+// You should have received a copy of the CC0 Public Domain Dedication
+// along with this software.
+// If not, see <http://creativecommons.org/publicdomain/zero/1.0/>.
+//
+
+//! # Compatibility
+//!
+//! Helpers for converting between hash types which share a representation,
+//! such as the newtypes downstream crates define for txids and block hashes.
+//!
+//! Two versions of this library in one build define distinct types, so
+//! neither these helpers nor `From` impls can convert a hash of one version
+//! into the other. Every version can however convert hashes to and from
+//! their byte arrays, in their internal order, and going through these is
+//! the way to bridge a version mismatch:
+//!
+//! ```text
+//! let txid = new::sha256d::Hash::from_byte_array(old_txid.to_byte_array());
+//! ```
+//!
+//! Methods which are renamed stay available for at least one release as
+//! `#[deprecated]` shims, such as `Hash::into_inner`, so that the warnings
+//! point out what to migrate before the old names are removed.
+
+use Hash;
+
+/// Conversion between hash types with the same byte array, without hashing
+pub trait HashCast: Hash {
+    /// Reinterprets the hash as another hash type with the same byte array,
+    /// keeping the bytes in their internal order. If the two types differ
+    /// in `DISPLAY_BACKWARD`, the hex encoding therefore changes.
+    fn cast<H: Hash<Inner = Self::Inner>>(self) -> H {
+        H::from_byte_array(self.to_byte_array())
+    }
+}
+
+impl<T: Hash> HashCast for T {}
+
+#[cfg(test)]
+mod tests {
+    use compat::HashCast;
+    use hex::ToHex;
+    use {sha256, sha256d, Hash};
+
+    impl_hash_newtype!(
+        /// A transaction id
+        Txid, sha256d::Hash
+    );
+
+    #[test]
+    fn cast() {
+        let hash = sha256d::Hash::hash(b"abc");
+        let txid: Txid = hash.cast();
+        assert_eq!(txid.0, hash);
+        assert_eq!(txid.cast::<sha256d::Hash>(), hash);
+
+        // Internal order is kept, so the display order changes
+        let plain: sha256::Hash = hash.cast();
+        assert_eq!(&plain[..], &hash[..]);
+        assert_eq!(plain.to_hex(), hash[..].to_hex());
+        assert!(plain.to_hex() != hash.to_hex());
+    }
+}
@@ -0,0 +1,122 @@
+// Bitcoin Hashes Library
+// Written in 2019 by
+//   The rust-bitcoin developers
+//
+// To the extent possible under law, the author(s) have dedicated all
+// copyright and related and neighboring rights to this software to
+// the public domain worldwide. This software is distributed without
+// any warranty.
+//
+// You should have received a copy of the CC0 Public Domain Dedication
+// along with this software.
+// If not, see <http://creativecommons.org/publicdomain/zero/1.0/>.
+//
+
+//! # Dynamic hashing
+//!
+//! `Hash` and `HashEngine` cannot be used as trait objects, so the hash
+//! function has to be known at compile time. `DynHashEngine` is an
+//! object-safe engine interface for when the hash function is only chosen
+//! at runtime.
+
+use std::{io, marker};
+
+use {Hash, HashEngine};
+
+/// An object-safe hash engine, which outputs the hash as bytes
+pub trait DynHashEngine: io::Write {
+    /// Add data to the engine
+    fn input(&mut self, data: &[u8]);
+
+    /// Length of the hash this engine produces, in bytes
+    fn hash_len(&self) -> usize;
+
+    /// Finalizes the engine, returning the bytes of the hash in their
+    /// internal order
+    fn finalize(self: Box<Self>) -> Vec<u8>;
+}
+
+/// A hash engine for the hash function `H` behind the `DynHashEngine` interface
+pub struct HashEngineBox<H: Hash> {
+    engine: H::Engine,
+    _hash: marker::PhantomData<H>,
+}
+
+impl<H: Hash> HashEngineBox<H> {
+    /// Constructs a new engine for the hash function `H`
+    pub fn new() -> HashEngineBox<H> {
+        HashEngineBox {
+            engine: H::engine(),
+            _hash: marker::PhantomData,
+        }
+    }
+}
+
+impl<H: Hash + 'static> HashEngineBox<H> {
+    /// Constructs a new engine for the hash function `H`, boxed as a trait object
+    pub fn boxed() -> Box<dyn DynHashEngine> {
+        Box::new(HashEngineBox::<H>::new())
+    }
+}
+
+impl<H: Hash> Default for HashEngineBox<H> {
+    fn default() -> HashEngineBox<H> {
+        HashEngineBox::new()
+    }
+}
+
+impl<H: Hash> DynHashEngine for HashEngineBox<H> {
+    fn input(&mut self, data: &[u8]) {
+        self.engine.input(data)
+    }
+
+    fn hash_len(&self) -> usize {
+        H::LEN
+    }
+
+    fn finalize(self: Box<Self>) -> Vec<u8> {
+        H::from_engine(self.engine)[..].to_vec()
+    }
+}
+
+impl<H: Hash> io::Write for HashEngineBox<H> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.engine.input(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use dynamic::{DynHashEngine, HashEngineBox};
+    use {ripemd160, sha256, sha256d, sha512, Hash, Hmac};
+
+    #[test]
+    fn dyn_engine() {
+        let data = b"The quick brown fox jumps over the lazy dog";
+
+        let mut engines: Vec<(Box<dyn DynHashEngine>, Vec<u8>)> = vec![
+            (HashEngineBox::<sha256::Hash>::boxed(), sha256::Hash::hash(data)[..].to_vec()),
+            (HashEngineBox::<sha256d::Hash>::boxed(), sha256d::Hash::hash(data)[..].to_vec()),
+            (HashEngineBox::<sha512::Hash>::boxed(), sha512::Hash::hash(data)[..].to_vec()),
+            (HashEngineBox::<ripemd160::Hash>::boxed(), ripemd160::Hash::hash(data)[..].to_vec()),
+            (HashEngineBox::<Hmac<sha256::Hash>>::boxed(), Hmac::<sha256::Hash>::hash(data)[..].to_vec()),
+        ];
+
+        for (mut engine, expected) in engines.drain(..) {
+            assert_eq!(engine.hash_len(), expected.len());
+            engine.input(&data[..10]);
+            engine.write_all(&data[10..]).expect("write to engine");
+            assert_eq!(engine.finalize(), expected);
+        }
+
+        let engine = HashEngineBox::<sha256::Hash>::new();
+        assert_eq!(Box::new(engine).finalize(), &sha256::Hash::hash(&[])[..]);
+    }
+}
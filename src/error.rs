@@ -15,46 +15,201 @@
 //! # Error Type
 //!
 
-use std::{error, fmt};
+use core::fmt;
+#[cfg(feature = "std")]
+use std::{error, io};
+
+#[cfg(all(feature = "alloc", not(feature = "std")))]
+use prelude::String;
 
 /// Hex decoding error
-#[derive(Copy, Clone, PartialEq, Eq)]
+#[derive(Clone, PartialEq, Eq)]
 pub enum Error {
     /// non-hexadecimal character
+    #[deprecated(note = "no longer returned; hex decoding gives `InvalidHexChar` with the position")]
     InvalidChar(char),
+    /// non-hexadecimal character, at the given byte offset into the string
+    InvalidHexChar {
+        /// the invalid character
+        char: char,
+        /// byte offset of the character into the decoded string
+        position: usize,
+    },
     /// purported hex string had odd length
     OddLengthString(usize),
     /// tried to parse fixed-length hash from a string with the wrong type (expected, got)
     InvalidLength(usize, usize),
+    /// data did not hash to the expected value
+    HashMismatch,
+    /// an I/O error, which only the `std` feature produces
+    Io(IoError),
 }
 
-impl fmt::Debug for Error {
+/// The message of an I/O error, which is kept as a string since `io::Error`
+/// is not `Clone`. The `Error::Io` variant holds one whatever the features
+/// of the crate, so matching on `Error` does not depend on them.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct IoError {
+    #[cfg(feature = "alloc")]
+    message: String,
+}
+
+impl IoError {
+    /// Returns the message of the I/O error
+    #[cfg(feature = "alloc")]
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for IoError {
+    #[cfg(feature = "alloc")]
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("unknown")
+    }
+}
+
+impl Error {
+    /// Whether the error is due to the input having the wrong length, rather
+    /// than to its content
+    #[allow(deprecated)]
+    pub fn is_length_error(&self) -> bool {
         match *self {
-            Error::InvalidChar(ch) => write!(f, "invalid hex character {}", ch),
-            Error::OddLengthString(ell) => write!(f, "odd hex string length {}", ell),
-            Error::InvalidLength(ell, ell2) => write!(f, "bad hex string length {} (expected {})", ell2, ell),
+            Error::InvalidChar(_) | Error::InvalidHexChar { .. } => false,
+            Error::HashMismatch => false,
+            Error::Io(_) => false,
+            Error::OddLengthString(_) | Error::InvalidLength(_, _) => true,
+        }
+    }
+
+    /// Shifts the position of an invalid character by `offset`, for errors
+    /// from decoding a suffix of the string
+    pub(crate) fn shift_position(self, offset: usize) -> Error {
+        match self {
+            Error::InvalidHexChar { char, position } => Error::InvalidHexChar {
+                char,
+                position: position + offset,
+            },
+            e => e,
         }
     }
 }
 
 impl fmt::Display for Error {
+    #[allow(deprecated)]
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        fmt::Debug::fmt(self, f)
+        match *self {
+            // Characters are quoted so that whitespace and control
+            // characters are visible
+            Error::InvalidChar(ch) => write!(f, "invalid hex character {:?}", ch),
+            Error::InvalidHexChar { char, position } => {
+                write!(f, "invalid hex character {:?} at position {}", char, position)
+            }
+            Error::OddLengthString(ell) => write!(f, "odd hex string length {}", ell),
+            Error::InvalidLength(ell, ell2) => write!(f, "invalid length: expected {}, got {}", ell, ell2),
+            Error::HashMismatch => f.write_str("hash mismatch"),
+            Error::Io(ref e) => write!(f, "I/O error: {}", e),
+        }
     }
 }
 
-impl error::Error for Error {
-    fn cause(&self) -> Option<&error::Error> {
-        None
+impl fmt::Debug for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self, f)
     }
+}
 
+#[cfg(feature = "std")]
+impl error::Error for Error {
+    #[allow(deprecated)]
     fn description(&self) -> &str {
         match *self {
-            Error::InvalidChar(_) => "invalid hex character",
+            Error::InvalidChar(_) | Error::InvalidHexChar { .. } => "invalid hex character",
             Error::OddLengthString(_) => "odd hex string length",
-            Error::InvalidLength(_, _) => "bad hex string length",
+            Error::InvalidLength(_, _) => "invalid length",
+            Error::HashMismatch => "hash mismatch",
+            Error::Io(_) => "I/O error",
         }
     }
 }
 
+#[cfg(feature = "std")]
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Error {
+        Error::Io(IoError { message: e.to_string() })
+    }
+}
+
+/// I/O errors are converted back into I/O errors of their original message,
+/// all other errors into errors of kind `InvalidData`
+#[cfg(feature = "std")]
+impl From<Error> for io::Error {
+    // `io::Error::other` is too recent for our minimum supported rustc
+    #[allow(clippy::io_other_error)]
+    fn from(e: Error) -> io::Error {
+        match e {
+            Error::Io(e) => io::Error::new(io::ErrorKind::Other, e.message),
+            e => io::Error::new(io::ErrorKind::InvalidData, e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error;
+
+    use Error;
+
+    #[test]
+    fn error() {
+        fn is_std_error<E: error::Error + Send + Sync + 'static>(_: &E) {}
+        is_std_error(&Error::InvalidHexChar { char: 'x', position: 1 });
+
+        assert_eq!(
+            Error::InvalidHexChar { char: 'x', position: 1 }.to_string(),
+            "invalid hex character 'x' at position 1",
+        );
+        assert_eq!(
+            Error::InvalidHexChar { char: '\n', position: 64 }.to_string(),
+            "invalid hex character '\\n' at position 64",
+        );
+        #[allow(deprecated)]
+        let legacy = Error::InvalidChar(' ');
+        assert_eq!(legacy.to_string(), "invalid hex character ' '");
+        assert_eq!(Error::OddLengthString(3).to_string(), "odd hex string length 3");
+        assert_eq!(Error::InvalidLength(32, 31).to_string(), "invalid length: expected 32, got 31");
+        assert!(error::Error::source(&Error::InvalidLength(32, 31)).is_none());
+
+        assert!(!Error::InvalidHexChar { char: 'x', position: 1 }.is_length_error());
+        assert!(Error::OddLengthString(3).is_length_error());
+        assert!(Error::InvalidLength(32, 31).is_length_error());
+        assert_eq!(Error::HashMismatch.to_string(), "hash mismatch");
+        assert!(!Error::HashMismatch.is_length_error());
+    }
+
+    #[test]
+    fn io_error() {
+        use std::io;
+
+        fn parse(s: &str) -> io::Result<Vec<u8>> {
+            Ok(::hex::FromHex::from_hex(s)?)
+        }
+        let err = parse("0").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(err.to_string(), "odd hex string length 1");
+
+        let err = Error::from(io::Error::new(io::ErrorKind::UnexpectedEof, "eof"));
+        match err {
+            Error::Io(ref e) => assert_eq!(e.message(), "eof"),
+            _ => panic!("not an I/O error: {}", err),
+        }
+        assert_eq!(err.to_string(), "I/O error: eof");
+        assert!(!err.is_length_error());
+        assert_eq!(io::Error::from(err).to_string(), "eof");
+    }
+}
@@ -0,0 +1,69 @@
+// Bitcoin Hashes Library
+// Written in 2019 by
+//   The rust-bitcoin developers
+//
+// To the extent possible under law, the author(s) have dedicated all
+// copyright and related and neighboring rights to this software to
+// the public domain worldwide. This software is distributed without
+// any warranty.
+//
+// You should have received a copy of the CC0 Public Domain Dedication
+// along with this software.
+// If not, see <http://creativecommons.org/publicdomain/zero/1.0/>.
+//
+
+//! # Extension Traits
+//!
+//! Methods for hashing byte containers directly, which are only available
+//! once the trait is imported.
+//!
+//! ```rust
+//! use bitcoin_hashes::{sha256, Hash, HashEngine};
+//! use bitcoin_hashes::ext::HashableExt;
+//!
+//! let data = vec![1u8, 2, 3];
+//! assert_eq!(data.hash_as::<sha256::Hash>(), sha256::Hash::hash(&data));
+//!
+//! let mut engine = sha256::Hash::engine();
+//! b"prefix".hash_with_engine(&mut engine);
+//! data.hash_with_engine(&mut engine);
+//! ```
+
+use {Hash, HashEngine};
+
+/// Hashing of anything which can be viewed as bytes
+///
+/// Hashes are bytes too, so a method named `hash` would make calls such as
+/// `sha256::Hash::hash(..)` ambiguous wherever this trait is imported.
+pub trait HashableExt: AsRef<[u8]> {
+    /// Hashes the bytes with the hash function `H`
+    fn hash_as<H: Hash>(&self) -> H {
+        <H as Hash>::hash(self.as_ref())
+    }
+
+    /// Inputs the bytes into an engine, as part of a larger message
+    fn hash_with_engine<E: HashEngine>(&self, engine: &mut E) {
+        engine.input(self.as_ref())
+    }
+}
+
+impl<T: AsRef<[u8]> + ?Sized> HashableExt for T {}
+
+#[cfg(test)]
+mod tests {
+    use ext::HashableExt;
+    use {ripemd160, sha256, sha256d, Hash};
+
+    #[test]
+    fn hashable_ext() {
+        let data = b"hello world".to_vec();
+        assert_eq!(data.hash_as::<sha256::Hash>(), sha256::Hash::hash(b"hello world"));
+        assert_eq!("hello world".hash_as::<ripemd160::Hash>(), ripemd160::Hash::hash(b"hello world"));
+        assert_eq!(data[..5].hash_as::<sha256d::Hash>(), sha256d::Hash::hash(b"hello"));
+
+        let mut engine = sha256::Hash::engine();
+        "hello".hash_with_engine(&mut engine);
+        data[5..].hash_with_engine(&mut engine);
+        assert_eq!(sha256::Hash::from_engine(engine), sha256::Hash::hash(b"hello world"));
+    }
+}
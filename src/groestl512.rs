@@ -0,0 +1,418 @@
+// Bitcoin Hashes Library
+// Written in 2019 by
+//   The rust-bitcoin developers
+//
+// To the extent possible under law, the author(s) have dedicated all
+// copyright and related and neighboring rights to this software to
+// the public domain worldwide. This software is distributed without
+// any warranty.
+//
+// You should have received a copy of the CC0 Public Domain Dedication
+// along with this software.
+// If not, see <http://creativecommons.org/publicdomain/zero/1.0/>.
+//
+
+//! # Groestl-512
+//!
+//! Groestl-512 as specified in the final round of the SHA-3 competition,
+//! with the full 64-byte digest. Groestlcoin hashes block headers with two
+//! rounds of it, truncated to their first 32 bytes.
+
+use core::hash;
+
+use byteorder::{ByteOrder, BigEndian};
+
+use HashEngine as EngineTrait;
+use Hash as HashTrait;
+use Error;
+
+const BLOCK_SIZE: usize = 128;
+
+/// Number of rounds of each of the permutations P and Q
+const ROUNDS: u8 = 14;
+
+/// Left rotations of the rows of the state in P and Q
+const SHIFT_P: [usize; 8] = [0, 1, 2, 3, 4, 5, 6, 11];
+const SHIFT_Q: [usize; 8] = [1, 3, 5, 11, 0, 2, 4, 6];
+
+/// The first row of the circulant matrix used by MixBytes
+const MIX: [u8; 8] = [2, 2, 3, 4, 5, 3, 5, 7];
+
+/// The AES S-box
+const SBOX: [u8; 256] = [
+    0x63, 0x7c, 0x77, 0x7b, 0xf2, 0x6b, 0x6f, 0xc5, 0x30, 0x01, 0x67, 0x2b, 0xfe, 0xd7, 0xab, 0x76,
+    0xca, 0x82, 0xc9, 0x7d, 0xfa, 0x59, 0x47, 0xf0, 0xad, 0xd4, 0xa2, 0xaf, 0x9c, 0xa4, 0x72, 0xc0,
+    0xb7, 0xfd, 0x93, 0x26, 0x36, 0x3f, 0xf7, 0xcc, 0x34, 0xa5, 0xe5, 0xf1, 0x71, 0xd8, 0x31, 0x15,
+    0x04, 0xc7, 0x23, 0xc3, 0x18, 0x96, 0x05, 0x9a, 0x07, 0x12, 0x80, 0xe2, 0xeb, 0x27, 0xb2, 0x75,
+    0x09, 0x83, 0x2c, 0x1a, 0x1b, 0x6e, 0x5a, 0xa0, 0x52, 0x3b, 0xd6, 0xb3, 0x29, 0xe3, 0x2f, 0x84,
+    0x53, 0xd1, 0x00, 0xed, 0x20, 0xfc, 0xb1, 0x5b, 0x6a, 0xcb, 0xbe, 0x39, 0x4a, 0x4c, 0x58, 0xcf,
+    0xd0, 0xef, 0xaa, 0xfb, 0x43, 0x4d, 0x33, 0x85, 0x45, 0xf9, 0x02, 0x7f, 0x50, 0x3c, 0x9f, 0xa8,
+    0x51, 0xa3, 0x40, 0x8f, 0x92, 0x9d, 0x38, 0xf5, 0xbc, 0xb6, 0xda, 0x21, 0x10, 0xff, 0xf3, 0xd2,
+    0xcd, 0x0c, 0x13, 0xec, 0x5f, 0x97, 0x44, 0x17, 0xc4, 0xa7, 0x7e, 0x3d, 0x64, 0x5d, 0x19, 0x73,
+    0x60, 0x81, 0x4f, 0xdc, 0x22, 0x2a, 0x90, 0x88, 0x46, 0xee, 0xb8, 0x14, 0xde, 0x5e, 0x0b, 0xdb,
+    0xe0, 0x32, 0x3a, 0x0a, 0x49, 0x06, 0x24, 0x5c, 0xc2, 0xd3, 0xac, 0x62, 0x91, 0x95, 0xe4, 0x79,
+    0xe7, 0xc8, 0x37, 0x6d, 0x8d, 0xd5, 0x4e, 0xa9, 0x6c, 0x56, 0xf4, 0xea, 0x65, 0x7a, 0xae, 0x08,
+    0xba, 0x78, 0x25, 0x2e, 0x1c, 0xa6, 0xb4, 0xc6, 0xe8, 0xdd, 0x74, 0x1f, 0x4b, 0xbd, 0x8b, 0x8a,
+    0x70, 0x3e, 0xb5, 0x66, 0x48, 0x03, 0xf6, 0x0e, 0x61, 0x35, 0x57, 0xb9, 0x86, 0xc1, 0x1d, 0x9e,
+    0xe1, 0xf8, 0x98, 0x11, 0x69, 0xd9, 0x8e, 0x94, 0x9b, 0x1e, 0x87, 0xe9, 0xce, 0x55, 0x28, 0xdf,
+    0x8c, 0xa1, 0x89, 0x0d, 0xbf, 0xe6, 0x42, 0x68, 0x41, 0x99, 0x2d, 0x0f, 0xb0, 0x54, 0xbb, 0x16,
+];
+
+/// Engine to compute Groestl-512 hash function
+pub struct HashEngine {
+    buffer: [u8; BLOCK_SIZE],
+    h: [u8; BLOCK_SIZE],
+    length: usize,
+}
+
+write_impl!(HashEngine);
+extend_impl!(HashEngine, Hash);
+engine_default_impl!(HashEngine, Hash);
+zeroize_engine_impl!(HashEngine);
+
+impl Clone for HashEngine {
+    fn clone(&self) -> HashEngine {
+        HashEngine {
+            h: self.h,
+            length: self.length,
+            buffer: self.buffer,
+        }
+    }
+}
+
+impl EngineTrait for HashEngine {
+    type MidState = [u8; 128];
+
+    fn midstate(&self) -> [u8; 128] {
+        self.h
+    }
+
+    const BLOCK_SIZE: usize = 128;
+
+    engine_input_impl!();
+}
+
+/// Output of the Groestl-512 hash function
+pub struct Hash([u8; 64]);
+
+impl Copy for Hash {}
+
+impl Clone for Hash {
+    fn clone(&self) -> Hash {
+        *self
+    }
+}
+
+impl PartialEq for Hash {
+    fn eq(&self, other: &Hash) -> bool {
+        self.0[..] == other.0[..]
+    }
+}
+
+impl Eq for Hash {}
+
+impl Default for Hash {
+    fn default() -> Hash {
+        Hash([0; 64])
+    }
+}
+
+use core::cmp::Ordering;
+
+impl PartialOrd for Hash {
+    fn partial_cmp(&self, other: &Hash) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Hash {
+    fn cmp(&self, other: &Hash) -> Ordering {
+        self.0[..].cmp(&other.0[..])
+    }
+}
+
+impl hash::Hash for Hash {
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        self.0[..].hash(state)
+    }
+}
+
+hex_fmt_impl!(Debug, Hash);
+hex_fmt_impl!(Display, Hash);
+hex_fmt_impl!(LowerHex, Hash);
+hex_fmt_impl!(UpperHex, Hash);
+index_impl!(Hash);
+serde_impl!(Hash, 64);
+borrow_slice_impl!(Hash);
+from_str_impl!(Hash);
+try_from_impl!(Hash);
+from_bytes_impl!(Hash, 64);
+into_iter_impl!(Hash);
+eq_bytes_impl!(Hash, 64);
+deref_impl!(Hash, 64);
+inner_ref_impl!(Hash, 64);
+bitops_impl!(Hash);
+ct_eq_impl!(Hash);
+zeroize_impl!(Hash);
+rand_impl!(Hash);
+proptest_impl!(Hash);
+quickcheck_impl!(Hash);
+
+impl HashTrait for Hash {
+    type Engine = HashEngine;
+    type Inner = [u8; 64];
+
+    fn engine() -> HashEngine {
+        // The initial value encodes the output length in bits
+        let mut h = [0; BLOCK_SIZE];
+        BigEndian::write_u16(&mut h[BLOCK_SIZE - 2..], 512);
+        HashEngine {
+            h,
+            length: 0,
+            buffer: [0; BLOCK_SIZE],
+        }
+    }
+
+    fn from_engine(mut e: HashEngine) -> Hash {
+        // Pad with a single 1-bit then 0s, leaving 8 bytes for the total
+        // number of blocks, padding included
+        let blocks = if e.length % BLOCK_SIZE < BLOCK_SIZE - 8 {
+            e.length / BLOCK_SIZE + 1
+        } else {
+            e.length / BLOCK_SIZE + 2
+        };
+
+        let zeroes = [0; BLOCK_SIZE - 8];
+        e.input(&[0x80]);
+        if e.length % BLOCK_SIZE > zeroes.len() {
+            e.input(&zeroes);
+        }
+        let pad_length = zeroes.len() - (e.length % BLOCK_SIZE);
+        e.input(&zeroes[..pad_length]);
+        debug_assert_eq!(e.length % BLOCK_SIZE, zeroes.len());
+
+        let mut count = [0; 8];
+        BigEndian::write_u64(&mut count, blocks as u64);
+        e.input(&count);
+        debug_assert_eq!(e.length % BLOCK_SIZE, 0);
+
+        // Output transformation: the last half of `P(h) ^ h`
+        let mut x = e.h;
+        permute(&mut x, false);
+        let mut ret = [0; 64];
+        for (out, (x, h)) in ret.iter_mut().zip(x[64..].iter().zip(e.h[64..].iter())) {
+            *out = x ^ h;
+        }
+        Hash(ret)
+    }
+
+    const LEN: usize = 64;
+
+    fn from_slice(sl: &[u8]) -> Result<Hash, Error> {
+        if sl.len() != 64 {
+            Err(Error::InvalidLength(Self::LEN, sl.len()))
+        } else {
+            let mut ret = [0; 64];
+            ret.copy_from_slice(sl);
+            Ok(Hash(ret))
+        }
+    }
+
+    fn from_byte_array(bytes: Self::Inner) -> Self {
+        Hash(bytes)
+    }
+
+    fn to_byte_array(self) -> Self::Inner {
+        self.0
+    }
+}
+
+/// Multiplies by `x` in the AES field
+fn mul2(x: u8) -> u8 {
+    (x << 1) ^ if x & 0x80 != 0 { 0x1b } else { 0 }
+}
+
+fn mul(x: u8, by: u8) -> u8 {
+    match by {
+        2 => mul2(x),
+        3 => mul2(x) ^ x,
+        4 => mul2(mul2(x)),
+        5 => mul2(mul2(x)) ^ x,
+        7 => mul2(mul2(x)) ^ mul2(x) ^ x,
+        _ => unreachable!(),
+    }
+}
+
+/// Applies the permutation P, or Q if `q` is set, to a state whose bytes
+/// are stored column by column, so that byte `8 * col + row` is at `(row, col)`
+fn permute(s: &mut [u8; BLOCK_SIZE], q: bool) {
+    let shift = if q { &SHIFT_Q } else { &SHIFT_P };
+    for round in 0..ROUNDS {
+        // AddRoundConstant
+        for col in 0..16 {
+            let c = (col << 4) as u8 ^ round;
+            if q {
+                for b in s[8 * col..8 * col + 7].iter_mut() {
+                    *b ^= 0xff;
+                }
+                s[8 * col + 7] ^= 0xff ^ c;
+            } else {
+                s[8 * col] ^= c;
+            }
+        }
+
+        // SubBytes and ShiftBytes
+        let mut t = [0; BLOCK_SIZE];
+        for col in 0..16 {
+            for row in 0..8 {
+                t[8 * col + row] = SBOX[s[8 * ((col + shift[row]) % 16) + row] as usize];
+            }
+        }
+
+        // MixBytes
+        for col in 0..16 {
+            let a = &t[8 * col..8 * col + 8];
+            for row in 0..8 {
+                let mut b = 0;
+                for (k, &x) in a.iter().enumerate() {
+                    b ^= mul(x, MIX[(k + 8 - row) % 8]);
+                }
+                s[8 * col + row] = b;
+            }
+        }
+    }
+}
+
+impl HashEngine {
+    // Compression function f(h, m) = P(h ^ m) ^ Q(m) ^ h
+    fn process_block(&mut self) {
+        let mut p = [0; BLOCK_SIZE];
+        for (p, (h, m)) in p.iter_mut().zip(self.h.iter().zip(self.buffer.iter())) {
+            *p = h ^ m;
+        }
+        let mut q = self.buffer;
+        permute(&mut p, false);
+        permute(&mut q, true);
+        for (h, (p, q)) in self.h.iter_mut().zip(p.iter().zip(q.iter())) {
+            *h ^= p ^ q;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use groestl512;
+    use hex::{FromHex, ToHex};
+    use Hash;
+
+    #[test]
+    fn test() {
+        // Test vectors computed with the `groestl` crate, whose empty-input
+        // digest matches the one given with the specification
+        let tests: Vec<(Vec<u8>, &str)> = vec![
+            (
+                vec![],
+                "6d3ad29d279110eef3adbd66de2a0345a77baede1557f5d099fce0c03d6dc2ba\
+                 8e6d4a6633dfbd66053c20faa87d1a11f39a7fbe4a6c2f009801370308fc4ad8",
+            ),
+            (
+                b"abc".to_vec(),
+                "70e1c68c60df3b655339d67dc291cc3f1dde4ef343f11b23fdd44957693815a7\
+                 5a8339c682fc28322513fd1f283c18e53cff2b264e06bf83a2f0ac8c1f6fbff6",
+            ),
+            (
+                b"The quick brown fox jumps over the lazy dog".to_vec(),
+                "badc1f70ccd69e0cf3760c3f93884289da84ec13c70b3d12a53a7a8a4a513f99\
+                 715d46288f55e1dbf926e6d084a0538e4eebfc91cf2b21452921ccde9131718d",
+            ),
+            // The longest input whose padding fits in one block, and the
+            // shortest which needs a second one
+            (
+                vec![0x61; 119],
+                "05379d6eb1dec550d6c97258fece314a3a5230bafc6a780ae0e55273cd2889c5\
+                 6196d3279654fabb7f755c4a48bf7c5dad8455c952bd161058e48706551e35e0",
+            ),
+            (
+                vec![0x61; 120],
+                "6e90753dd04f2bdab81666f8ca12205e41cc0ff154f10b34517212bc37d2e852\
+                 a86c583b0685c1081f0a7f743b81b8e00f52e2def11fea21e3c7dc7f76a1bcfb",
+            ),
+            (
+                vec![0x61; 128],
+                "67aaf4835a3bfac29dc0413172af0f73d5c452c4ab7318535c6b4c4fa9fb87d4\
+                 a5aaeba1a39ff272b795d6e3f72ecd8d3537e2b94a1682ac7c485ed324de8036",
+            ),
+            (
+                (0..1000u32).map(|i| i as u8).collect(),
+                "d704f639672eb1dd6bbe7af6ac2945ec063e8490141a14bcaf89c6b8f53d6028\
+                 7a983594a513dda687e5be7b909790c9dd5ba70a498c7af8daff387be509402d",
+            ),
+        ];
+
+        for (input, output_str) in tests {
+            // Hash through high-level API, check hex encoding/decoding
+            let hash = groestl512::Hash::hash(&input);
+            assert_eq!(hash, groestl512::Hash::from_hex(output_str).expect("parse hex"));
+            assert_eq!(hash.to_hex(), output_str);
+
+            // Hash through engine, checking that we can input byte by byte
+            let mut engine = groestl512::Hash::engine();
+            for ch in &input {
+                engine.write_all(&[*ch]).expect("write to engine");
+            }
+            assert_eq!(groestl512::Hash::from_engine(engine), hash);
+        }
+    }
+
+    #[test]
+    fn groestlcoin_double() {
+        // Two rounds, as Groestlcoin hashes its block headers
+        let once = groestl512::Hash::hash(b"abc");
+        assert_eq!(
+            groestl512::Hash::hash(&once[..]).to_hex(),
+            "453d26f15b76bd4a4b2c3d21f8271404e2ffd9aad994f4d36a376d40a757af09\
+             537712244da6eef03744779f96d2221cfc9df1073a56fc92b162d5fb9baafe15",
+        );
+    }
+}
+
+#[cfg(all(test, feature="unstable"))]
+mod benches {
+    use std::io::Write;
+    use test::Bencher;
+
+    use groestl512;
+    use Hash;
+
+    #[bench]
+    pub fn groestl512_10(bh: & mut Bencher) {
+        let mut engine = groestl512::Hash::engine();
+        let bytes = [1u8; 10];
+        bh.iter( || {
+            engine.write_all(&bytes).expect("write");
+        });
+        bh.bytes = bytes.len() as u64;
+    }
+
+    #[bench]
+    pub fn groestl512_1k(bh: & mut Bencher) {
+        let mut engine = groestl512::Hash::engine();
+        let bytes = [1u8; 1024];
+        bh.iter( || {
+            engine.write_all(&bytes).expect("write");
+        });
+        bh.bytes = bytes.len() as u64;
+    }
+
+    #[bench]
+    pub fn groestl512_64k(bh: & mut Bencher) {
+        let mut engine = groestl512::Hash::engine();
+        let bytes = [1u8; 65536];
+        bh.iter( || {
+            engine.write_all(&bytes).expect("write");
+        });
+        bh.bytes = bytes.len() as u64;
+    }
+}
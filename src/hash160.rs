@@ -31,9 +31,22 @@ pub struct Hash([u8; 20]);
 hex_fmt_impl!(Debug, Hash);
 hex_fmt_impl!(Display, Hash);
 hex_fmt_impl!(LowerHex, Hash);
+hex_fmt_impl!(UpperHex, Hash);
 index_impl!(Hash);
 serde_impl!(Hash, 20);
 borrow_slice_impl!(Hash);
+from_str_impl!(Hash);
+try_from_impl!(Hash);
+from_bytes_impl!(Hash, 20);
+into_iter_impl!(Hash);
+eq_bytes_impl!(Hash, 20);
+deref_impl!(Hash, 20);
+inner_ref_impl!(Hash, 20);
+ct_eq_impl!(Hash);
+zeroize_impl!(Hash);
+rand_impl!(Hash);
+proptest_impl!(Hash);
+quickcheck_impl!(Hash);
 
 impl HashTrait for Hash {
     type Engine = sha256::HashEngine;
@@ -64,7 +77,11 @@ impl HashTrait for Hash {
         }
     }
 
-    fn into_inner(self) -> Self::Inner {
+    fn from_byte_array(bytes: Self::Inner) -> Self {
+        Hash(bytes)
+    }
+
+    fn to_byte_array(self) -> Self::Inner {
         self.0
     }
 }
@@ -120,9 +137,9 @@ mod tests {
             for ch in test.input {
                 engine.write_all(&[ch]).expect("write to engine");
             }
-            let manual_hash = Hash::from_engine(engine);
+            let manual_hash = hash160::Hash::from_engine(engine);
             assert_eq!(hash, manual_hash);
-            assert_eq!(hash.into_inner()[..].as_ref(), test.output.as_slice());
+            assert_eq!(hash.to_byte_array()[..].as_ref(), test.output.as_slice());
         }
     }
 
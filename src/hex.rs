@@ -15,21 +15,49 @@
 //! # Hex encoding and decoding
 //!
 
-use std::fmt;
-use {Error, Hash};
+use core::{char, fmt, str};
+#[cfg(all(feature = "alloc", not(feature = "std")))]
+use prelude::{String, Vec};
+use {ByteArray, Error, Hash};
 
 /// Trait for objects that can be serialized as hex strings
+#[cfg(feature = "alloc")]
 pub trait ToHex {
     /// Hex representation of the object
     fn to_hex(&self) -> String;
 }
 
+/// Trait for objects that can be serialized as uppercase hex strings
+#[cfg(feature = "alloc")]
+pub trait ToUpperHex {
+    /// Uppercase hex representation of the object
+    fn to_upper_hex(&self) -> String;
+}
+
 /// Trait for objects that can be deserialized from hex strings
 pub trait FromHex: Sized {
     /// Produce an object from a hex string
     fn from_hex(s: &str) -> Result<Self, Error>;
+
+    /// Produce an object from a hex string which may optionally be prefixed
+    /// with `0x` or `0X`, as emitted by many JSON APIs
+    fn from_hex_with_prefix(s: &str) -> Result<Self, Error> {
+        // Positions of invalid characters count the prefix
+        let hex = strip_0x_prefix(s);
+        Self::from_hex(hex).map_err(|e| e.shift_position(s.len() - hex.len()))
+    }
+}
+
+/// Strips a leading `0x` or `0X` from a string, if present
+fn strip_0x_prefix(s: &str) -> &str {
+    if s.starts_with("0x") || s.starts_with("0X") {
+        &s[2..]
+    } else {
+        s
+    }
 }
 
+#[cfg(feature = "alloc")]
 impl<T: fmt::LowerHex> ToHex for T {
     /// Outputs the hash in hexadecimal form
     fn to_hex(&self) -> String {
@@ -37,6 +65,14 @@ impl<T: fmt::LowerHex> ToHex for T {
     }
 }
 
+#[cfg(feature = "alloc")]
+impl<T: fmt::UpperHex> ToUpperHex for T {
+    /// Outputs the hash in uppercase hexadecimal form
+    fn to_upper_hex(&self) -> String {
+        format!("{:X}", self)
+    }
+}
+
 impl<T: Hash> FromHex for T {
     /// Parses a hex string as a hash object
     fn from_hex(s: &str) -> Result<Self, Error> {
@@ -44,46 +80,146 @@ impl<T: Hash> FromHex for T {
             return Err(Error::InvalidLength(2 * Self::LEN, s.len()));
         }
 
-        let mut vec = Vec::<u8>::from_hex(s)?;
+        let mut bytes = T::Inner::zeroed();
+        HexIterator::new(s)?.fill(bytes.as_mut_bytes())?;
         if Self::DISPLAY_BACKWARD {
-            vec.reverse();
+            bytes.as_mut_bytes().reverse();
         }
-        Self::from_slice(&vec)
+        Ok(Self::from_byte_array(bytes))
     }
 }
 
 /// Iterator over a hex-encoded string slice which decodes hex and yields bytes.
+///
+/// The length of the string is checked on construction, so the iterator
+/// knows exactly how many bytes it yields; invalid characters are reported
+/// by yielding an error in place of the byte they would have been part of.
 pub struct HexIterator<'a> {
-    /// The slice whose first two characters will be decoded to yield the next byte
-    pub sl: &'a str
+    /// The string being decoded
+    sl: &'a str,
+    /// Offset of the next pair of characters to decode from the front
+    front: usize,
+    /// Offset just past the next pair of characters to decode from the back
+    back: usize,
+}
+
+impl<'a> HexIterator<'a> {
+    /// Constructs a new iterator over a hex string, which must be of even length
+    pub fn new(s: &'a str) -> Result<HexIterator<'a>, Error> {
+        if s.len() % 2 == 1 {
+            return Err(Error::OddLengthString(s.len()));
+        }
+        Ok(HexIterator {
+            sl: s,
+            front: 0,
+            back: s.len(),
+        })
+    }
+
+    /// Constructs a new iterator over a hex string, skipping a leading `0x`
+    /// or `0X` if there is one
+    pub fn with_prefix_skip(s: &'a str) -> Result<HexIterator<'a>, Error> {
+        let mut iter = HexIterator::new(strip_0x_prefix(s))?;
+        // Keep the whole string, so that positions in errors count the prefix
+        iter.sl = s;
+        iter.front += s.len() - iter.back;
+        iter.back = s.len();
+        Ok(iter)
+    }
+
+    /// Constructs a new iterator over the hex characters in a byte slice,
+    /// such as one read from the network, which must be ASCII and of even
+    /// length. This avoids decoding the input as UTF-8 first.
+    pub fn from_ascii(sl: &'a [u8]) -> Result<HexIterator<'a>, Error> {
+        if let Some(position) = sl.iter().position(|b| !b.is_ascii()) {
+            // Report the character if the bytes there are valid UTF-8
+            let rest = &sl[position..];
+            let valid = match str::from_utf8(rest) {
+                Ok(s) => s,
+                Err(e) => str::from_utf8(&rest[..e.valid_up_to()]).expect("valid prefix"),
+            };
+            let ch = valid.chars().next().unwrap_or(char::REPLACEMENT_CHARACTER);
+            return Err(Error::InvalidHexChar {
+                char: ch,
+                position,
+            });
+        }
+        HexIterator::new(str::from_utf8(sl).expect("ASCII is valid UTF-8"))
+    }
+
+    /// Decodes exactly `out.len()` bytes from the front of the iterator into
+    /// `out`, without allocating. Decoding a fixed-size array works by
+    /// passing a zeroed array on the stack.
+    ///
+    /// If fewer bytes remain, nothing is consumed and an `InvalidLength`
+    /// error giving the expected and available number of hex characters is
+    /// returned. On an invalid character, decoding stops there.
+    pub fn fill(&mut self, out: &mut [u8]) -> Result<(), Error> {
+        if self.len() < out.len() {
+            return Err(Error::InvalidLength(2 * out.len(), self.back - self.front));
+        }
+        for byte in out.iter_mut() {
+            *byte = self.next().expect("length checked above")?;
+        }
+        Ok(())
+    }
+
+    /// Decodes the pair of characters starting at byte offset `idx`
+    fn decode_pair(&self, idx: usize) -> Result<u8, Error> {
+        let bytes = self.sl.as_bytes();
+        // Bytes which are not ASCII map to characters which are not hex digits
+        match ((bytes[idx] as char).to_digit(16), (bytes[idx + 1] as char).to_digit(16)) {
+            (Some(hi), Some(lo)) => Ok(((hi << 4) + lo) as u8),
+            (None, _) => Err(self.invalid_char(idx)),
+            (_, None) => Err(self.invalid_char(idx + 1)),
+        }
+    }
+
+    /// Returns the error for the character the byte at offset `idx` is part of
+    fn invalid_char(&self, idx: usize) -> Error {
+        let (position, ch) = self.sl.char_indices()
+            .take_while(|&(start, _)| start <= idx)
+            .last()
+            .expect("offset is within the string");
+        Error::InvalidHexChar {
+            char: ch,
+            position,
+        }
+    }
 }
 
 impl<'a> Iterator for HexIterator<'a> {
     type Item = Result<u8, Error>;
 
     fn next(&mut self) -> Option<Result<u8, Error>> {
-        if self.sl.len() % 2 == 1 {
-            Some(Err(Error::OddLengthString(self.sl.len())))
-        } else if self.sl.is_empty() {
+        if self.front == self.back {
             None
         } else {
-            let (hi, lo) = {
-                let mut iter = self.sl.chars();
-                let hi = iter.next().unwrap();
-                let lo = iter.next().unwrap();
-                match (hi.to_digit(16), lo.to_digit(16)) {
-                    (Some(hi), Some(lo)) => (hi, lo),
-                    (None, _) => return Some(Err(Error::InvalidChar(hi))),
-                    (_, None) => return Some(Err(Error::InvalidChar(lo))),
-                }
-            };
-            let ret = (hi << 4) + lo;
-            self.sl = &self.sl[2..];
-            Some(Ok(ret as u8))
+            let ret = self.decode_pair(self.front);
+            self.front += 2;
+            Some(ret)
         }
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = (self.back - self.front) / 2;
+        (len, Some(len))
+    }
 }
 
+impl<'a> DoubleEndedIterator for HexIterator<'a> {
+    fn next_back(&mut self) -> Option<Result<u8, Error>> {
+        if self.front == self.back {
+            None
+        } else {
+            self.back -= 2;
+            Some(self.decode_pair(self.back))
+        }
+    }
+}
+
+impl<'a> ExactSizeIterator for HexIterator<'a> {}
+
 /// Output hex into an object implementing `fmt::Write`, which is usually more
 /// efficient than going through a `String` using `ToHex`.
 pub fn format_hex<T: fmt::Write>(data: &[u8], mut fmt: T) -> fmt::Result {
@@ -102,6 +238,83 @@ pub fn format_hex_reverse<T: fmt::Write>(data: &[u8], mut fmt: T) -> fmt::Result
     Ok(())
 }
 
+/// Output uppercase hex into an object implementing `fmt::Write`
+pub fn format_hex_uppercase<T: fmt::Write>(data: &[u8], mut fmt: T) -> fmt::Result {
+    for ch in data {
+        write!(fmt, "{:02X}", *ch)?;
+    }
+    Ok(())
+}
+
+/// Output uppercase hex in reverse order
+pub fn format_hex_reverse_uppercase<T: fmt::Write>(data: &[u8], mut fmt: T) -> fmt::Result {
+    for ch in data.iter().rev() {
+        write!(fmt, "{:02X}", *ch)?;
+    }
+    Ok(())
+}
+
+/// A byte slice which formats as hex with `{:x}` (or `{}`) and `{:X}`,
+/// which unlike `ToHex` does not allocate a `String`
+#[derive(Copy, Clone)]
+pub struct DisplayHex<'a>(&'a [u8]);
+
+/// Wraps a byte slice so that it can be formatted as hex
+pub fn display_hex<'a>(bytes: &'a [u8]) -> DisplayHex<'a> {
+    DisplayHex(bytes)
+}
+
+impl<'a> fmt::LowerHex for DisplayHex<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        format_hex(self.0, f)
+    }
+}
+
+impl<'a> fmt::UpperHex for DisplayHex<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        format_hex_uppercase(self.0, f)
+    }
+}
+
+impl<'a> fmt::Display for DisplayHex<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::LowerHex::fmt(self, f)
+    }
+}
+
+impl<'a> fmt::Debug for DisplayHex<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::LowerHex::fmt(self, f)
+    }
+}
+
+/// Write the lowercase hex encoding of `data` into `output` as ASCII
+/// characters, without allocating. `output` must be exactly twice as long as
+/// `data`, otherwise an `InvalidLength` error is returned.
+pub fn format_hex_into(data: &[u8], output: &mut [u8]) -> Result<(), Error> {
+    hex_into(data.iter(), data.len(), output)
+}
+
+/// Write the lowercase hex encoding of `data` in reverse order into
+/// `output`, as for `format_hex_into`
+pub fn format_hex_reverse_into(data: &[u8], output: &mut [u8]) -> Result<(), Error> {
+    hex_into(data.iter().rev(), data.len(), output)
+}
+
+fn hex_into<'a, I: Iterator<Item = &'a u8>>(data: I, len: usize, output: &mut [u8]) -> Result<(), Error> {
+    const HEX_CHARS: &[u8; 16] = b"0123456789abcdef";
+
+    if output.len() != 2 * len {
+        return Err(Error::InvalidLength(2 * len, output.len()));
+    }
+    for (byte, out) in data.zip(output.chunks_mut(2)) {
+        out[0] = HEX_CHARS[(byte >> 4) as usize];
+        out[1] = HEX_CHARS[(byte & 0xf) as usize];
+    }
+    Ok(())
+}
+
+#[cfg(feature = "alloc")]
 impl ToHex for [u8] {
     fn to_hex(&self) -> String {
         let mut ret = String::with_capacity(2 * self.len());
@@ -110,16 +323,20 @@ impl ToHex for [u8] {
     }
 }
 
+#[cfg(feature = "alloc")]
+impl ToUpperHex for [u8] {
+    fn to_upper_hex(&self) -> String {
+        let mut ret = String::with_capacity(2 * self.len());
+        format_hex_uppercase(self, &mut ret).expect("format to string");
+        ret
+    }
+}
+
+#[cfg(feature = "alloc")]
 impl FromHex for Vec<u8> {
     fn from_hex(s: &str) -> Result<Vec<u8>, Error> {
-        if s.len() % 2 == 1 {
-            return Err(Error::OddLengthString(s.len()));
-        }
-
-        let mut vec = Vec::with_capacity(s.len() / 2);
-        let iter = HexIterator {
-            sl: s
-        };
+        let iter = HexIterator::new(s)?;
+        let mut vec = Vec::with_capacity(iter.len());
         for byte in iter {
             vec.push(byte?);
         }
@@ -127,18 +344,80 @@ impl FromHex for Vec<u8> {
     }
 }
 
+/// Decodes a hex string into a `Vec`, as `Vec::from_hex` does, but 16
+/// characters at a time using SSE2 on x86-64. This is faster for long
+/// strings such as raw transactions and blocks. Elsewhere, and for the
+/// characters after the last full 16, it decodes one pair at a time.
+#[cfg(feature = "alloc")]
+pub fn from_hex_simd(s: &str) -> Result<Vec<u8>, Error> {
+    let mut iter = HexIterator::new(s)?;
+    let mut vec = Vec::with_capacity(iter.len());
+    // On an invalid character this stops before its chunk, which is then
+    // decoded below to report the character
+    iter.front = decode_simd(s.as_bytes(), &mut vec);
+    for byte in iter {
+        vec.push(byte?);
+    }
+    Ok(vec)
+}
+
+/// Decodes the longest prefix of `bytes` made of whole 16-character chunks
+/// of valid hex into `out`, returning the number of characters decoded
+#[cfg(all(feature = "alloc", target_arch = "x86_64"))]
+fn decode_simd(bytes: &[u8], out: &mut Vec<u8>) -> usize {
+    use core::arch::x86_64::*;
+
+    let mut done = 0;
+    // SSE2 is part of x86-64, so it needs no runtime detection
+    unsafe {
+        let zero = _mm_setzero_si128();
+        let ascii_zero = _mm_set1_epi8(b'0' as i8);
+        let ascii_a = _mm_set1_epi8(b'a' as i8);
+        let lowercase = _mm_set1_epi8(0x20);
+        let low_bytes = _mm_set1_epi16(0x00ff);
+
+        for chunk in bytes.chunks_exact(16) {
+            let v = _mm_loadu_si128(chunk.as_ptr() as *const __m128i);
+            // `x <= n` as unsigned bytes is `x - n` saturating to zero
+            let digit = _mm_sub_epi8(v, ascii_zero);
+            let is_digit = _mm_cmpeq_epi8(_mm_subs_epu8(digit, _mm_set1_epi8(9)), zero);
+            let alpha = _mm_sub_epi8(_mm_or_si128(v, lowercase), ascii_a);
+            let is_alpha = _mm_cmpeq_epi8(_mm_subs_epu8(alpha, _mm_set1_epi8(5)), zero);
+            if _mm_movemask_epi8(_mm_or_si128(is_digit, is_alpha)) != 0xffff {
+                break;
+            }
+
+            let nibbles = _mm_or_si128(
+                _mm_and_si128(digit, is_digit),
+                _mm_and_si128(_mm_add_epi8(alpha, _mm_set1_epi8(10)), is_alpha),
+            );
+            // Each 16-bit lane holds a high nibble in its low byte and the
+            // following low nibble in its high byte
+            let pairs = _mm_or_si128(
+                _mm_slli_epi16(_mm_and_si128(nibbles, low_bytes), 4),
+                _mm_srli_epi16(nibbles, 8),
+            );
+            let mut buf = [0u8; 16];
+            _mm_storeu_si128(buf.as_mut_ptr() as *mut __m128i, _mm_packus_epi16(pairs, zero));
+            out.extend_from_slice(&buf[..8]);
+            done += 16;
+        }
+    }
+    done
+}
+
+#[cfg(all(feature = "alloc", not(target_arch = "x86_64")))]
+fn decode_simd(_bytes: &[u8], _out: &mut Vec<u8>) -> usize {
+    0
+}
+
 macro_rules! impl_fromhex_array {
     ($len:expr) => {
         impl FromHex for [u8; $len] {
             fn from_hex(s: &str) -> Result<[u8; $len], Error> {
                 if s.len() == 2 * $len {
                     let mut ret = [0; $len];
-                    let iter = HexIterator {
-                        sl: s,
-                    };
-                    for (n, byte) in iter.enumerate() {
-                        ret[n] = byte?;
-                    }
+                    HexIterator::new(s)?.fill(&mut ret)?;
                     Ok(ret)
                 } else {
                     if s.len() % 2 == 1 {
@@ -174,9 +453,13 @@ impl_fromhex_array!(512);
 
 #[cfg(test)]
 mod tests {
-    use super::{ToHex, FromHex};
+    use super::{ToHex, ToUpperHex, FromHex, HexIterator};
     use Error;
 
+    fn invalid(ch: char, position: usize) -> Error {
+        Error::InvalidHexChar { char: ch, position }
+    }
+
     #[test]
     fn hex_roundtrip() {
         let expected = "0123456789abcdef";
@@ -198,6 +481,218 @@ mod tests {
         assert_eq!(ser, expected);
     }
 
+    #[test]
+    fn hex_iterator() {
+        let iter = HexIterator::new("0123456789abcdef").expect("even length");
+        assert_eq!(iter.len(), 8);
+        assert_eq!(iter.size_hint(), (8, Some(8)));
+
+        let iter = HexIterator::new("0123456789abcdef").expect("even length");
+        assert_eq!(
+            iter.rev().collect::<Result<Vec<u8>, Error>>(),
+            Ok(vec![0xef, 0xcd, 0xab, 0x89, 0x67, 0x45, 0x23, 0x01])
+        );
+
+        // Iterating from both ends meets in the middle
+        let mut iter = HexIterator::new("012345").expect("even length");
+        assert_eq!(iter.next(), Some(Ok(0x01)));
+        assert_eq!(iter.next_back(), Some(Ok(0x45)));
+        assert_eq!(iter.len(), 1);
+        assert_eq!(iter.next_back(), Some(Ok(0x23)));
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next_back(), None);
+
+        // Invalid characters take the place of one byte each
+        let iter = HexIterator::new("01zz23\u{e9}").expect("even length");
+        assert_eq!(iter.len(), 4);
+        assert_eq!(
+            iter.collect::<Vec<_>>(),
+            vec![Ok(0x01), Err(invalid('z', 2)), Ok(0x23), Err(invalid('\u{e9}', 6))]
+        );
+        assert_eq!(Vec::<u8>::from_hex("ab\u{e9}"), Err(invalid('\u{e9}', 2)));
+
+        // Filling fixed-size buffers from a longer string
+        let mut iter = HexIterator::new("0123456789abcdef").expect("even length");
+        let mut head = [0; 2];
+        let mut tail = [0; 8];
+        iter.fill(&mut head).expect("fill");
+        assert_eq!(head, [0x01, 0x23]);
+        assert_eq!(iter.fill(&mut tail), Err(Error::InvalidLength(16, 12)));
+        assert_eq!(iter.len(), 6);
+        iter.fill(&mut tail[..6]).expect("fill");
+        assert_eq!(&tail[..6], &[0x45, 0x67, 0x89, 0xab, 0xcd, 0xef]);
+        let mut iter = HexIterator::new("01zz").expect("even length");
+        assert_eq!(iter.fill(&mut head), Err(invalid('z', 2)));
+
+        assert!(HexIterator::new("012").is_err());
+        assert_eq!(Vec::<u8>::from_hex("012"), Err(Error::OddLengthString(3)));
+    }
+
+    #[test]
+    fn hex_iterator_from_ascii() {
+        let iter = HexIterator::from_ascii(b"deadbeef").expect("ascii");
+        assert_eq!(iter.collect::<Result<Vec<u8>, Error>>(), Ok(vec![0xde, 0xad, 0xbe, 0xef]));
+        let iter = HexIterator::from_ascii(b"01zz").expect("ascii");
+        assert_eq!(iter.collect::<Vec<_>>(), vec![Ok(0x01), Err(invalid('z', 2))]);
+        assert_eq!(HexIterator::from_ascii(b"012").err(), Some(Error::OddLengthString(3)));
+
+        // Non-ASCII bytes are rejected up front, giving the character when
+        // they are valid UTF-8
+        assert_eq!(HexIterator::from_ascii("ab\u{e9}".as_bytes()).err(), Some(invalid('\u{e9}', 2)));
+        assert_eq!(HexIterator::from_ascii(b"abc\xff").err(), Some(invalid('\u{fffd}', 3)));
+        assert_eq!(HexIterator::from_ascii(b"\xe9ab").err(), Some(invalid('\u{fffd}', 0)));
+    }
+
+    #[test]
+    fn hex_prefix() {
+        use {sha256, sha256d};
+
+        let bytes = vec![0xde, 0xad, 0xbe, 0xef];
+        assert_eq!(Vec::<u8>::from_hex_with_prefix("0xdeadbeef"), Ok(bytes.clone()));
+        assert_eq!(Vec::<u8>::from_hex_with_prefix("0XDEADBEEF"), Ok(bytes.clone()));
+        assert_eq!(Vec::<u8>::from_hex_with_prefix("deadbeef"), Ok(bytes.clone()));
+        assert_eq!(<[u8; 4]>::from_hex_with_prefix("0xdeadbeef"), Ok([0xde, 0xad, 0xbe, 0xef]));
+        assert_eq!(Vec::<u8>::from_hex("0xdeadbeef"), Err(invalid('x', 1)));
+
+        // Positions count the prefix
+        assert_eq!(Vec::<u8>::from_hex_with_prefix("0xdeadbeez"), Err(invalid('z', 9)));
+        assert_eq!(Vec::<u8>::from_hex_with_prefix("deadbeez"), Err(invalid('z', 7)));
+        let mut iter = HexIterator::with_prefix_skip("0xdezz").expect("even length");
+        assert_eq!(iter.len(), 2);
+        assert_eq!(iter.next_back(), Some(Err(invalid('z', 4))));
+        assert_eq!(iter.next(), Some(Ok(0xde)));
+        assert_eq!(iter.next(), None);
+
+        let iter = HexIterator::with_prefix_skip("0xdeadbeef").expect("even length");
+        assert_eq!(iter.collect::<Result<Vec<u8>, Error>>(), Ok(bytes.clone()));
+        let iter = HexIterator::with_prefix_skip("deadbeef").expect("even length");
+        assert_eq!(iter.collect::<Result<Vec<u8>, Error>>(), Ok(bytes));
+
+        let s = "ef537f25c895bfa782526529a9b63d97aa631564d5d789c2b765448c8635fb6c";
+        let prefixed = format!("0x{}", s);
+        assert_eq!(
+            sha256::Hash::from_hex_with_prefix(&prefixed),
+            sha256::Hash::from_hex(s)
+        );
+        assert_eq!(
+            sha256d::Hash::from_hex_with_prefix(&prefixed),
+            sha256d::Hash::from_hex(s)
+        );
+        assert_eq!(
+            sha256::Hash::from_hex_with_prefix("0x"),
+            Err(Error::InvalidLength(64, 0))
+        );
+    }
+
+    #[test]
+    fn display_hex() {
+        use super::display_hex;
+
+        let bytes = [0x00, 0x01, 0xab, 0xff];
+        assert_eq!(format!("{:x}", display_hex(&bytes)), "0001abff");
+        assert_eq!(format!("{:X}", display_hex(&bytes)), "0001ABFF");
+        assert_eq!(format!("{}", display_hex(&bytes)), "0001abff");
+        assert_eq!(format!("{:?}", display_hex(&bytes[1..3])), "01ab");
+        assert_eq!(format!("{:x}", display_hex(&[])), "");
+        assert_eq!(display_hex(&bytes).to_string(), bytes.to_hex());
+    }
+
+    #[test]
+    fn hex_simd() {
+        use super::from_hex_simd;
+
+        // Lengths around the 16-character chunks, in both cases
+        let bytes: Vec<u8> = (0..100u32).map(|i| (i * 37) as u8).collect();
+        for len in 0..bytes.len() {
+            let hex = bytes[..len].to_hex();
+            assert_eq!(from_hex_simd(&hex), Ok(bytes[..len].to_vec()));
+            assert_eq!(from_hex_simd(&hex.to_uppercase()), Ok(bytes[..len].to_vec()));
+        }
+        assert_eq!(from_hex_simd("0123456789abcdefABCDEF"), Vec::<u8>::from_hex("0123456789abcdefABCDEF"));
+
+        // Errors are those of `from_hex`, wherever the invalid character is
+        let hex = bytes.to_hex();
+        for &bad in &['g', 'G', '/', ':', '@', '`', ' ', '\u{0}'] {
+            for i in 0..40 {
+                let mut s = hex[..40].to_owned();
+                s.replace_range(i..i + 1, &bad.to_string());
+                assert_eq!(from_hex_simd(&s), Err(invalid(bad, i)));
+            }
+        }
+        let s = format!("{}\u{e9}{}", &hex[..20], &hex[..18]);
+        assert_eq!(from_hex_simd(&s), Err(invalid('\u{e9}', 20)));
+        assert_eq!(from_hex_simd("012"), Err(Error::OddLengthString(3)));
+    }
+
+    #[test]
+    fn upper_hex() {
+        use {sha256, sha256d, Hmac};
+
+        let bytes = [0x01, 0x23, 0x45, 0x67, 0x89, 0xab, 0xcd, 0xef];
+        assert_eq!(bytes[..].to_upper_hex(), "0123456789ABCDEF");
+
+        let s = "ef537f25c895bfa782526529a9b63d97aa631564d5d789c2b765448c8635fb6c";
+        let up = "EF537F25C895BFA782526529A9B63D97AA631564D5D789C2B765448C8635FB6C";
+        let hash = s.parse::<sha256::Hash>().expect("parse sha256");
+        assert_eq!(format!("{:X}", hash), up);
+        assert_eq!(hash.to_upper_hex(), up);
+
+        // Backward-displayed hashes are also backward in uppercase
+        let hash = s.parse::<sha256d::Hash>().expect("parse sha256d");
+        assert_eq!(format!("{:X}", hash), up);
+
+        let hash = s.parse::<Hmac<sha256::Hash>>().expect("parse hmac");
+        assert_eq!(hash.to_upper_hex(), up);
+    }
+
+    #[test]
+    fn hex_into() {
+        use super::{format_hex, format_hex_into, format_hex_reverse, format_hex_reverse_into};
+
+        let data: Vec<u8> = (0..=255).collect();
+        let mut expected = String::new();
+        format_hex(&data, &mut expected).expect("format to string");
+        let mut output = [0; 512];
+        format_hex_into(&data, &mut output).expect("right length");
+        assert_eq!(&output[..], expected.as_bytes());
+
+        let mut expected = String::new();
+        format_hex_reverse(&data[..3], &mut expected).expect("format to string");
+        assert_eq!(expected, "020100");
+        format_hex_reverse_into(&data[..3], &mut output[..6]).expect("right length");
+        assert_eq!(&output[..6], expected.as_bytes());
+
+        assert_eq!(format_hex_into(&data[..3], &mut output[..5]), Err(Error::InvalidLength(6, 5)));
+        assert_eq!(format_hex_into(&[], &mut []), Ok(()));
+    }
+
+    #[test]
+    fn hash_from_str() {
+        use {sha256, sha256d, Hmac};
+
+        let s = "ef537f25c895bfa782526529a9b63d97aa631564d5d789c2b765448c8635fb6c";
+        let hash = s.parse::<sha256::Hash>().expect("parse sha256");
+        assert_eq!(hash, sha256::Hash::from_hex(s).expect("parse sha256"));
+        assert_eq!(hash.to_string(), s);
+
+        // Backward-displayed hashes roundtrip through their display form
+        let hash = s.parse::<sha256d::Hash>().expect("parse sha256d");
+        assert_eq!(hash.to_string(), s);
+        assert_eq!(hash[0], 0x6c);
+
+        let hash = s.parse::<Hmac<sha256::Hash>>().expect("parse hmac");
+        assert_eq!(hash.to_string(), s);
+
+        assert_eq!(
+            s[1..].parse::<sha256::Hash>(),
+            Err(Error::InvalidLength(64, 63))
+        );
+        assert_eq!(
+            "Z".repeat(64).parse::<sha256::Hash>(),
+            Err(invalid('Z', 0))
+        );
+    }
+
     #[test]
     fn hex_error() {
         let oddlen = "0123456789abcdef0";
@@ -219,16 +714,46 @@ mod tests {
         );
         assert_eq!(
             Vec::<u8>::from_hex(badchar1),
-            Err(Error::InvalidChar('Z'))
+            Err(invalid('Z', 0))
         );
         assert_eq!(
             Vec::<u8>::from_hex(badchar2),
-            Err(Error::InvalidChar('Y'))
+            Err(invalid('Y', 3))
         );
         assert_eq!(
             Vec::<u8>::from_hex(badchar3),
-            Err(Error::InvalidChar('«'))
+            Err(invalid('«', 0))
         );
     }
 }
 
+
+#[cfg(all(test, feature="unstable"))]
+mod benches {
+    use test::Bencher;
+
+    use super::{from_hex_simd, FromHex, ToHex};
+
+    fn hex_64k() -> String {
+        let bytes: Vec<u8> = (0..32768u32).map(|i| (i * 37) as u8).collect();
+        bytes.to_hex()
+    }
+
+    #[bench]
+    pub fn from_hex_64k(bh: & mut Bencher) {
+        let hex = hex_64k();
+        bh.iter( || {
+            Vec::<u8>::from_hex(&hex).expect("valid hex")
+        });
+        bh.bytes = hex.len() as u64;
+    }
+
+    #[bench]
+    pub fn from_hex_simd_64k(bh: & mut Bencher) {
+        let hex = hex_64k();
+        bh.iter( || {
+            from_hex_simd(&hex).expect("valid hex")
+        });
+        bh.bytes = hex.len() as u64;
+    }
+}
@@ -0,0 +1,167 @@
+// Bitcoin Hashes Library
+// Written in 2019 by
+//   The rust-bitcoin developers
+//
+// To the extent possible under law, the author(s) have dedicated all
+// copyright and related and neighboring rights to this software to
+// the public domain worldwide. This software is distributed without
+// any warranty.
+//
+// You should have received a copy of the CC0 Public Domain Dedication
+// along with this software.
+// If not, see <http://creativecommons.org/publicdomain/zero/1.0/>.
+//
+
+//! # HKDF
+//!
+//! The HMAC-based key derivation function from RFC 5869, parameterized by
+//! the underlying hash function.
+
+use hmac::{Hmac, HmacEngine};
+use {Error, Hash, HashEngine};
+
+/// Maximum number of output blocks `expand` can produce
+const MAX_BLOCKS: usize = 255;
+
+/// HKDF-Extract: derives a pseudorandom key from the input keying material
+/// `ikm` and an optional (possibly empty) `salt`
+pub fn extract<H: Hash>(salt: &[u8], ikm: &[u8]) -> Hmac<H> {
+    let mut engine = HmacEngine::<H>::new(salt);
+    engine.input(ikm);
+    Hmac::from_engine(engine)
+}
+
+/// HKDF-Expand: fills `okm` with output keying material derived from the
+/// pseudorandom key `prk` and the context string `info`. At most 255 times
+/// the hash length can be output; longer requests give `Error::InvalidLength`.
+pub fn expand<H: Hash>(prk: &Hmac<H>, info: &[u8], okm: &mut [u8]) -> Result<(), Error> {
+    if okm.len() > MAX_BLOCKS * H::LEN {
+        return Err(Error::InvalidLength(MAX_BLOCKS * H::LEN, okm.len()));
+    }
+
+    let mut prev: Option<Hmac<H>> = None;
+    for (i, chunk) in okm.chunks_mut(H::LEN).enumerate() {
+        let mut engine = HmacEngine::<H>::new(&prk[..]);
+        if let Some(ref prev) = prev {
+            engine.input(&prev[..]);
+        }
+        engine.input(info);
+        // The block counter starts at one and cannot exceed `MAX_BLOCKS`
+        engine.input(&[(i + 1) as u8]);
+        let block = Hmac::from_engine(engine);
+
+        chunk.copy_from_slice(&block[..chunk.len()]);
+        prev = Some(block);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use hex::{FromHex, ToHex};
+    use hkdf;
+    use hmac::Hmac;
+    use {sha256, sha512, Error};
+
+    #[derive(Clone)]
+    struct Test {
+        ikm: Vec<u8>,
+        salt: Vec<u8>,
+        info: Vec<u8>,
+        prk: &'static str,
+        okm: &'static str,
+    }
+
+    #[test]
+    fn test_sha256() {
+        let tests = vec![
+            // Test vectors from RFC 5869, Appendix A
+            Test {
+                ikm: vec![0x0b; 22],
+                salt: (0x00..0x0d).collect(),
+                info: (0xf0..0xfa).collect(),
+                prk: "077709362c2e32df0ddc3f0dc47bba6390b6c73bb50f9c3122ec844ad7c2b3e5",
+                okm: "3cb25f25faacd57a90434f64d0362f2a2d2d0a90cf1a5a4c5db02d56ecc4c5bf\
+                      34007208d5b887185865",
+            },
+            Test {
+                ikm: (0x00..0x50).collect(),
+                salt: (0x60..0xb0).collect(),
+                info: (0xb0..=0xff).collect(),
+                prk: "06a6b88c5853361a06104c9ceb35b45cef760014904671014a193f40c15fc244",
+                okm: "b11e398dc80327a1c8e7f78c596a49344f012eda2d4efad8a050cc4c19afa97c\
+                      59045a99cac7827271cb41c65e590e09da3275600c2f09b8367793a9aca3db71\
+                      cc30c58179ec3e87c14c01d5c1f3434f1d87",
+            },
+            Test {
+                ikm: vec![0x0b; 22],
+                salt: vec![],
+                info: vec![],
+                prk: "19ef24a32c717b167f33a91d6f648bdf96596776afdb6377ac434c1c293ccb04",
+                okm: "8da4e775a563c18f715f802a063c5a31b8a11f5c5ee1879ec3454e5f3c738d2d\
+                      9d201395faa4b61a96c8",
+            },
+        ];
+
+        for test in tests {
+            let prk = hkdf::extract::<sha256::Hash>(&test.salt, &test.ikm);
+            assert_eq!(prk.to_hex(), test.prk);
+
+            let expected = Vec::<u8>::from_hex(test.okm).expect("valid hex");
+            let mut okm = vec![0; expected.len()];
+            hkdf::expand(&prk, &test.info, &mut okm).expect("short enough output");
+            assert_eq!(okm, expected);
+        }
+    }
+
+    #[test]
+    fn test_sha512() {
+        let tests = vec![
+            // The inputs of RFC 5869 test cases 1 and 3, outputs computed with
+            // Python's `hmac` module
+            Test {
+                ikm: vec![0x0b; 22],
+                salt: (0x00..0x0d).collect(),
+                info: (0xf0..0xfa).collect(),
+                prk: "665799823737ded04a88e47e54a5890bb2c3d247c7a4254a8e61350723590a26\
+                      c36238127d8661b88cf80ef802d57e2f7cebcf1e00e083848be19929c61b4237",
+                okm: "832390086cda71fb47625bb5ceb168e4c8e26a1a16ed34d9fc7fe92c14815793\
+                      38da362cb8d9f925d7cb",
+            },
+            Test {
+                ikm: vec![0x0b; 22],
+                salt: vec![],
+                info: vec![],
+                prk: "fd200c4987ac491313bd4a2a13287121247239e11c9ef82802044b66ef357e5b\
+                      194498d0682611382348572a7b1611de54764094286320578a863f36562b0df6",
+                okm: "f5fa02b18298a72a8c23898a8703472c6eb179dc204c03425c970e3b164bf90f\
+                      ff22d04836d0e2343bac",
+            },
+        ];
+
+        for test in tests {
+            let prk = hkdf::extract::<sha512::Hash>(&test.salt, &test.ikm);
+            assert_eq!(prk.to_hex(), test.prk);
+
+            let expected = Vec::<u8>::from_hex(test.okm).expect("valid hex");
+            let mut okm = vec![0; expected.len()];
+            hkdf::expand(&prk, &test.info, &mut okm).expect("short enough output");
+            assert_eq!(okm, expected);
+        }
+    }
+
+    #[test]
+    fn output_limit() {
+        let prk: Hmac<sha256::Hash> = hkdf::extract(b"salt", b"ikm");
+
+        let mut okm = vec![0; 255 * 32];
+        assert_eq!(hkdf::expand(&prk, b"", &mut okm), Ok(()));
+        // Any prefix of the output is the output of a shorter expansion
+        let mut short = [0; 40];
+        hkdf::expand(&prk, b"", &mut short).expect("short enough output");
+        assert_eq!(&short[..], &okm[..40]);
+
+        let mut okm = vec![0; 255 * 32 + 1];
+        assert_eq!(hkdf::expand(&prk, b"", &mut okm), Err(Error::InvalidLength(255 * 32, 255 * 32 + 1)));
+    }
+}
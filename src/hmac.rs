@@ -19,10 +19,17 @@
 
 //! # HMAC support
 
-use std::{borrow, fmt, io, ops};
+use core::{borrow, convert, fmt, iter, ops, str};
+#[cfg(feature = "std")]
+use std::io;
 #[cfg(feature="serde")]
 use serde::{Serialize, Serializer, Deserialize, Deserializer};
+#[cfg(feature="subtle")]
+use subtle::{Choice, ConstantTimeEq};
+#[cfg(feature="zeroize")]
+use zeroize::{Zeroize, ZeroizeOnDrop};
 
+use hex::FromHex;
 use {Error, Hash, HashEngine};
 
 /// A hash computed from a RFC 2104 HMAC. Parameterized by the underlying hash function.
@@ -58,10 +65,10 @@ impl<T: Hash> HmacEngine<T> {
                 *b_o ^= *b_h;
             }
         } else {
-            for (b_i, b_h) in ipad.iter_mut().zip(&key[..]) {
+            for (b_i, b_h) in ipad.iter_mut().zip(key) {
                 *b_i ^= *b_h;
             }
-            for (b_o, b_h) in opad.iter_mut().zip(&key[..]) {
+            for (b_o, b_h) in opad.iter_mut().zip(key) {
                 *b_o ^= *b_h;
             }
         };
@@ -72,6 +79,18 @@ impl<T: Hash> HmacEngine<T> {
     }
 }
 
+#[cfg(feature="zeroize")]
+impl<T: Hash> Zeroize for HmacEngine<T> where T::Engine: Zeroize {
+    fn zeroize(&mut self) {
+        self.iengine.zeroize();
+        self.oengine.zeroize();
+    }
+}
+
+// Both inner engines zeroize themselves when dropped
+#[cfg(feature="zeroize")]
+impl<T: Hash> ZeroizeOnDrop for HmacEngine<T> where T::Engine: ZeroizeOnDrop {}
+
 impl<T: Hash> HashEngine for HmacEngine<T> {
     type MidState = <<T as Hash>::Engine as HashEngine>::MidState;
 
@@ -80,15 +99,37 @@ impl<T: Hash> HashEngine for HmacEngine<T> {
     }
 
     const BLOCK_SIZE: usize = T::Engine::BLOCK_SIZE;
+
+    fn input(&mut self, buf: &[u8]) {
+        self.iengine.input(buf)
+    }
 }
 
+#[cfg(feature = "std")]
 impl<T: Hash> io::Write for HmacEngine<T> {
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
-        self.iengine.write(buf)
+        self.input(buf);
+        Ok(buf.len())
     }
 
     fn flush(&mut self) -> io::Result<()> {
-        self.iengine.flush()
+        Ok(())
+    }
+}
+
+impl<T: Hash> iter::Extend<u8> for HmacEngine<T> {
+    fn extend<I: IntoIterator<Item = u8>>(&mut self, iter: I) {
+        for byte in iter {
+            self.input(&[byte]);
+        }
+    }
+}
+
+impl<'a, T: Hash> iter::Extend<&'a u8> for HmacEngine<T> {
+    fn extend<I: IntoIterator<Item = &'a u8>>(&mut self, iter: I) {
+        for byte in iter {
+            self.input(&[*byte]);
+        }
     }
 }
 
@@ -110,6 +151,12 @@ impl<T: Hash> fmt::LowerHex for Hmac<T> {
     }
 }
 
+impl<T: Hash + fmt::UpperHex> fmt::UpperHex for Hmac<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::UpperHex::fmt(&self.0, f)
+    }
+}
+
 impl<T: Hash> ops::Index<usize> for Hmac<T> {
     type Output = u8;
     fn index(&self, index: usize) -> &u8 {
@@ -151,6 +198,62 @@ impl<T: Hash> borrow::Borrow<[u8]> for Hmac<T> {
     }
 }
 
+impl<T: Hash> convert::AsRef<[u8]> for Hmac<T> {
+    fn as_ref(&self) -> &[u8] {
+        &self[..]
+    }
+}
+
+#[cfg(feature = "as_mut")]
+impl<T: Hash + convert::AsMut<[u8]>> convert::AsMut<[u8]> for Hmac<T> {
+    fn as_mut(&mut self) -> &mut [u8] {
+        self.0.as_mut()
+    }
+}
+
+impl<T: Hash> str::FromStr for Hmac<T> {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Hmac<T>, Error> {
+        FromHex::from_hex(s)
+    }
+}
+
+impl<'a, T: Hash> convert::TryFrom<&'a [u8]> for Hmac<T> {
+    type Error = Error;
+    fn try_from(sl: &'a [u8]) -> Result<Hmac<T>, Error> {
+        Hmac::from_slice(sl)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Hash> convert::TryFrom<::prelude::Vec<u8>> for Hmac<T> {
+    type Error = Error;
+    fn try_from(v: ::prelude::Vec<u8>) -> Result<Hmac<T>, Error> {
+        Hmac::from_slice(&v)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Hash> From<Hmac<T>> for ::prelude::Vec<u8> {
+    fn from(hmac: Hmac<T>) -> ::prelude::Vec<u8> {
+        hmac[..].to_vec()
+    }
+}
+
+#[cfg(feature="subtle")]
+impl<T: Hash> ConstantTimeEq for Hmac<T> {
+    fn ct_eq(&self, other: &Hmac<T>) -> Choice {
+        self[..].ct_eq(&other[..])
+    }
+}
+
+#[cfg(feature="zeroize")]
+impl<T: Hash + Zeroize> Zeroize for Hmac<T> {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
 impl<T: Hash> Hash for Hmac<T> {
     type Engine = HmacEngine<T>;
     type Inner = T::Inner;
@@ -172,8 +275,12 @@ impl<T: Hash> Hash for Hmac<T> {
         T::from_slice(sl).map(Hmac)
     }
 
-    fn into_inner(self) -> Self::Inner {
-        self.0.into_inner()
+    fn from_byte_array(bytes: Self::Inner) -> Self {
+        Hmac(T::from_byte_array(bytes))
+    }
+
+    fn to_byte_array(self) -> Self::Inner {
+        self.0.to_byte_array()
     }
 }
 
@@ -194,8 +301,9 @@ impl<'de, T: Hash + Deserialize<'de>> Deserialize<'de> for Hmac<T> {
 
 #[cfg(test)]
 mod tests {
+    use hex::{FromHex, ToHex};
     use sha256;
-    #[cfg(feature="serde")] use sha512;
+    use sha512;
     use {Hash, HashEngine, Hmac, HmacEngine};
 
     #[derive(Clone)]
@@ -316,10 +424,74 @@ mod tests {
             engine.input(&test.input);
             let hash = Hmac::<sha256::Hash>::from_engine(engine);
             assert_eq!(&hash[..], &test.output[..]);
-            assert_eq!(hash.into_inner()[..].as_ref(), test.output.as_slice());
+            assert_eq!(hash.to_byte_array()[..].as_ref(), test.output.as_slice());
+        }
+    }
+
+    #[test]
+    fn hmac_sha512() {
+        // Test cases 1-4 and 6 from RFC 4231
+        let tests: Vec<(Vec<u8>, &[u8], &str)> = vec![
+            (
+                vec![0x0b; 20],
+                b"Hi There",
+                "87aa7cdea5ef619d4ff0b4241a1d6cb02379f4e2ce4ec2787ad0b30545e17cde\
+                 daa833b7d6b8a702038b274eaea3f4e4be9d914eeb61f1702e696c203a126854",
+            ),
+            (
+                b"Jefe".to_vec(),
+                b"what do ya want for nothing?",
+                "164b7a7bfcf819e2e395fbe73b56e0a387bd64222e831fd610270cd7ea250554\
+                 9758bf75c05a994a6d034f65f8f0e6fdcaeab1a34d4a6b4b636e070a38bce737",
+            ),
+            (
+                vec![0xaa; 20],
+                &[0xdd; 50],
+                "fa73b0089d56a284efb0f0756c890be9b1b5dbdd8ee81a3655f83e33b2279d39\
+                 bf3e848279a722c806b485a47e67c807b946a337bee8942674278859e13292fb",
+            ),
+            (
+                (1..26).collect(),
+                &[0xcd; 50],
+                "b0ba465637458c6990e5a8c5f61d4af7e576d97ff94b872de76f8050361ee3db\
+                 a91ca5c11aa25eb4d679275cc5788063a5f19741120c4f2de2adebeb10a298dd",
+            ),
+            (
+                vec![0xaa; 131],
+                b"Test Using Larger Than Block-Size Key - Hash Key First",
+                "80b24263c7c1a3ebb71493c1dd7be8b49b46d1f41b4aeec1121b013783f8f352\
+                 6b56d037e05f2598bd0fd2215d6a1e5295e64f73f63f0aec8b915a985d786598",
+            ),
+        ];
+
+        for (key, input, output) in tests {
+            let mut engine = HmacEngine::<sha512::Hash>::new(&key);
+            engine.input(input);
+            let hash = Hmac::<sha512::Hash>::from_engine(engine);
+            assert_eq!(hash.to_hex(), output);
+            assert_eq!(&hash[..], &Vec::<u8>::from_hex(output).expect("valid hex")[..]);
         }
     }
 
+    #[test]
+    fn bip32_master_key() {
+        // BIP32 test vector 1: the master key and chain code are the two halves
+        // of HMAC-SHA512 keyed with "Bitcoin seed" over the seed
+        let seed = Vec::<u8>::from_hex("000102030405060708090a0b0c0d0e0f").expect("valid hex");
+        let mut engine = HmacEngine::<sha512::Hash>::new(b"Bitcoin seed");
+        engine.input(&seed);
+        let hmac = Hmac::<sha512::Hash>::from_engine(engine);
+
+        assert_eq!(
+            hmac[..32].to_hex(),
+            "e8f32e723decf4051aefac8e2c93c9c5b214313817cdb01a1494b917c8436b35",
+        );
+        assert_eq!(
+            hmac[32..].to_hex(),
+            "873dff81c02f525623fd1fe5167eac3a55a049de3d314bb42ee227ffed37d508",
+        );
+    }
+
     #[cfg(feature="serde")]
     #[test]
     fn hmac_sha512_serde() {
@@ -0,0 +1,298 @@
+// Bitcoin Hashes Library
+// Written in 2019 by
+//   The rust-bitcoin developers
+//
+// To the extent possible under law, the author(s) have dedicated all
+// copyright and related and neighboring rights to this software to
+// the public domain worldwide. This software is distributed without
+// any warranty.
+//
+// You should have received a copy of the CC0 Public Domain Dedication
+// along with this software.
+// If not, see <http://creativecommons.org/publicdomain/zero/1.0/>.
+//
+
+//! # I/O adapters
+//!
+//! Generic wrappers which let any hash function be used with the `std::io`
+//! traits without knowing the concrete engine type.
+
+use std::fs::File;
+use std::io;
+use std::path::Path;
+
+use {Error, Hash, HashEngine};
+
+/// A writer which hashes all data written to it. It may optionally pass the
+/// data on to an inner writer (a "tee"), in which case only the bytes which
+/// the inner writer accepted are hashed.
+pub struct HashWriter<H: Hash, W = io::Sink> {
+    engine: H::Engine,
+    inner: W,
+}
+
+impl<H: Hash> HashWriter<H> {
+    /// Construct a writer which only hashes the data written to it
+    pub fn new() -> HashWriter<H> {
+        HashWriter::tee(io::sink())
+    }
+}
+
+impl<H: Hash> Default for HashWriter<H> {
+    fn default() -> HashWriter<H> {
+        HashWriter::new()
+    }
+}
+
+impl<H: Hash, W: io::Write> HashWriter<H, W> {
+    /// Construct a writer which hashes the data written to it while also
+    /// writing it to `inner`
+    pub fn tee(inner: W) -> HashWriter<H, W> {
+        HashWriter {
+            engine: H::engine(),
+            inner,
+        }
+    }
+
+    /// Gets a reference to the inner writer
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Gets a mutable reference to the inner writer. Data written directly
+    /// to it will not be hashed.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Produces the hash of all data written so far
+    pub fn finalize(self) -> H {
+        H::from_engine(self.engine)
+    }
+
+    /// Produces the hash of all data written so far, returning it along
+    /// with the inner writer
+    pub fn into_parts(self) -> (H, W) {
+        (H::from_engine(self.engine), self.inner)
+    }
+}
+
+impl<H: Hash, W: io::Write> io::Write for HashWriter<H, W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.engine.input(&buf[..written]);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Writes all of `data` to `writer`, returning the hash of the data along
+/// with the writer. Partial writes are retried as for `Write::write_all`, and
+/// only the bytes which the writer accepted are hashed.
+pub fn writer_hash<H: Hash, W: io::Write>(writer: W, data: &[u8]) -> io::Result<(H, W)> {
+    let mut writer = HashWriter::tee(writer);
+    io::Write::write_all(&mut writer, data)?;
+    Ok(writer.into_parts())
+}
+
+/// Size of the buffer `hash_file` reads files in
+pub const DEFAULT_BUFFER_SIZE: usize = 64 * 1024;
+
+/// Hashes the contents of the file at `path`, reading it in chunks of
+/// `DEFAULT_BUFFER_SIZE` bytes
+pub fn hash_file<H: Hash, P: AsRef<Path>>(path: P) -> io::Result<H> {
+    hash_file_with_buffer_size(path, DEFAULT_BUFFER_SIZE)
+}
+
+/// Hashes the contents of the file at `path`, reading it in chunks of
+/// `buffer_size` bytes
+///
+/// Panics if `buffer_size` is zero.
+pub fn hash_file_with_buffer_size<H: Hash, P: AsRef<Path>>(path: P, buffer_size: usize) -> io::Result<H> {
+    assert!(buffer_size > 0, "buffer size must not be zero");
+
+    let mut file = File::open(path)?;
+    let mut buf = vec![0; buffer_size];
+    let mut engine = H::engine();
+    loop {
+        match io::Read::read(&mut file, &mut buf) {
+            Ok(0) => return Ok(H::from_engine(engine)),
+            Ok(n) => engine.input(&buf[..n]),
+            Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// A reader which hashes all data read through it, so that data can be
+/// checked against a known hash, such as that of a downloaded block, without
+/// buffering all of it
+pub struct HashVerifiedReader<R: io::Read, H: Hash> {
+    inner: R,
+    engine: H::Engine,
+    expected: H,
+}
+
+impl<R: io::Read, H: Hash> HashVerifiedReader<R, H> {
+    /// Construct a reader which checks the data read from `inner` against
+    /// the `expected` hash
+    pub fn new(inner: R, expected: H) -> HashVerifiedReader<R, H> {
+        HashVerifiedReader {
+            inner,
+            engine: H::engine(),
+            expected,
+        }
+    }
+
+    /// Gets a reference to the inner reader
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Gets the inner reader back
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// Checks the hash of all data read so far against the expected hash.
+    /// This should be called once the inner reader has returned EOF, since
+    /// until then only a prefix of the data has been hashed.
+    pub fn verify(&self) -> Result<(), Error> {
+        if H::from_engine(self.engine.clone()) == self.expected {
+            Ok(())
+        } else {
+            Err(Error::HashMismatch)
+        }
+    }
+}
+
+impl<R: io::Read, H: Hash> io::Read for HashVerifiedReader<R, H> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.engine.input(&buf[..read]);
+        Ok(read)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{self, Read, Write};
+
+    use super::{hash_file, hash_file_with_buffer_size, writer_hash, HashVerifiedReader, HashWriter};
+    use {sha256, sha256d, sha512, Error, Hash};
+
+    /// Writer which only ever accepts a few bytes at a time
+    struct Trickle(Vec<u8>);
+
+    impl Write for Trickle {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            let len = ::std::cmp::min(buf.len(), 3);
+            self.0.extend_from_slice(&buf[..len]);
+            Ok(len)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn hash_writer() {
+        let data = b"The quick brown fox jumps over the lazy dog";
+
+        let mut writer = HashWriter::<sha256::Hash>::new();
+        writer.write_all(&data[..10]).expect("write");
+        writer.write_all(&data[10..]).expect("write");
+        assert_eq!(writer.finalize(), sha256::Hash::hash(data));
+
+        let mut writer = HashWriter::<sha512::Hash, _>::tee(Vec::new());
+        writer.write_all(data).expect("write");
+        let (hash, inner) = writer.into_parts();
+        assert_eq!(hash, sha512::Hash::hash(data));
+        assert_eq!(&inner[..], &data[..]);
+    }
+
+    #[test]
+    fn hash_writer_partial_writes() {
+        let data = b"The quick brown fox jumps over the lazy dog";
+
+        let mut writer = HashWriter::<sha256::Hash, _>::tee(Trickle(vec![]));
+        assert_eq!(writer.write(data).expect("write"), 3);
+        writer.write_all(&data[3..]).expect("write");
+        let (hash, inner) = writer.into_parts();
+        assert_eq!(hash, sha256::Hash::hash(data));
+        assert_eq!(&inner.0[..], &data[..]);
+    }
+
+    #[test]
+    fn hash_writer_copy() {
+        // Hash a stream while passing it through, e.g. to check a download
+        let data: Vec<u8> = (0..10_000u32).map(|i| i as u8).collect();
+
+        let mut writer = HashWriter::<sha256::Hash, _>::tee(Trickle(vec![]));
+        let copied = io::copy(&mut &data[..], &mut writer).expect("copy");
+        assert_eq!(copied, data.len() as u64);
+        let (hash, inner) = writer.into_parts();
+        assert_eq!(hash, sha256::Hash::hash(&data));
+        assert_eq!(inner.0, data);
+    }
+
+    #[test]
+    fn writer_hash_partial_writes() {
+        let data: Vec<u8> = (0..1000u32).map(|i| i as u8).collect();
+
+        let (hash, inner) = writer_hash::<sha256::Hash, _>(Trickle(vec![]), &data).expect("write");
+        assert_eq!(hash, sha256::Hash::hash(&data));
+        assert_eq!(inner.0, data);
+
+        // A writer which stops accepting data fails the write
+        let mut buf = [0; 10];
+        let err = writer_hash::<sha256::Hash, _>(&mut buf[..], &data).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::WriteZero);
+    }
+
+    #[test]
+    fn hash_verified_reader() {
+        let data: Vec<u8> = (0..10_000u32).map(|i| i as u8).collect();
+        let expected = sha256d::Hash::hash(&data);
+
+        let mut reader = HashVerifiedReader::new(&data[..], expected);
+        let mut buf = [0; 1000];
+        assert_eq!(reader.read(&mut buf).expect("read"), 1000);
+        // Only a prefix has been read
+        assert_eq!(reader.verify(), Err(Error::HashMismatch));
+        let mut rest = vec![];
+        reader.read_to_end(&mut rest).expect("read");
+        assert_eq!(rest.len(), 9000);
+        assert_eq!(reader.verify(), Ok(()));
+        assert!(reader.into_inner().is_empty());
+
+        let mut reader = HashVerifiedReader::new(&data[1..], expected);
+        io::copy(&mut reader, &mut io::sink()).expect("copy");
+        assert_eq!(reader.verify(), Err(Error::HashMismatch));
+    }
+
+    #[test]
+    fn hash_files() {
+        use std::{env, fs, process};
+
+        let data: Vec<u8> = (0..200_000u32).map(|i| i as u8).collect();
+        let path = env::temp_dir().join(format!("bitcoin_hashes_hash_file_{}", process::id()));
+        fs::write(&path, &data).expect("write file");
+
+        let hash = hash_file::<sha256::Hash, _>(&path);
+        let small_buffer = hash_file_with_buffer_size::<sha256::Hash, _>(&path, 1000);
+        let empty = fs::write(&path, []).and_then(|_| hash_file::<sha512::Hash, _>(&path));
+        fs::remove_file(&path).expect("remove file");
+
+        assert_eq!(hash.expect("hash file"), sha256::Hash::hash(&data));
+        assert_eq!(small_buffer.expect("hash file"), sha256::Hash::hash(&data));
+        assert_eq!(empty.expect("hash file"), sha512::Hash::hash(&[]));
+
+        let err = hash_file::<sha256::Hash, _>(&path).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    }
+}
@@ -0,0 +1,193 @@
+// Bitcoin Hashes Library
+// Written in 2019 by
+//   The rust-bitcoin developers
+//
+// To the extent possible under law, the author(s) have dedicated all
+// copyright and related and neighboring rights to this software to
+// the public domain worldwide. This software is distributed without
+// any warranty.
+//
+// You should have received a copy of the CC0 Public Domain Dedication
+// along with this software.
+// If not, see <http://creativecommons.org/publicdomain/zero/1.0/>.
+//
+
+//! # Keccak
+//!
+//! The Keccak-f[1600] permutation and the sponge construction from FIPS 202,
+//! shared by the SHA3, SHAKE and original Keccak engines.
+
+use byteorder::{ByteOrder, LittleEndian};
+
+/// Largest rate of any supported instance (SHAKE128), in bytes
+pub const MAX_RATE: usize = 168;
+
+/// Padding suffix of the original Keccak functions, which predate the
+/// FIPS 202 domain separation
+pub const KECCAK_DOMAIN: u8 = 0x01;
+/// Domain separation suffix of the fixed-length SHA3 functions
+pub const SHA3_DOMAIN: u8 = 0x06;
+/// Domain separation suffix of the SHAKE extendable-output functions
+pub const SHAKE_DOMAIN: u8 = 0x1f;
+
+const RC: [u64; 24] = [
+    0x0000000000000001, 0x0000000000008082, 0x800000000000808a, 0x8000000080008000,
+    0x000000000000808b, 0x0000000080000001, 0x8000000080008081, 0x8000000000008009,
+    0x000000000000008a, 0x0000000000000088, 0x0000000080008009, 0x000000008000000a,
+    0x000000008000808b, 0x800000000000008b, 0x8000000000008089, 0x8000000000008003,
+    0x8000000000008002, 0x8000000000000080, 0x000000000000800a, 0x800000008000000a,
+    0x8000000080008081, 0x8000000000008080, 0x0000000080000001, 0x8000000080008008,
+];
+
+// Rotation offsets and destination lanes of the combined rho and pi steps,
+// in the order in which lanes are visited starting from lane 1
+const RHO: [u32; 24] = [
+     1,  3,  6, 10, 15, 21, 28, 36, 45, 55,  2, 14,
+    27, 41, 56,  8, 25, 43, 62, 18, 39, 61, 20, 44,
+];
+const PI: [usize; 24] = [
+    10,  7, 11, 17, 18,  3,  5, 16,  8, 21, 24,  4,
+    15, 23, 19, 13, 12,  2, 20, 14, 22,  9,  6,  1,
+];
+
+/// The Keccak-f[1600] permutation
+fn keccak_f(a: &mut [u64; 25]) {
+    for rc in RC.iter() {
+        // Theta
+        let mut c = [0u64; 5];
+        for (x, c_x) in c.iter_mut().enumerate() {
+            *c_x = a[x] ^ a[x + 5] ^ a[x + 10] ^ a[x + 15] ^ a[x + 20];
+        }
+        for x in 0..5 {
+            let d = c[(x + 4) % 5] ^ c[(x + 1) % 5].rotate_left(1);
+            for y in 0..5 {
+                a[5 * y + x] ^= d;
+            }
+        }
+
+        // Rho and pi
+        let mut last = a[1];
+        for (&j, &r) in PI.iter().zip(RHO.iter()) {
+            let tmp = a[j];
+            a[j] = last.rotate_left(r);
+            last = tmp;
+        }
+
+        // Chi
+        for y in 0..5 {
+            let mut row = [0u64; 5];
+            row.copy_from_slice(&a[5 * y..5 * y + 5]);
+            for (x, a_x) in a[5 * y..5 * y + 5].iter_mut().enumerate() {
+                *a_x = row[x] ^ (!row[(x + 1) % 5] & row[(x + 2) % 5]);
+            }
+        }
+
+        // Iota
+        a[0] ^= *rc;
+    }
+}
+
+/// A Keccak sponge with a 1600-bit state and a configurable rate
+pub struct Sponge {
+    state: [u64; 25],
+    buffer: [u8; MAX_RATE],
+    rate: usize,
+    // While absorbing this is the total number of bytes input; once the
+    // sponge has been padded it is the offset into the current output block
+    length: usize,
+}
+
+impl Clone for Sponge {
+    fn clone(&self) -> Sponge {
+        Sponge {
+            state: self.state,
+            buffer: self.buffer,
+            rate: self.rate,
+            length: self.length,
+        }
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl ::zeroize::Zeroize for Sponge {
+    fn zeroize(&mut self) {
+        ::zeroize::Zeroize::zeroize(&mut self.state);
+        ::zeroize::Zeroize::zeroize(&mut self.buffer[..]);
+        ::zeroize::Zeroize::zeroize(&mut self.length);
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl Drop for Sponge {
+    fn drop(&mut self) {
+        ::zeroize::Zeroize::zeroize(self);
+    }
+}
+
+impl Sponge {
+    /// Constructs an empty sponge absorbing `rate` bytes per permutation
+    pub fn new(rate: usize) -> Sponge {
+        debug_assert!(rate <= MAX_RATE);
+        Sponge {
+            state: [0; 25],
+            buffer: [0; MAX_RATE],
+            rate,
+            length: 0,
+        }
+    }
+
+    /// Serializes the full permutation state
+    pub fn midstate(&self) -> [u8; 200] {
+        let mut ret = [0; 200];
+        LittleEndian::write_u64_into(&self.state, &mut ret);
+        ret
+    }
+
+    fn absorb_block(&mut self) {
+        for (i, lane) in self.state.iter_mut().take(self.rate / 8).enumerate() {
+            *lane ^= LittleEndian::read_u64(&self.buffer[8 * i..8 * i + 8]);
+        }
+        keccak_f(&mut self.state);
+    }
+
+    /// Absorbs data into the sponge
+    pub fn absorb(&mut self, mut inp: &[u8]) {
+        while !inp.is_empty() {
+            let buf_idx = self.length % self.rate;
+            let write_len = ::core::cmp::min(self.rate - buf_idx, inp.len());
+
+            self.buffer[buf_idx..buf_idx + write_len].copy_from_slice(&inp[..write_len]);
+            self.length += write_len;
+            if buf_idx + write_len == self.rate {
+                self.absorb_block();
+            }
+
+            inp = &inp[write_len..];
+        }
+    }
+
+    /// Pads the absorbed data with the given domain separation suffix and
+    /// switches the sponge to squeezing
+    pub fn pad(&mut self, domain: u8) {
+        let buf_idx = self.length % self.rate;
+        for b in self.buffer[buf_idx..self.rate].iter_mut() {
+            *b = 0;
+        }
+        self.buffer[buf_idx] ^= domain;
+        self.buffer[self.rate - 1] ^= 0x80;
+        self.absorb_block();
+        self.length = 0;
+    }
+
+    /// Squeezes output out of a padded sponge
+    pub fn squeeze(&mut self, out: &mut [u8]) {
+        for byte in out.iter_mut() {
+            if self.length == self.rate {
+                keccak_f(&mut self.state);
+                self.length = 0;
+            }
+            *byte = (self.state[self.length / 8] >> (8 * (self.length % 8))) as u8;
+            self.length += 1;
+        }
+    }
+}
@@ -0,0 +1,225 @@
+// Bitcoin Hashes Library
+// Written in 2019 by
+//   The rust-bitcoin developers
+//
+// To the extent possible under law, the author(s) have dedicated all
+// copyright and related and neighboring rights to this software to
+// the public domain worldwide. This software is distributed without
+// any warranty.
+//
+// You should have received a copy of the CC0 Public Domain Dedication
+// along with this software.
+// If not, see <http://creativecommons.org/publicdomain/zero/1.0/>.
+//
+
+//! # Keccak-256
+//!
+//! The original Keccak-256 function, as used by Ethereum. It differs from
+//! the FIPS 202 SHA3-256 function only in the domain separation bits of its
+//! padding, so the two give unrelated outputs.
+
+use keccak::{self, Sponge};
+use HashEngine as EngineTrait;
+use Hash as HashTrait;
+use Error;
+
+const BLOCK_SIZE: usize = 136;
+
+/// Engine to compute Keccak-256 hash function
+#[derive(Clone)]
+pub struct HashEngine(Sponge);
+
+write_impl!(HashEngine);
+extend_impl!(HashEngine, Hash);
+engine_default_impl!(HashEngine, Hash);
+zeroize_impl!(HashEngine);
+
+// The sponge zeroizes itself when dropped
+#[cfg(feature = "zeroize")]
+impl ::zeroize::ZeroizeOnDrop for HashEngine {}
+
+impl EngineTrait for HashEngine {
+    type MidState = [u8; 200];
+
+    fn midstate(&self) -> [u8; 200] {
+        self.0.midstate()
+    }
+
+    const BLOCK_SIZE: usize = BLOCK_SIZE;
+
+    fn input(&mut self, data: &[u8]) {
+        self.0.absorb(data)
+    }
+}
+
+/// Output of the Keccak-256 hash function
+#[derive(Copy, Clone, PartialEq, Eq, Default, PartialOrd, Ord, Hash)]
+pub struct Hash([u8; 32]);
+
+hex_fmt_impl!(Debug, Hash);
+hex_fmt_impl!(Display, Hash);
+hex_fmt_impl!(LowerHex, Hash);
+hex_fmt_impl!(UpperHex, Hash);
+index_impl!(Hash);
+serde_impl!(Hash, 32);
+borrow_slice_impl!(Hash);
+from_str_impl!(Hash);
+try_from_impl!(Hash);
+from_bytes_impl!(Hash, 32);
+into_iter_impl!(Hash);
+eq_bytes_impl!(Hash, 32);
+deref_impl!(Hash, 32);
+inner_ref_impl!(Hash, 32);
+bitops_impl!(Hash);
+ct_eq_impl!(Hash);
+zeroize_impl!(Hash);
+rand_impl!(Hash);
+proptest_impl!(Hash);
+quickcheck_impl!(Hash);
+
+impl HashTrait for Hash {
+    type Engine = HashEngine;
+    type Inner = [u8; 32];
+
+    fn engine() -> HashEngine {
+        HashEngine(Sponge::new(BLOCK_SIZE))
+    }
+
+    fn from_engine(mut e: HashEngine) -> Hash {
+        let mut ret = [0; 32];
+        e.0.pad(keccak::KECCAK_DOMAIN);
+        e.0.squeeze(&mut ret);
+        Hash(ret)
+    }
+
+    const LEN: usize = 32;
+
+    fn from_slice(sl: &[u8]) -> Result<Hash, Error> {
+        if sl.len() != 32 {
+            Err(Error::InvalidLength(Self::LEN, sl.len()))
+        } else {
+            let mut ret = [0; 32];
+            ret.copy_from_slice(sl);
+            Ok(Hash(ret))
+        }
+    }
+
+    fn from_byte_array(bytes: Self::Inner) -> Self {
+        Hash(bytes)
+    }
+
+    fn to_byte_array(self) -> Self::Inner {
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use keccak256;
+    use hex::{FromHex, ToHex};
+    use Hash;
+
+    #[derive(Clone)]
+    struct Test {
+        input: Vec<u8>,
+        output_str: &'static str,
+    }
+
+    #[test]
+    fn test() {
+        let tests = vec![
+            // Test vectors computed with the `sha3` crate's Keccak functions;
+            // the first is the well-known Ethereum hash of empty input
+            Test {
+                input: vec![],
+                output_str: "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470",
+            },
+            Test {
+                input: b"abc".to_vec(),
+                output_str: "4e03657aea45a94fc7d47ba826c8d667c0d1e6e33a64a036ec44f58fa12d6c45",
+            },
+            Test {
+                input: b"The quick brown fox jumps over the lazy dog".to_vec(),
+                output_str: "4d741b6f1eb29cb2a9b9911c82f56fa8d73b04959d3d9d222895df6c0b28aa15",
+            },
+            Test {
+                input: (0..200).collect(),
+                output_str: "bfb0aa97863e797943cf7c33bb7e880bb4543f3d2703c0923c6901c2af57b890",
+            },
+        ];
+
+        for test in tests {
+            // Hash through high-level API, check hex encoding/decoding
+            let hash = keccak256::Hash::hash(&test.input);
+            assert_eq!(hash, keccak256::Hash::from_hex(test.output_str).expect("parse hex"));
+            assert_eq!(&hash.to_hex(), &test.output_str);
+
+            // Hash through engine, checking that we can input byte by byte
+            let mut engine = keccak256::Hash::engine();
+            for ch in &test.input {
+                engine.write_all(&[*ch]).expect("write to engine");
+            }
+            let manual_hash = keccak256::Hash::from_engine(engine);
+            assert_eq!(hash, manual_hash);
+        }
+    }
+
+    #[cfg(feature="serde")]
+    #[test]
+    fn keccak256_serde() {
+        use serde_test::{Configure, Token, assert_tokens};
+
+        static HASH_BYTES: [u8; 32] = [
+            0x4e, 0x03, 0x65, 0x7a, 0xea, 0x45, 0xa9, 0x4f,
+            0xc7, 0xd4, 0x7b, 0xa8, 0x26, 0xc8, 0xd6, 0x67,
+            0xc0, 0xd1, 0xe6, 0xe3, 0x3a, 0x64, 0xa0, 0x36,
+            0xec, 0x44, 0xf5, 0x8f, 0xa1, 0x2d, 0x6c, 0x45,
+        ];
+
+        let hash = keccak256::Hash::from_slice(&HASH_BYTES).expect("right number of bytes");
+        assert_tokens(&hash.compact(), &[Token::BorrowedBytes(&HASH_BYTES[..])]);
+        assert_tokens(&hash.readable(), &[Token::Str("4e03657aea45a94fc7d47ba826c8d667c0d1e6e33a64a036ec44f58fa12d6c45")]);
+    }
+}
+
+#[cfg(all(test, feature="unstable"))]
+mod benches {
+    use std::io::Write;
+    use test::Bencher;
+
+    use keccak256;
+    use Hash;
+
+    #[bench]
+    pub fn keccak256_10(bh: & mut Bencher) {
+        let mut engine = keccak256::Hash::engine();
+        let bytes = [1u8; 10];
+        bh.iter( || {
+            engine.write_all(&bytes).expect("write");
+        });
+        bh.bytes = bytes.len() as u64;
+    }
+
+    #[bench]
+    pub fn keccak256_1k(bh: & mut Bencher) {
+        let mut engine = keccak256::Hash::engine();
+        let bytes = [1u8; 1024];
+        bh.iter( || {
+            engine.write_all(&bytes).expect("write");
+        });
+        bh.bytes = bytes.len() as u64;
+    }
+
+    #[bench]
+    pub fn keccak256_64k(bh: & mut Bencher) {
+        let mut engine = keccak256::Hash::engine();
+        let bytes = [1u8; 65536];
+        bh.iter( || {
+            engine.write_all(&bytes).expect("write");
+        });
+        bh.bytes = bytes.len() as u64;
+    }
+
+}
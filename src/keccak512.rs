@@ -0,0 +1,281 @@
+// Bitcoin Hashes Library
+// Written in 2019 by
+//   The rust-bitcoin developers
+//
+// To the extent possible under law, the author(s) have dedicated all
+// copyright and related and neighboring rights to this software to
+// the public domain worldwide. This software is distributed without
+// any warranty.
+//
+// You should have received a copy of the CC0 Public Domain Dedication
+// along with this software.
+// If not, see <http://creativecommons.org/publicdomain/zero/1.0/>.
+//
+
+//! # Keccak-512
+//!
+//! The original Keccak-512 function, which differs from the FIPS 202
+//! SHA3-512 function only in the domain separation bits of its padding.
+
+use core::hash;
+
+use keccak::{self, Sponge};
+use HashEngine as EngineTrait;
+use Hash as HashTrait;
+use Error;
+
+const BLOCK_SIZE: usize = 72;
+
+/// Engine to compute Keccak-512 hash function
+#[derive(Clone)]
+pub struct HashEngine(Sponge);
+
+write_impl!(HashEngine);
+extend_impl!(HashEngine, Hash);
+engine_default_impl!(HashEngine, Hash);
+zeroize_impl!(HashEngine);
+
+// The sponge zeroizes itself when dropped
+#[cfg(feature = "zeroize")]
+impl ::zeroize::ZeroizeOnDrop for HashEngine {}
+
+impl EngineTrait for HashEngine {
+    type MidState = [u8; 200];
+
+    fn midstate(&self) -> [u8; 200] {
+        self.0.midstate()
+    }
+
+    const BLOCK_SIZE: usize = BLOCK_SIZE;
+
+    fn input(&mut self, data: &[u8]) {
+        self.0.absorb(data)
+    }
+}
+
+/// Output of the Keccak-512 hash function
+pub struct Hash([u8; 64]);
+
+impl Copy for Hash {}
+
+impl Clone for Hash {
+    fn clone(&self) -> Hash {
+        *self
+    }
+}
+
+impl PartialEq for Hash {
+    fn eq(&self, other: &Hash) -> bool {
+        self.0[..] == other.0[..]
+    }
+}
+
+impl Eq for Hash {}
+
+impl Default for Hash {
+    fn default() -> Hash {
+        Hash([0; 64])
+    }
+}
+
+use core::cmp::Ordering;
+
+impl PartialOrd for Hash {
+    fn partial_cmp(&self, other: &Hash) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Hash {
+    fn cmp(&self, other: &Hash) -> Ordering {
+        self.0[..].cmp(&other.0[..])
+    }
+}
+
+impl hash::Hash for Hash {
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        self.0[..].hash(state)
+    }
+}
+
+hex_fmt_impl!(Debug, Hash);
+hex_fmt_impl!(Display, Hash);
+hex_fmt_impl!(LowerHex, Hash);
+hex_fmt_impl!(UpperHex, Hash);
+index_impl!(Hash);
+serde_impl!(Hash, 64);
+borrow_slice_impl!(Hash);
+from_str_impl!(Hash);
+try_from_impl!(Hash);
+from_bytes_impl!(Hash, 64);
+into_iter_impl!(Hash);
+eq_bytes_impl!(Hash, 64);
+deref_impl!(Hash, 64);
+inner_ref_impl!(Hash, 64);
+bitops_impl!(Hash);
+ct_eq_impl!(Hash);
+zeroize_impl!(Hash);
+rand_impl!(Hash);
+proptest_impl!(Hash);
+quickcheck_impl!(Hash);
+
+impl HashTrait for Hash {
+    type Engine = HashEngine;
+    type Inner = [u8; 64];
+
+    fn engine() -> HashEngine {
+        HashEngine(Sponge::new(BLOCK_SIZE))
+    }
+
+    fn from_engine(mut e: HashEngine) -> Hash {
+        let mut ret = [0; 64];
+        e.0.pad(keccak::KECCAK_DOMAIN);
+        e.0.squeeze(&mut ret);
+        Hash(ret)
+    }
+
+    const LEN: usize = 64;
+
+    fn from_slice(sl: &[u8]) -> Result<Hash, Error> {
+        if sl.len() != 64 {
+            Err(Error::InvalidLength(Self::LEN, sl.len()))
+        } else {
+            let mut ret = [0; 64];
+            ret.copy_from_slice(sl);
+            Ok(Hash(ret))
+        }
+    }
+
+    fn from_byte_array(bytes: Self::Inner) -> Self {
+        Hash(bytes)
+    }
+
+    fn to_byte_array(self) -> Self::Inner {
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use keccak512;
+    use hex::{FromHex, ToHex};
+    use Hash;
+
+    #[derive(Clone)]
+    struct Test {
+        input: Vec<u8>,
+        output_str: &'static str,
+    }
+
+    #[test]
+    fn test() {
+        let tests = vec![
+            // Test vectors computed with the `sha3` crate's Keccak functions;
+            // the first is the well-known Ethereum hash of empty input
+            Test {
+                input: vec![],
+                output_str: "0eab42de4c3ceb9235fc91acffe746b29c29a8c366b7c60e4e67c466f36a4304\
+                             c00fa9caf9d87976ba469bcbe06713b435f091ef2769fb160cdab33d3670680e",
+            },
+            Test {
+                input: b"abc".to_vec(),
+                output_str: "18587dc2ea106b9a1563e32b3312421ca164c7f1f07bc922a9c83d77cea3a1e5\
+                             d0c69910739025372dc14ac9642629379540c17e2a65b19d77aa511a9d00bb96",
+            },
+            Test {
+                input: b"The quick brown fox jumps over the lazy dog".to_vec(),
+                output_str: "d135bb84d0439dbac432247ee573a23ea7d3c9deb2a968eb31d47c4fb45f1ef4\
+                             422d6c531b5b9bd6f449ebcc449ea94d0a8f05f62130fda612da53c79659f609",
+            },
+            Test {
+                input: (0..200).collect(),
+                output_str: "f452d81b62b961f8023f8228cbe780379b36c49ddcef29e0dffb01a930c2cc53\
+                             a694ed6ae3f0d224a2f1be55814a81841b90d56bcdf4a48a633f258a32dc14fc",
+            },
+        ];
+
+        for test in tests {
+            // Hash through high-level API, check hex encoding/decoding
+            let hash = keccak512::Hash::hash(&test.input);
+            assert_eq!(hash, keccak512::Hash::from_hex(test.output_str).expect("parse hex"));
+            assert_eq!(&hash.to_hex(), &test.output_str);
+
+            // Hash through engine, checking that we can input byte by byte
+            let mut engine = keccak512::Hash::engine();
+            for ch in &test.input {
+                engine.write_all(&[*ch]).expect("write to engine");
+            }
+            let manual_hash = keccak512::Hash::from_engine(engine);
+            assert_eq!(hash, manual_hash);
+        }
+    }
+
+    #[cfg(feature="serde")]
+    #[test]
+    fn keccak512_serde() {
+        use serde_test::{Configure, Token, assert_tokens};
+
+        static HASH_BYTES: [u8; 64] = [
+            0x18, 0x58, 0x7d, 0xc2, 0xea, 0x10, 0x6b, 0x9a,
+            0x15, 0x63, 0xe3, 0x2b, 0x33, 0x12, 0x42, 0x1c,
+            0xa1, 0x64, 0xc7, 0xf1, 0xf0, 0x7b, 0xc9, 0x22,
+            0xa9, 0xc8, 0x3d, 0x77, 0xce, 0xa3, 0xa1, 0xe5,
+            0xd0, 0xc6, 0x99, 0x10, 0x73, 0x90, 0x25, 0x37,
+            0x2d, 0xc1, 0x4a, 0xc9, 0x64, 0x26, 0x29, 0x37,
+            0x95, 0x40, 0xc1, 0x7e, 0x2a, 0x65, 0xb1, 0x9d,
+            0x77, 0xaa, 0x51, 0x1a, 0x9d, 0x00, 0xbb, 0x96,
+        ];
+
+        let hash = keccak512::Hash::from_slice(&HASH_BYTES).expect("right number of bytes");
+        assert_tokens(&hash.compact(), &[Token::BorrowedBytes(&HASH_BYTES[..])]);
+        assert_tokens(
+            &hash.readable(),
+            &[Token::Str(
+                "18587dc2ea106b9a1563e32b3312421ca164c7f1f07bc922a9c83d77cea3a1e5\
+                 d0c69910739025372dc14ac9642629379540c17e2a65b19d77aa511a9d00bb96"
+            )],
+        );
+    }
+}
+
+#[cfg(all(test, feature="unstable"))]
+mod benches {
+    use std::io::Write;
+    use test::Bencher;
+
+    use keccak512;
+    use Hash;
+
+    #[bench]
+    pub fn keccak512_10(bh: & mut Bencher) {
+        let mut engine = keccak512::Hash::engine();
+        let bytes = [1u8; 10];
+        bh.iter( || {
+            engine.write_all(&bytes).expect("write");
+        });
+        bh.bytes = bytes.len() as u64;
+    }
+
+    #[bench]
+    pub fn keccak512_1k(bh: & mut Bencher) {
+        let mut engine = keccak512::Hash::engine();
+        let bytes = [1u8; 1024];
+        bh.iter( || {
+            engine.write_all(&bytes).expect("write");
+        });
+        bh.bytes = bytes.len() as u64;
+    }
+
+    #[bench]
+    pub fn keccak512_64k(bh: & mut Bencher) {
+        let mut engine = keccak512::Hash::engine();
+        let bytes = [1u8; 65536];
+        bh.iter( || {
+            engine.write_all(&bytes).expect("write");
+        });
+        bh.bytes = bytes.len() as u64;
+    }
+
+}
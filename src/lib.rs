@@ -14,10 +14,39 @@
 
 //! # Rust Hashes Library
 //!
-//! This is a simple, no-dependency library which implements the hash functions
-//! needed by Bitcoin. These are SHA256, SHA256d, and RIPEMD160. As an ancillary
-//! thing, it exposes hexadecimal serialization and deserialization, since these
-//! are needed to display hashes anway.
+//! This is a library which implements the hash functions needed by Bitcoin
+//! and related projects. These are SHA1, the SHA2 family (SHA224, SHA256,
+//! SHA256d, SHA384, SHA512 and its truncated variants, and tagged SHA256 and
+//! SHA512 hashes), SHA3 and Keccak, BLAKE2b and BLAKE3, RIPEMD160, Hash160,
+//! Groestl512 and SipHash, along with HMAC, HKDF and PBKDF2 built on them. As
+//! an ancillary thing, it exposes hexadecimal serialization and
+//! deserialization, since these are needed to display hashes anway.
+//!
+//! The only required dependency is `byteorder`, which is used to manage
+//! endianness. Support for `serde`, `subtle`, `zeroize`, `rand`, `proptest`
+//! and `quickcheck` is available behind features of the same names.
+//!
+//! The library is `no_std` without the default `std` feature. The `alloc`
+//! feature then restores the parts which need an allocator, such as `ToHex`,
+//! the `merkle` module and decoding hex into a `Vec`; it requires rustc 1.36.
+//! Only `std` provides the `io` and `dynamic` modules, the `io::Write`
+//! implementations of engines and the runtime detection of CPU features.
+//!
+//! The `proptest` and `quickcheck` features implement the `Arbitrary` traits
+//! of those crates for the hashes, for property-based testing:
+//!
+//! ```rust
+//! # #[cfg(feature = "quickcheck")] {
+//! extern crate quickcheck;
+//! use bitcoin_hashes::{sha256, sha256d, Hash};
+//!
+//! fn double_sha256(hash: sha256::Hash) -> bool {
+//!     let once = sha256::Hash::hash(&hash[..]);
+//!     sha256d::Hash::hash(&hash[..])[..] == sha256::Hash::hash(&once[..])[..]
+//! }
+//! quickcheck::quickcheck(double_sha256 as fn(sha256::Hash) -> bool);
+//! # }
+//! ```
 //!
 
 // Coding conventions
@@ -27,35 +56,145 @@
 #![deny(unused_mut)]
 #![deny(missing_docs)]
 
+#![cfg_attr(all(not(test), not(feature = "std")), no_std)]
 #![cfg_attr(all(test, feature = "unstable"), feature(test))]
 #[cfg(all(test, feature = "unstable"))] extern crate test;
 
-#[cfg(feature="serde")] extern crate serde;
+#[cfg(any(test, feature = "std"))] extern crate core;
+#[cfg(all(feature = "alloc", not(feature = "std")))] #[macro_use] extern crate alloc;
+
+#[cfg(feature="serde")] pub extern crate serde;
 #[cfg(all(test,feature="serde"))] extern crate serde_test;
+#[cfg(feature="rand")] extern crate rand;
+#[cfg(feature="proptest")] extern crate proptest;
+#[cfg(feature="quickcheck")] extern crate quickcheck;
+// quickcheck's `shrink` returns a `Box`, so it needs `std` in any case
+#[cfg(all(feature="quickcheck", not(feature = "std"), not(test)))] extern crate std;
+#[cfg(feature="subtle")] extern crate subtle;
+#[cfg(feature="zeroize")] extern crate zeroize;
 extern crate byteorder;
 
 #[macro_use] mod util;
 #[macro_use] mod serde_macros;
+#[macro_use] mod newtype;
 pub mod error;
 pub mod hex;
+pub mod blake2b256;
+pub mod blake2b512;
+pub mod blake3;
+pub mod checksum;
+pub mod ext;
+pub mod compat;
+#[cfg(feature = "std")] pub mod dynamic;
+pub mod groestl512;
 pub mod hash160;
+pub mod hkdf;
 pub mod hmac;
+#[cfg(feature = "std")] pub mod io;
+#[cfg(feature = "alloc")] pub mod merkle;
+pub mod multihasher;
+pub mod pbkdf2;
+#[cfg(all(feature = "sha-ni", target_arch = "x86_64"))] mod sha256_ni;
+#[cfg(all(feature = "std", target_arch = "x86_64", not(feature = "fuzztarget")))] mod sha256_avx2;
+mod keccak;
+pub mod keccak256;
+pub mod keccak512;
 pub mod ripemd160;
 pub mod sha1;
+pub mod sha224;
 pub mod sha256;
+pub mod sha384;
 pub mod sha512;
+pub mod sha512_224;
+pub mod sha512_256;
 pub mod sha256d;
+pub mod sha256t;
+pub mod sha512t;
+pub mod sha3_256;
+pub mod sha3_512;
+pub mod siphash;
+pub mod taproot;
+pub mod xof;
+mod uint;
 pub mod cmp;
 
-use std::{borrow, fmt, hash, io, ops};
+use core::{borrow, fmt, hash, ops, str};
+
+#[cfg(feature = "std")]
+use std::io::Write as EngineWrite;
+
+/// Stands in for the `io::Write` bound on engines without the `std` feature
+#[cfg(not(feature = "std"))]
+#[doc(hidden)]
+pub trait EngineWrite {}
+
+#[cfg(not(feature = "std"))]
+impl<T> EngineWrite for T {}
+
+// The allocating types, which `no_std` builds with `alloc` cannot take
+// from the standard prelude
+#[cfg(feature = "alloc")]
+mod prelude {
+    #[cfg(feature = "std")]
+    pub use std::{string::String, vec::Vec};
+    #[cfg(not(feature = "std"))]
+    pub use alloc::{string::String, vec::Vec};
+}
+
+// Paths used by the exported macros, which must work in `no_std` crates
+#[doc(hidden)]
+pub mod _export {
+    pub use core::{borrow, convert, fmt, ops, str};
+}
 
 pub use hmac::{Hmac, HmacEngine};
 pub use error::Error;
 
-/// A hashing engine which bytes can be serialized into. It is expected
-/// to implement the `io::Write` trait, but to never return errors under
-/// any conditions.
-pub trait HashEngine: Clone + io::Write {
+/// A fixed-size array of bytes, as the `Inner` type of a hash is. This lets
+/// the provided methods of `Hash` build hashes of any length without
+/// allocating. It is implemented for byte arrays of up to 128 bytes, and of
+/// 256 and 512 bytes.
+pub trait ByteArray: Copy {
+    /// Returns an array whose bytes are all zero
+    fn zeroed() -> Self;
+
+    /// Returns the bytes of the array
+    fn as_bytes(&self) -> &[u8];
+
+    /// Returns the bytes of the array, which may be changed
+    fn as_mut_bytes(&mut self) -> &mut [u8];
+}
+
+macro_rules! byte_array_impl(
+    ($($len:expr),*) => ($(
+        impl ByteArray for [u8; $len] {
+            fn zeroed() -> [u8; $len] {
+                [0; $len]
+            }
+
+            fn as_bytes(&self) -> &[u8] {
+                &self[..]
+            }
+
+            fn as_mut_bytes(&mut self) -> &mut [u8] {
+                &mut self[..]
+            }
+        }
+    )*)
+);
+
+byte_array_impl!(0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22,
+    23, 24, 25, 26, 27, 28, 29, 30, 31, 32, 33, 34, 35, 36, 37, 38, 39, 40, 41, 42, 43, 44, 45,
+    46, 47, 48, 49, 50, 51, 52, 53, 54, 55, 56, 57, 58, 59, 60, 61, 62, 63, 64, 65, 66, 67, 68,
+    69, 70, 71, 72, 73, 74, 75, 76, 77, 78, 79, 80, 81, 82, 83, 84, 85, 86, 87, 88, 89, 90, 91,
+    92, 93, 94, 95, 96, 97, 98, 99, 100, 101, 102, 103, 104, 105, 106, 107, 108, 109, 110, 111,
+    112, 113, 114, 115, 116, 117, 118, 119, 120, 121, 122, 123, 124, 125, 126, 127, 128, 256, 512);
+
+/// A hashing engine which bytes can be serialized into. With the `std`
+/// feature, its `io::Write` implementation forwards to `input` and never
+/// returns errors under any conditions, so it can be used wherever a writer
+/// is expected.
+pub trait HashEngine: Clone + EngineWrite {
     /// Byte array representing the internal state of the hash engine
     type MidState;
 
@@ -66,14 +205,42 @@ pub trait HashEngine: Clone + io::Write {
     /// Length of the hash's internal block size, in bytes
     const BLOCK_SIZE: usize;
 
-    /// Add data to the hash engine without any error return type to deal with
+    /// Add data to the hash engine without any error return type to deal with.
+    /// With the `std` feature this defaults to `io::Write::write_all`, so an
+    /// engine which only implements `io::Write` need not implement it; the
+    /// engines of this crate implement it and forward `io::Write` to it.
+    #[cfg(feature = "std")]
     #[inline(always)]
     fn input(&mut self, data: &[u8]) {
         self.write_all(data).expect("hash returned error");
     }
+
+    /// Add data to the hash engine without any error return type to deal with
+    #[cfg(not(feature = "std"))]
+    fn input(&mut self, data: &[u8]);
+
+    /// Returns an independent copy of the engine in its current state, so
+    /// that several messages sharing a common prefix can be hashed without
+    /// rehashing the prefix for each of them
+    fn fork(&self) -> Self {
+        self.clone()
+    }
 }
 
 /// Trait which applies to hashes of all types
+///
+/// Note that the `PartialEq` implementations of hashes are *not* constant
+/// time. When comparing secret-dependent values such as MAC tags, use
+/// `verify`, `cmp::fixed_time_eq`, or `constant_time_eq` and
+/// `subtle::ConstantTimeEq::ct_eq` when the `subtle` feature is enabled.
+///
+/// With the `zeroize` feature, engines wipe their internal state when they
+/// are dropped. Hashes themselves are `Copy` and so cannot do this; they
+/// implement `Zeroize` and must be wiped explicitly.
+///
+/// Every hash also implements the standard library's `hash::Hash`, so it
+/// can be used as a `HashMap` key. Both traits have a `hash` function, so in
+/// generic code `H::hash` is ambiguous; write `<H as Hash>::hash` instead.
 pub trait Hash: Copy + Clone + PartialEq + Eq + Default + PartialOrd + Ord +
     hash::Hash + fmt::Debug + fmt::Display + fmt::LowerHex +
     ops::Index<ops::RangeFull, Output = [u8]> +
@@ -81,15 +248,15 @@ pub trait Hash: Copy + Clone + PartialEq + Eq + Default + PartialOrd + Ord +
     ops::Index<ops::RangeTo<usize>, Output = [u8]> +
     ops::Index<ops::Range<usize>, Output = [u8]> +
     ops::Index<usize, Output = u8> +
-    hex::ToHex + borrow::Borrow<[u8]>
+    borrow::Borrow<[u8]>
 {
-    /// A hashing engine which bytes can be serialized into. It is expected
-    /// to implement the `io::Write` trait, and to never return errors under
-    /// any conditions.
+    /// A hashing engine which bytes can be serialized into. With the `std`
+    /// feature it also implements `io::Write`, which never returns errors
+    /// under any conditions.
     type Engine: HashEngine;
 
     /// The byte array that represents the hash internally
-    type Inner;
+    type Inner: ByteArray;
 
     /// Construct a new engine
     fn engine() -> Self::Engine;
@@ -103,20 +270,1057 @@ pub trait Hash: Copy + Clone + PartialEq + Eq + Default + PartialOrd + Ord +
     /// Copies a byte slice into a hash object
     fn from_slice(sl: &[u8]) -> Result<Self, Error>;
 
+    /// Constructs a hash from its bytes in their internal order, the order
+    /// in which indexing the hash yields them
+    fn from_byte_array(bytes: Self::Inner) -> Self {
+        Self::from_slice(bytes.as_bytes()).expect("right number of bytes")
+    }
+
+    /// Constructs a hash from its bytes in display order, which is the
+    /// reverse of the internal order for hashes with `DISPLAY_BACKWARD` set
+    fn from_display_byte_array(bytes: Self::Inner) -> Self {
+        let mut bytes = bytes;
+        if Self::DISPLAY_BACKWARD {
+            bytes.as_mut_bytes().reverse();
+        }
+        Self::from_byte_array(bytes)
+    }
+
     /// Hashes some bytes
     fn hash(data: &[u8]) -> Self {
-        use std::io::Write;
+        let mut engine = Self::engine();
+        engine.input(data);
+        Self::from_engine(engine)
+    }
+
+    /// Hashes the concatenation of two byte slices, without allocating it
+    fn hash_two<A: AsRef<[u8]>, B: AsRef<[u8]>>(a: A, b: B) -> Self {
+        let mut engine = Self::engine();
+        engine.input(a.as_ref());
+        engine.input(b.as_ref());
+        Self::from_engine(engine)
+    }
 
+    /// Hashes the concatenation of three byte slices, without allocating
+    /// it. Use `hash_all` for more pieces.
+    fn hash_three<A: AsRef<[u8]>, B: AsRef<[u8]>, C: AsRef<[u8]>>(a: A, b: B, c: C) -> Self {
         let mut engine = Self::engine();
-        engine.write_all(data).unwrap();
+        engine.input(a.as_ref());
+        engine.input(b.as_ref());
+        engine.input(c.as_ref());
         Self::from_engine(engine)
     }
 
+    /// Hashes `data` and checks whether the result is this hash. The
+    /// comparison is constant time, so unlike `==` it is safe to use for
+    /// secret-dependent values such as MAC tags.
+    fn verify(&self, data: &[u8]) -> bool {
+        cmp::fixed_time_eq(&self[..], &<Self as Hash>::hash(data)[..])
+    }
+
+    /// Checks whether two hashes are equal in constant time, using `subtle`.
+    /// This is only available with the `subtle` feature, so that code which
+    /// must not leak timing fails to build rather than falling back to `==`.
+    /// It is not called `ct_eq` so as not to be ambiguous with
+    /// `subtle::ConstantTimeEq::ct_eq`, which returns a `subtle::Choice`.
+    #[cfg(feature = "subtle")]
+    fn constant_time_eq(&self, other: &Self) -> bool {
+        subtle::ConstantTimeEq::ct_eq(&self[..], &other[..]).into()
+    }
+
     /// Flag indicating whether user-visible serializations of this hash
     /// should be backward. For some reason Satoshi decided this should be
     /// true for `Sha256dHash`, so here we are.
     const DISPLAY_BACKWARD: bool = false;
 
+    /// Returns the bytes of the hash in their internal order
+    fn to_byte_array(self) -> Self::Inner {
+        let mut bytes = Self::Inner::zeroed();
+        bytes.as_mut_bytes().copy_from_slice(&self[..]);
+        bytes
+    }
+
+    /// Returns the bytes of the hash in display order, which is the reverse
+    /// of the internal order for hashes with `DISPLAY_BACKWARD` set
+    fn to_display_byte_array(self) -> Self::Inner {
+        // Reversing the bytes twice gives them back, so the conversion from
+        // display order also converts to it
+        Self::from_display_byte_array(self.to_byte_array()).to_byte_array()
+    }
+
+    /// Parses a hash from the hex of its bytes in their internal order.
+    /// Unlike `FromHex::from_hex`, the bytes are not reversed for hashes
+    /// with `DISPLAY_BACKWARD` set, such as those stored in internal order
+    /// by some databases.
+    fn from_hex_internal(s: &str) -> Result<Self, Error> {
+        if s.len() != 2 * Self::LEN {
+            return Err(Error::InvalidLength(2 * Self::LEN, s.len()));
+        }
+        let mut bytes = Self::Inner::zeroed();
+        hex::HexIterator::new(s)?.fill(bytes.as_mut_bytes())?;
+        Ok(Self::from_byte_array(bytes))
+    }
+
+    /// Returns the hex of the bytes of the hash in their internal order,
+    /// the inverse of `from_hex_internal`
+    #[cfg(feature = "alloc")]
+    fn to_hex_internal(&self) -> prelude::String {
+        hex::ToHex::to_hex(&self[..])
+    }
+
     /// Unwraps the hash and returns the underlying byte array
-    fn into_inner(self) -> Self::Inner;
+    #[deprecated(note = "use `to_byte_array`, which returns the same bytes")]
+    fn into_inner(self) -> Self::Inner {
+        self.to_byte_array()
+    }
+
+    /// Constructs a hash whose bytes are all zero, as used for sentinel
+    /// values such as the genesis block's previous block hash
+    fn all_zeros() -> Self {
+        Self::from_byte_array(Self::Inner::zeroed())
+    }
+
+    /// Constructs a hash whose bytes are all `0xff`
+    fn all_ones() -> Self {
+        let mut bytes = Self::Inner::zeroed();
+        for b in bytes.as_mut_bytes() {
+            *b = 0xff;
+        }
+        Self::from_byte_array(bytes)
+    }
+
+    /// XORs the bytes of two hashes. The result is not the hash of
+    /// anything, but it commits to both hashes regardless of their order.
+    /// This is the generic form of `^`, which is only implemented for some
+    /// hash types.
+    ///
+    /// ```rust
+    /// use bitcoin_hashes::{sha256, Hash};
+    ///
+    /// // A commitment to a set of items, which does not depend on their order
+    /// fn commit(items: &[&[u8]]) -> sha256::Hash {
+    ///     let mut acc = sha256::Hash::all_zeros();
+    ///     for item in items {
+    ///         acc.xor_in_place(&sha256::Hash::hash(item));
+    ///     }
+    ///     acc
+    /// }
+    ///
+    /// assert_eq!(commit(&[b"a", b"b"]), commit(&[b"b", b"a"]));
+    /// assert_eq!(commit(&[b"a", b"b"]), sha256::Hash::hash(b"a").xor(&sha256::Hash::hash(b"b")));
+    /// ```
+    fn xor(&self, other: &Self) -> Self {
+        let mut bytes = self.to_byte_array();
+        for (b, y) in bytes.as_mut_bytes().iter_mut().zip(other[..].iter()) {
+            *b ^= y;
+        }
+        Self::from_byte_array(bytes)
+    }
+
+    /// XORs the bytes of `other` into this hash, as `xor` does
+    fn xor_in_place(&mut self, other: &Self) {
+        *self = self.xor(other);
+    }
+
+    /// Checks whether every byte of the hash is zero
+    fn is_all_zeros(&self) -> bool {
+        self[..].iter().all(|&b| b == 0)
+    }
+
+    /// Counts the number of leading zero bits of the hash, taking its bytes
+    /// in display order. For hashes displayed backward, such as block hashes,
+    /// these are the leading zeros of the little-endian number which Bitcoin
+    /// compares with difficulty targets.
+    fn leading_zeros(&self) -> u32 {
+        let mut ret = 0;
+        for i in 0..Self::LEN {
+            let b = if Self::DISPLAY_BACKWARD { self[Self::LEN - 1 - i] } else { self[i] };
+            ret += b.leading_zeros();
+            if b != 0 {
+                break;
+            }
+        }
+        ret
+    }
+
+    /// Checks whether the hash is at most `target`, comparing them in
+    /// display order as `cmp::DisplayOrdered` does, which is how Bitcoin
+    /// compares block hashes with difficulty targets
+    fn meets_difficulty(&self, target: &Self) -> bool {
+        cmp::DisplayOrdered(*self) <= cmp::DisplayOrdered(*target)
+    }
+
+    /// Computes `2^256 / (hash + 1)`, the expected number of attempts needed
+    /// to find a hash at most this one, reading the hash as a big-endian
+    /// number in display order. Returns `None` for hashes which are not 32
+    /// bytes long, or for the all-zero hash whose work does not fit in 256
+    /// bits.
+    fn work(&self) -> Option<[u8; 32]> {
+        if Self::LEN != 32 {
+            return None;
+        }
+        let mut target = [0; 32];
+        target.copy_from_slice(&self[..]);
+        if Self::DISPLAY_BACKWARD {
+            target.reverse();
+        }
+        uint::work(&target)
+    }
+}
+
+/// Iterator over the bytes of a hash, in their internal order, returned by
+/// the `IntoIterator` implementations of hashes. Reverse it with `rev` for
+/// the display order of hashes with `DISPLAY_BACKWARD` set.
+#[derive(Clone, Debug)]
+pub struct HashBytes<H: Hash> {
+    hash: H,
+    front: usize,
+    back: usize,
+}
+
+impl<H: Hash> HashBytes<H> {
+    /// Constructs an iterator over all bytes of `hash`
+    pub fn new(hash: H) -> HashBytes<H> {
+        HashBytes {
+            hash,
+            front: 0,
+            back: H::LEN,
+        }
+    }
+}
+
+impl<H: Hash> Iterator for HashBytes<H> {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<u8> {
+        if self.front == self.back {
+            return None;
+        }
+        self.front += 1;
+        Some(self.hash[self.front - 1])
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.back - self.front;
+        (len, Some(len))
+    }
+}
+
+impl<H: Hash> DoubleEndedIterator for HashBytes<H> {
+    fn next_back(&mut self) -> Option<u8> {
+        if self.front == self.back {
+            return None;
+        }
+        self.back -= 1;
+        Some(self.hash[self.back])
+    }
+}
+
+impl<H: Hash> ExactSizeIterator for HashBytes<H> {}
+
+/// Hashes the concatenation of a sequence of byte slices
+pub fn hash_all<H, I>(items: I) -> H
+where
+    H: Hash,
+    I: IntoIterator,
+    I::Item: AsRef<[u8]>,
+{
+    let mut engine = H::engine();
+    for item in items {
+        engine.input(item.as_ref());
+    }
+    H::from_engine(engine)
+}
+
+/// Hashes a sequence of byte slices, prefixing each with its length as a
+/// 4-byte little-endian integer so that different splits of the same data
+/// hash differently. Panics if any item is 4GiB or longer.
+pub fn hash_all_with_lengths<H, I>(items: I) -> H
+where
+    H: Hash,
+    I: IntoIterator,
+    I::Item: AsRef<[u8]>,
+{
+    use byteorder::{ByteOrder, LittleEndian};
+
+    let mut engine = H::engine();
+    for item in items {
+        let item = item.as_ref();
+        let len = item.len() as u32;
+        assert_eq!(len as usize, item.len(), "item too long");
+
+        let mut len_bytes = [0; 4];
+        LittleEndian::write_u32(&mut len_bytes, len);
+        engine.input(&len_bytes);
+        engine.input(item);
+    }
+    H::from_engine(engine)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+    use std::io::Write;
+
+    use {blake2b256, blake2b512, blake3, groestl512, hash160, ripemd160, sha1, sha256, sha256d, sha512};
+    use {keccak256, keccak512, sha224, sha384, sha3_256, sha3_512, sha512_224, sha512_256};
+    use hex::{FromHex, ToHex};
+    use {Hash, HashEngine, Hmac, HmacEngine};
+
+    /// A hash longer than any of this crate's, implementing only the required
+    /// methods of `Hash`, to check that the provided ones work for it
+    #[derive(Copy, Clone)]
+    struct Wide([u8; 96]);
+
+    hex_fmt_impl!(Debug, Wide);
+    hex_fmt_impl!(Display, Wide);
+    hex_fmt_impl!(LowerHex, Wide);
+    index_impl!(Wide);
+    borrow_slice_impl!(Wide);
+
+    impl PartialEq for Wide {
+        fn eq(&self, other: &Wide) -> bool {
+            self.0[..] == other.0[..]
+        }
+    }
+
+    impl Eq for Wide {}
+
+    impl PartialOrd for Wide {
+        fn partial_cmp(&self, other: &Wide) -> Option<::core::cmp::Ordering> {
+            Some(self.cmp(other))
+        }
+    }
+
+    impl Ord for Wide {
+        fn cmp(&self, other: &Wide) -> ::core::cmp::Ordering {
+            self.0[..].cmp(&other.0[..])
+        }
+    }
+
+    impl ::core::hash::Hash for Wide {
+        fn hash<H: ::core::hash::Hasher>(&self, state: &mut H) {
+            self.0[..].hash(state)
+        }
+    }
+
+    impl Default for Wide {
+        fn default() -> Wide {
+            Wide([0; 96])
+        }
+    }
+
+    impl Hash for Wide {
+        type Engine = sha512::HashEngine;
+        type Inner = [u8; 96];
+
+        fn engine() -> sha512::HashEngine {
+            sha512::Hash::engine()
+        }
+
+        fn from_engine(e: sha512::HashEngine) -> Wide {
+            let long = sha512::Hash::from_engine(e);
+            let mut ret = [0; 96];
+            ret[..64].copy_from_slice(&long[..]);
+            ret[64..].copy_from_slice(&sha256::Hash::hash(&long[..])[..]);
+            Wide(ret)
+        }
+
+        const LEN: usize = 96;
+
+        fn from_slice(sl: &[u8]) -> Result<Wide, ::Error> {
+            if sl.len() != 96 {
+                return Err(::Error::InvalidLength(96, sl.len()));
+            }
+            let mut ret = [0; 96];
+            ret.copy_from_slice(sl);
+            Ok(Wide(ret))
+        }
+
+        const DISPLAY_BACKWARD: bool = true;
+    }
+
+    #[test]
+    fn wide_hash() {
+        let hash = Wide::hash(b"abc");
+        let bytes = hash.to_byte_array();
+        assert_eq!(&bytes[..64], &sha512::Hash::hash(b"abc")[..]);
+        assert_eq!(Wide::from_byte_array(bytes), hash);
+
+        let mut display = bytes;
+        display.reverse();
+        assert_eq!(&hash.to_display_byte_array()[..], &display[..]);
+        assert_eq!(Wide::from_display_byte_array(display), hash);
+
+        // Displayed backward, as the hex of the display bytes
+        let hex = display.to_hex();
+        assert_eq!(hash.to_hex(), hex);
+        assert_eq!(Wide::from_hex(&hex), Ok(hash));
+        assert_eq!(
+            Wide::from_hex(&hex[2..]),
+            Err(::Error::InvalidLength(192, 190)),
+        );
+
+        assert!(Wide::all_zeros().is_all_zeros());
+        assert_eq!(Wide::all_zeros(), Wide::default());
+        assert_eq!(&Wide::all_ones()[..], &[0xff; 96][..]);
+
+        assert_eq!(Wide::from_hex_internal(&bytes.to_hex()), Ok(hash));
+
+        assert_eq!(hash.xor(&Wide::all_ones()).xor(&hash), Wide::all_ones());
+    }
+
+    fn write_into<W: Write>(w: &mut W, data: &[u8]) {
+        for chunk in data.chunks(7) {
+            w.write_all(chunk).expect("write to engine");
+        }
+    }
+
+    fn check_write<T: Hash>() {
+        let data: Vec<u8> = (0..300).map(|i| i as u8).collect();
+        let expected = <T as Hash>::hash(&data);
+
+        // A single call to `write` should consume the whole input
+        let mut engine = T::engine();
+        assert_eq!(engine.write(&data).expect("write to engine"), data.len());
+        engine.flush().expect("flush engine");
+        assert_eq!(T::from_engine(engine), expected);
+
+        // Writing in chunks through a generic writer gives the same result
+        let mut engine = T::engine();
+        write_into(&mut engine, &data);
+        assert_eq!(T::from_engine(engine), expected);
+
+        // Mixing `write` and `input` also gives the same result
+        let mut engine = T::engine();
+        engine.input(&data[..100]);
+        write_into(&mut engine, &data[100..]);
+        assert_eq!(T::from_engine(engine), expected);
+    }
+
+    #[test]
+    fn write_only_engine() {
+        // An engine which only implements `io::Write` gets `input` from it
+        #[derive(Clone, Default)]
+        struct Summer(u64);
+
+        impl Write for Summer {
+            fn write(&mut self, data: &[u8]) -> ::std::io::Result<usize> {
+                self.0 += data.iter().map(|&b| b as u64).sum::<u64>();
+                Ok(data.len())
+            }
+
+            fn flush(&mut self) -> ::std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        impl HashEngine for Summer {
+            type MidState = u64;
+
+            fn midstate(&self) -> u64 {
+                self.0
+            }
+
+            const BLOCK_SIZE: usize = 1;
+        }
+
+        let mut engine = Summer::default();
+        engine.input(&[1, 2, 3]);
+        assert_eq!(engine.midstate(), 6);
+    }
+
+    #[test]
+    fn engine_write() {
+        check_write::<blake2b256::Hash>();
+        check_write::<blake2b512::Hash>();
+        check_write::<blake3::Hash>();
+        check_write::<groestl512::Hash>();
+        check_write::<hash160::Hash>();
+        check_write::<ripemd160::Hash>();
+        check_write::<sha1::Hash>();
+        check_write::<sha224::Hash>();
+        check_write::<sha256::Hash>();
+        check_write::<sha256d::Hash>();
+        check_write::<sha384::Hash>();
+        check_write::<sha512::Hash>();
+        check_write::<sha512_224::Hash>();
+        check_write::<sha512_256::Hash>();
+        check_write::<sha3_256::Hash>();
+        check_write::<sha3_512::Hash>();
+        check_write::<keccak256::Hash>();
+        check_write::<keccak512::Hash>();
+        check_write::<Hmac<sha256::Hash>>();
+        check_write::<Hmac<sha512::Hash>>();
+    }
+
+    fn check_fork<T: Hash>() {
+        let prefix = [0xab; 200];
+        let suffixes: [&[u8]; 3] = [b"", b"leaf one", b"leaf two"];
+
+        let mut engine = T::engine();
+        engine.input(&prefix);
+        for suffix in &suffixes {
+            let mut fork = engine.fork();
+            fork.input(suffix);
+
+            let mut fresh = T::engine();
+            fresh.input(&prefix);
+            fresh.input(suffix);
+            assert_eq!(T::from_engine(fork), T::from_engine(fresh));
+        }
+    }
+
+    #[test]
+    fn engine_fork() {
+        check_fork::<blake2b256::Hash>();
+        check_fork::<blake3::Hash>();
+        check_fork::<hash160::Hash>();
+        check_fork::<ripemd160::Hash>();
+        check_fork::<sha1::Hash>();
+        check_fork::<sha256::Hash>();
+        check_fork::<sha256d::Hash>();
+        check_fork::<sha512::Hash>();
+        check_fork::<sha3_256::Hash>();
+        check_fork::<Hmac<sha256::Hash>>();
+    }
+
+    #[test]
+    fn engine_clone() {
+        fn check_clone<T: Hash>() {
+            let mut e1 = T::engine();
+            e1.input(b"prefix");
+            let mut e2 = e1.clone();
+            e1.input(b"A");
+            e2.input(b"B");
+            assert!(T::from_engine(e1) != T::from_engine(e2));
+        }
+
+        check_clone::<blake2b256::Hash>();
+        check_clone::<blake2b512::Hash>();
+        check_clone::<blake3::Hash>();
+        check_clone::<groestl512::Hash>();
+        check_clone::<hash160::Hash>();
+        check_clone::<ripemd160::Hash>();
+        check_clone::<sha1::Hash>();
+        check_clone::<sha224::Hash>();
+        check_clone::<sha256::Hash>();
+        check_clone::<sha256d::Hash>();
+        check_clone::<sha384::Hash>();
+        check_clone::<sha512::Hash>();
+        check_clone::<sha3_256::Hash>();
+        check_clone::<sha3_512::Hash>();
+        check_clone::<keccak256::Hash>();
+        check_clone::<keccak512::Hash>();
+        check_clone::<sha512_224::Hash>();
+        check_clone::<sha512_256::Hash>();
+        check_clone::<Hmac<sha256::Hash>>();
+    }
+
+    #[test]
+    fn engine_default() {
+        fn check_default<T: Hash>() where T::Engine: Default {
+            let mut engine = T::Engine::default();
+            engine.input(b"abc");
+            assert_eq!(T::from_engine(engine), <T as Hash>::hash(b"abc"));
+        }
+
+        check_default::<blake2b256::Hash>();
+        check_default::<blake2b512::Hash>();
+        check_default::<blake3::Hash>();
+        check_default::<groestl512::Hash>();
+        check_default::<hash160::Hash>();
+        check_default::<ripemd160::Hash>();
+        check_default::<sha1::Hash>();
+        check_default::<sha224::Hash>();
+        check_default::<sha256::Hash>();
+        check_default::<sha256d::Hash>();
+        check_default::<sha384::Hash>();
+        check_default::<sha512::Hash>();
+        check_default::<sha3_256::Hash>();
+        check_default::<sha3_512::Hash>();
+        check_default::<keccak256::Hash>();
+        check_default::<keccak512::Hash>();
+        check_default::<sha512_224::Hash>();
+        check_default::<sha512_256::Hash>();
+
+        // Engines can be held by structs deriving `Default`
+        #[derive(Default)]
+        struct Hashers {
+            sha256: sha256::HashEngine,
+            sha512: sha512::HashEngine,
+        }
+        let mut hashers = Hashers::default();
+        hashers.sha256.input(b"abc");
+        hashers.sha512.input(b"abc");
+        assert_eq!(sha256::Hash::from_engine(hashers.sha256), sha256::Hash::hash(b"abc"));
+        assert_eq!(sha512::Hash::from_engine(hashers.sha512), sha512::Hash::hash(b"abc"));
+    }
+
+    #[test]
+    fn engine_extend() {
+        let data = b"The quick brown fox jumps over the lazy dog";
+
+        let engine: sha256::HashEngine = data.iter().cloned().collect();
+        assert_eq!(sha256::Hash::from_engine(engine), sha256::Hash::hash(data));
+
+        let mut engine = sha3_256::Hash::engine();
+        engine.extend(data.iter());
+        assert_eq!(sha3_256::Hash::from_engine(engine), sha3_256::Hash::hash(data));
+
+        let mut engine = HmacEngine::<sha512::Hash>::new(b"key");
+        engine.extend(data[..10].iter().cloned());
+        engine.extend(&data[10..]);
+        let mut expected = HmacEngine::<sha512::Hash>::new(b"key");
+        expected.input(data);
+        assert_eq!(Hmac::from_engine(engine), Hmac::from_engine(expected));
+    }
+
+    #[test]
+    fn hash_all() {
+        let items: [&[u8]; 3] = [b"one", b"", b"three"];
+        assert_eq!(::hash_all::<sha256::Hash, _>(&items), sha256::Hash::hash(b"onethree"));
+        assert_eq!(::hash_all::<sha256::Hash, _>(Vec::<Vec<u8>>::new()), sha256::Hash::hash(&[]));
+
+        assert_eq!(
+            ::hash_all_with_lengths::<sha256::Hash, _>(vec![b"one".to_vec(), vec![], b"three".to_vec()]),
+            sha256::Hash::hash(b"\x03\0\0\0one\0\0\0\0\x05\0\0\0three"),
+        );
+        assert!(
+            ::hash_all_with_lengths::<sha256::Hash, _>(&["ab", "c"])
+                != ::hash_all_with_lengths::<sha256::Hash, _>(&["a", "bc"])
+        );
+    }
+
+    #[test]
+    fn sentinels() {
+        assert!(sha256d::Hash::all_zeros().is_all_zeros());
+        assert_eq!(sha256d::Hash::all_zeros(), sha256d::Hash::default());
+        assert!(!sha256d::Hash::all_ones().is_all_zeros());
+        assert_eq!(&sha256d::Hash::all_ones()[..], &[0xff; 32][..]);
+        assert!(!sha256d::Hash::hash(&[]).is_all_zeros());
+
+        assert_eq!(&hash160::Hash::all_ones()[..], &[0xff; 20][..]);
+        assert_eq!(&sha512::Hash::all_zeros()[..], &[0; 64][..]);
+        assert!(Hmac::<sha512::Hash>::all_zeros().is_all_zeros());
+    }
+
+    #[test]
+    fn difficulty() {
+        let mut bytes = [0xff; 32];
+        assert_eq!(sha256::Hash::from_slice(&bytes).unwrap().leading_zeros(), 0);
+        bytes[0] = 0;
+        bytes[1] = 0;
+        bytes[2] = 0x1f;
+        assert_eq!(sha256::Hash::from_slice(&bytes).unwrap().leading_zeros(), 19);
+        // Block hashes are numbers in display order, so the same bytes are huge
+        let backward = sha256d::Hash::from_slice(&bytes).unwrap();
+        assert_eq!(backward.leading_zeros(), 0);
+        assert_eq!(sha256d::Hash::all_zeros().leading_zeros(), 256);
+
+        // The genesis block, and the target of difficulty 1 which it meets
+        let genesis = sha256d::Hash::from_hex(
+            "000000000019d6689c085ae165831e934ff763ae46a2a6c172b3f1b60a8ce26f",
+        ).unwrap();
+        let target = sha256d::Hash::from_hex(
+            "00000000ffff0000000000000000000000000000000000000000000000000000",
+        ).unwrap();
+        assert_eq!(genesis.leading_zeros(), 43);
+        assert_eq!(target.leading_zeros(), 32);
+        assert!(genesis.meets_difficulty(&target));
+        assert!(target.meets_difficulty(&target));
+        assert!(!backward.meets_difficulty(&target));
+        assert!(!target.meets_difficulty(&genesis));
+        assert!(sha256d::Hash::all_zeros().meets_difficulty(&target));
+        assert!(!sha256d::Hash::all_ones().meets_difficulty(&target));
+        // Bytewise, the genesis hash is greater than the target
+        assert!(genesis > target);
+
+        // A block at difficulty 1 adds 0x100010001 to the chainwork
+        let mut work = [0; 32];
+        work[27] = 1;
+        work[29] = 1;
+        work[31] = 1;
+        assert_eq!(target.work(), Some(work));
+
+        // 2^224 - 1 takes 2^32 attempts on average
+        let mut bytes = [0xff; 32];
+        bytes[..4].copy_from_slice(&[0; 4]);
+        let mut work = [0; 32];
+        work[27] = 1;
+        assert_eq!(sha256::Hash::from_slice(&bytes).unwrap().work(), Some(work));
+
+        let mut bytes = [0; 32];
+        bytes[31] = 1;
+        let mut work = [0; 32];
+        work[0] = 0x80;
+        assert_eq!(sha256::Hash::from_slice(&bytes).unwrap().work(), Some(work));
+
+        let mut work = [0; 32];
+        work[31] = 1;
+        assert_eq!(sha256::Hash::all_ones().work(), Some(work));
+        // 2^256 / 3
+        let mut bytes = [0; 32];
+        bytes[31] = 2;
+        assert_eq!(sha256::Hash::from_slice(&bytes).unwrap().work(), Some([0x55; 32]));
+
+        assert_eq!(sha256::Hash::all_zeros().work(), None);
+        assert_eq!(sha512::Hash::all_ones().work(), None);
+    }
+
+    #[test]
+    fn bitwise_ops() {
+        let hash = sha256::Hash::hash(b"abc");
+        assert_eq!(hash ^ hash, sha256::Hash::default());
+        assert_eq!(hash ^ !hash, sha256::Hash::all_ones());
+        assert_eq!(hash & !hash, sha256::Hash::default());
+        assert_eq!(hash | !hash, sha256::Hash::all_ones());
+        assert_eq!(hash & hash, hash);
+        assert_eq!(hash | hash, hash);
+
+        let other = sha256::Hash::hash(b"abd");
+        let mut acc = hash;
+        acc ^= other;
+        assert_eq!(acc, hash ^ other);
+        acc ^= other;
+        assert_eq!(acc, hash);
+        acc &= other;
+        assert_eq!(acc, hash & other);
+        acc |= hash;
+        assert_eq!(acc, hash);
+
+        let hash = sha512::Hash::hash(b"abc");
+        assert_eq!(hash ^ hash, sha512::Hash::default());
+        assert_eq!(&(hash ^ !hash)[..], &[0xff; 64][..]);
+    }
+
+    #[test]
+    fn try_from_bytes() {
+        let hash = sha256::Hash::hash(b"abc");
+
+        let bytes: [u8; 32] = hash.into();
+        assert_eq!(sha256::Hash::try_from(&bytes[..]), Ok(hash));
+        let vec: Vec<u8> = hash.into();
+        assert_eq!(sha256::Hash::try_from(vec), Ok(hash));
+
+        assert_eq!(sha256::Hash::try_from(&bytes[..31]), Err(::Error::InvalidLength(32, 31)));
+        assert_eq!(hash160::Hash::try_from(vec![0; 32]), Err(::Error::InvalidLength(20, 32)));
+
+        let hmac = Hmac::<sha512::Hash>::hash(b"abc");
+        let vec: Vec<u8> = hmac.into();
+        assert_eq!(Hmac::<sha512::Hash>::try_from(&vec[..]), Ok(hmac));
+        assert_eq!(Hmac::<sha512::Hash>::try_from(vec), Ok(hmac));
+    }
+
+    #[test]
+    fn from_byte_arrays() {
+        let hash = sha256::Hash::hash(b"abc");
+        let bytes: [u8; 32] = hash.into();
+        assert_eq!(sha256::Hash::from(bytes), hash);
+        let hash: sha256::Hash = bytes.into();
+        assert_eq!(hash, sha256::Hash::from_byte_array(bytes));
+
+        let hash = sha512::Hash::hash(b"abc");
+        let bytes: [u8; 64] = hash.into();
+        assert_eq!(sha512::Hash::from(bytes), hash);
+
+        let hash = ripemd160::Hash::hash(b"abc");
+        let bytes: [u8; 20] = hash.into();
+        assert_eq!(ripemd160::Hash::from(bytes), hash);
+
+        // Bytes in internal order, not in display order
+        let hash = sha256d::Hash::hash(b"abc");
+        assert_eq!(sha256d::Hash::from(hash.to_byte_array()), hash);
+    }
+
+    #[test]
+    fn as_ref() {
+        fn to_vec<T: AsRef<[u8]>>(data: T) -> Vec<u8> {
+            data.as_ref().to_vec()
+        }
+
+        let hash = sha256::Hash::hash(b"abc");
+        assert_eq!(to_vec(hash), &hash[..]);
+        assert_eq!(to_vec(sha256d::Hash::hash(b"abc")), &sha256d::Hash::hash(b"abc")[..]);
+        assert_eq!(to_vec(hash160::Hash::hash(b"abc")).len(), 20);
+        assert_eq!(to_vec(blake2b512::Hash::hash(b"abc")).len(), 64);
+        assert_eq!(to_vec(sha256::Hash::engine().midstate()).len(), 32);
+
+        let hmac = Hmac::<sha512::Hash>::hash(b"abc");
+        assert_eq!(to_vec(hmac), &hmac[..]);
+    }
+
+    #[test]
+    fn byte_arrays() {
+        let hash = sha256::Hash::hash(b"abc");
+        let bytes = hash.to_byte_array();
+        assert_eq!(sha256::Hash::from_byte_array(bytes), hash);
+        assert_eq!(sha256::Hash::from_display_byte_array(bytes), hash);
+        assert_eq!(hash.to_display_byte_array(), bytes);
+
+        // The display order of `sha256d` hashes is reversed
+        let hash = sha256d::Hash::hash(b"abc");
+        let bytes = hash.to_byte_array();
+        let mut display = bytes;
+        display.reverse();
+        assert_eq!(hash.to_display_byte_array(), display);
+        assert_eq!(sha256d::Hash::from_display_byte_array(display), hash);
+        assert_eq!(sha256d::Hash::from_byte_array(bytes), hash);
+        assert_eq!(hash.to_display_byte_array()[..].to_hex(), hash.to_hex());
+
+        #[allow(deprecated)]
+        let inner = hash.into_inner();
+        assert_eq!(inner, bytes);
+    }
+
+    #[test]
+    fn eq_bytes() {
+        let hash = sha256::Hash::hash(b"abc");
+        let bytes = hash.to_byte_array();
+        assert_eq!(hash, bytes);
+        assert_eq!(hash, bytes[..]);
+        assert!(hash != bytes[..31]);
+
+        // Bytes are compared in internal order, not the reversed display order
+        let hash = sha256d::Hash::hash(b"abc");
+        let mut bytes = hash.to_byte_array();
+        assert_eq!(hash, bytes);
+        bytes.reverse();
+        assert!(hash != bytes);
+
+        assert_eq!(hash160::Hash::hash(b"abc"), hash160::Hash::hash(b"abc").to_byte_array());
+        assert_eq!(sha512::Hash::hash(b"abc"), sha512::Hash::hash(b"abc").to_byte_array());
+    }
+
+    #[test]
+    fn std_hash() {
+        use std::collections::HashSet;
+        use sha256t;
+        use taproot::{TapLeafHash, TapLeafTag};
+
+        // `H::hash` would be ambiguous between the two `Hash` traits
+        fn set_of<H: Hash>(data: &[&[u8]]) -> HashSet<H> {
+            data.iter().map(|d| <H as Hash>::hash(d)).collect()
+        }
+
+        let data: &[&[u8]] = &[b"a", b"b", b"a"];
+        assert_eq!(set_of::<sha256::Hash>(data).len(), 2);
+        assert_eq!(set_of::<sha256d::Hash>(data).len(), 2);
+        assert_eq!(set_of::<sha512::Hash>(data).len(), 2);
+        assert_eq!(set_of::<Hmac<sha256::Hash>>(data).len(), 2);
+        assert_eq!(set_of::<sha256t::Hash<TapLeafTag>>(data).len(), 2);
+
+        let set = set_of::<TapLeafHash>(data);
+        assert!(set.contains(&TapLeafHash::hash(b"b")));
+        assert!(!set.contains(&TapLeafHash::hash(b"c")));
+    }
+
+    #[test]
+    fn deref() {
+        fn takes_array(bytes: &[u8; 32]) -> u8 {
+            bytes[0]
+        }
+
+        let hash = sha256::Hash::hash(b"abc");
+        assert_eq!(takes_array(&hash), hash[0]);
+        assert_eq!(*hash, hash.to_byte_array());
+        assert_eq!(hash.len(), 32);
+        assert!(hash.starts_with(&hash[..4]));
+
+        // The internal order, not the reversed display order
+        let hash = sha256d::Hash::hash(b"abc");
+        assert_eq!(takes_array(&hash), hash.to_byte_array()[0]);
+        assert_eq!(hash.last(), Some(&hash.to_display_byte_array()[0]));
+
+        let hash: &[u8; 20] = &hash160::Hash::hash(b"abc");
+        assert_eq!(hash.len(), 20);
+    }
+
+    #[test]
+    fn hash_pieces() {
+        let (a, b, c) = (b"The quick brown fox", vec![b' '; 1], "jumps over the lazy dog");
+        let abc = [&a[..], &b[..], c.as_bytes()].concat();
+        assert_eq!(sha256::Hash::hash_two(a, &b), sha256::Hash::hash(&abc[..20]));
+        assert_eq!(sha256::Hash::hash_three(a, &b, c), sha256::Hash::hash(&abc));
+        assert_eq!(sha512::Hash::hash_three(a, &b, c), sha512::Hash::hash(&abc));
+        assert_eq!(sha256d::Hash::hash_two(&abc[..7], &abc[7..]), sha256d::Hash::hash(&abc));
+        assert_eq!(sha256::Hash::hash_two([], []), sha256::Hash::hash(&[]));
+    }
+
+    #[test]
+    fn hex_internal() {
+        let hash = sha256d::Hash::hash(b"abc");
+        let internal = hash.to_hex_internal();
+        assert_eq!(internal, hash[..].to_hex());
+        assert_ne!(internal, hash.to_hex());
+        assert_eq!(sha256d::Hash::from_hex_internal(&internal), Ok(hash));
+        assert_eq!(sha256d::Hash::from_hex_internal(&hash.to_hex()), Ok(sha256d::Hash::from_display_byte_array(hash.to_byte_array())));
+
+        // Hashes displayed forward are the same either way
+        let hash = sha256::Hash::hash(b"abc");
+        assert_eq!(hash.to_hex_internal(), hash.to_hex());
+        assert_eq!(sha256::Hash::from_hex_internal(&hash.to_hex()), Ok(hash));
+
+        assert_eq!(sha256::Hash::from_hex_internal("00"), Err(::Error::InvalidLength(64, 2)));
+        assert!(sha256::Hash::from_hex_internal(&"x".repeat(64)).is_err());
+    }
+
+    #[test]
+    fn into_iter() {
+        let hash = sha256::Hash::hash(b"abc");
+        let bytes: Vec<u8> = hash.into_iter().collect();
+        assert_eq!(&bytes[..], &hash[..]);
+        let mut n = 0;
+        for byte in &hash {
+            assert_eq!(*byte, hash[n]);
+            n += 1;
+        }
+        assert_eq!(n, 32);
+
+        // Backward for the display order
+        let hash = sha256d::Hash::hash(b"abc");
+        let display: Vec<u8> = hash.into_iter().rev().collect();
+        assert_eq!(&display[..], &hash.to_display_byte_array()[..]);
+
+        let mut iter = ripemd160::Hash::hash(b"abc").into_iter();
+        assert_eq!(iter.len(), 20);
+        iter.next();
+        iter.next_back();
+        assert_eq!(iter.len(), 18);
+        assert_eq!(iter.count(), 18);
+        assert_eq!(sha512::Hash::hash(b"abc").into_iter().len(), 64);
+    }
+
+    #[test]
+    fn verify() {
+        let hash = sha256::Hash::hash(b"abc");
+        assert!(hash.verify(b"abc"));
+        assert!(!hash.verify(b"abd"));
+        assert!(!hash.verify(&[]));
+
+        let hash = sha512::Hash::hash(&[]);
+        assert!(hash.verify(&[]));
+        assert!(!hash.verify(b"abc"));
+    }
+
+    #[cfg(feature="as_mut")]
+    #[test]
+    fn as_mut() {
+        let mut hash = sha256::Hash::all_zeros();
+        hash.as_mut()[0] = 1;
+        assert_eq!(hash[0], 1);
+
+        let mut hmac = Hmac::<sha512::Hash>::all_zeros();
+        hmac.as_mut()[63] = 1;
+        assert_eq!(hmac[63], 1);
+
+        hash.inner_mut()[31] = 2;
+        assert_eq!(hash.inner_ref(), &hash.to_byte_array());
+        assert_eq!(hash[31], 2);
+    }
+
+    #[test]
+    fn xor() {
+        let a = sha256::Hash::hash(b"a");
+        let b = sha256::Hash::hash(b"b");
+        assert_eq!(a.xor(&b), a ^ b);
+        assert_eq!(a.xor(&b), b.xor(&a));
+        assert!(a.xor(&a).is_all_zeros());
+
+        let mut c = a;
+        c.xor_in_place(&b);
+        assert_eq!(c, a ^ b);
+        c.xor_in_place(&b);
+        assert_eq!(c, a);
+
+        // Also for hashes without `^`
+        let a = ripemd160::Hash::hash(b"a");
+        assert_eq!(a.xor(&ripemd160::Hash::all_ones()), ripemd160::Hash::from_slice(
+            &a[..].iter().map(|b| !b).collect::<Vec<u8>>()).unwrap());
+    }
+
+    #[test]
+    fn inner_ref() {
+        let hash = sha256::Hash::hash(b"abc");
+        let bytes: &[u8; 32] = hash.inner_ref();
+        assert_eq!(bytes, &hash.to_byte_array());
+
+        let hash = blake2b512::Hash::hash(b"abc");
+        assert_eq!(&hash.inner_ref()[..], &hash[..]);
+    }
+
+    #[cfg(feature="subtle")]
+    #[test]
+    fn constant_time_eq() {
+        use subtle::ConstantTimeEq;
+        use {sha256t, HmacEngine};
+
+        let hash = sha256::Hash::hash(b"abc");
+        let other = sha256::Hash::hash(b"abd");
+        assert_eq!(hash.ct_eq(&hash).unwrap_u8(), 1);
+        assert_eq!(hash.ct_eq(&other).unwrap_u8(), 0);
+
+        let hash = blake2b512::Hash::hash(b"abc");
+        let other = blake2b512::Hash::hash(b"abd");
+        assert_eq!(hash.ct_eq(&hash).unwrap_u8(), 1);
+        assert_eq!(hash.ct_eq(&other).unwrap_u8(), 0);
+
+        let mut engine = HmacEngine::<sha256::Hash>::new(b"key");
+        engine.input(b"message");
+        let tag = Hmac::from_engine(engine);
+        let mut engine = HmacEngine::<sha256::Hash>::new(b"other key");
+        engine.input(b"message");
+        let forged = Hmac::from_engine(engine);
+        assert_eq!(tag.ct_eq(&tag).unwrap_u8(), 1);
+        assert_eq!(tag.ct_eq(&forged).unwrap_u8(), 0);
+        assert!(tag.constant_time_eq(&tag));
+        assert!(!tag.constant_time_eq(&forged));
+
+        struct TestTag;
+        impl sha256t::Tag for TestTag {
+            fn engine() -> sha256::HashEngine {
+                sha256t::tag_engine("test")
+            }
+
+            fn name() -> &'static str {
+                "TestTag"
+            }
+        }
+        let hash = sha256t::Hash::<TestTag>::hash(b"abc");
+        let other = sha256t::Hash::<TestTag>::hash(b"abd");
+        assert_eq!(hash.ct_eq(&hash).unwrap_u8(), 1);
+        assert_eq!(hash.ct_eq(&other).unwrap_u8(), 0);
+        assert!(hash.constant_time_eq(&hash));
+        assert!(!hash.constant_time_eq(&other));
+    }
+
+    #[cfg(feature="zeroize")]
+    #[test]
+    fn zeroize() {
+        use zeroize::Zeroize;
+        use HmacEngine;
+
+        let mut engine = sha256::Hash::engine();
+        engine.input(b"secret seed material");
+        engine.zeroize();
+        assert_eq!(engine.midstate(), sha256::Midstate([0; 32]));
+
+        let mut engine = blake2b256::Hash::engine();
+        engine.input(b"secret seed material");
+        engine.zeroize();
+        assert_eq!(&engine.midstate()[..], &[0; 64][..]);
+
+        let mut engine = HmacEngine::<sha512::Hash>::new(b"Bitcoin seed");
+        engine.input(b"secret seed material");
+        engine.zeroize();
+        assert_eq!(&engine.midstate()[..], &[0; 64][..]);
+
+        let mut hash = sha256d::Hash::hash(b"secret");
+        hash.zeroize();
+        assert_eq!(hash, sha256d::Hash::default());
+
+        let mut hmac = Hmac::<sha512::Hash>::hash(b"secret");
+        hmac.zeroize();
+        assert_eq!(hmac, Hmac::default());
+    }
 }
@@ -0,0 +1,249 @@
+// Bitcoin Hashes Library
+// Written in 2019 by
+//   The rust-bitcoin developers
+//
+// To the extent possible under law, the author(s) have dedicated all
+// copyright and related and neighboring rights to this software to
+// the public domain worldwide. This software is distributed without
+// any warranty.
+//
+// You should have received a copy of the CC0 Public Domain Dedication
+// along with this software.
+// If not, see <http://creativecommons.org/publicdomain/zero/1.0/>.
+//
+
+//! # Merkle Trees
+//!
+//! Merkle roots and inclusion proofs built the way Bitcoin builds the
+//! transaction tree of a block: each node is the hash of its two children
+//! concatenated, and a level with an odd number of nodes pairs its last node
+//! with itself. With `sha256d::Hash` leaves these are Bitcoin merkle roots.
+//!
+//! Note that duplicating the last node means that distinct lists of leaves
+//! can have the same root (CVE-2012-2459), so callers which need the leaves
+//! to be unique must check that separately.
+
+#[cfg(not(feature = "std"))]
+use prelude::Vec;
+use {Hash, HashEngine};
+
+/// Hashes the concatenation of two nodes into their parent
+fn combine<H: Hash>(left: &H, right: &H) -> H {
+    let mut engine = H::engine();
+    engine.input(&left[..]);
+    engine.input(&right[..]);
+    H::from_engine(engine)
+}
+
+/// Computes the parents of a level of the tree
+fn next_level<H: Hash>(level: &[H]) -> Vec<H> {
+    level.chunks(2)
+        .map(|pair| combine(&pair[0], pair.get(1).unwrap_or(&pair[0])))
+        .collect()
+}
+
+/// Computes the merkle root of `leaves`, which is the all-zero hash if there
+/// are no leaves
+pub fn merkle_root<H: Hash>(leaves: &[H]) -> H {
+    if leaves.is_empty() {
+        return H::all_zeros();
+    }
+
+    let mut level = leaves.to_vec();
+    while level.len() > 1 {
+        level = next_level(&level);
+    }
+    level[0]
+}
+
+/// Computes the proof that the leaf at `index` is included in the merkle
+/// root of `leaves`: the sibling of each node on its path to the root, from
+/// the bottom up.
+///
+/// Panics if `index` is out of bounds.
+pub fn merkle_proof<H: Hash>(leaves: &[H], mut index: usize) -> Vec<H> {
+    assert!(index < leaves.len(), "leaf index out of bounds");
+
+    let mut proof = vec![];
+    let mut level = leaves.to_vec();
+    while level.len() > 1 {
+        let sibling = index ^ 1;
+        proof.push(*level.get(sibling).unwrap_or(&level[index]));
+        level = next_level(&level);
+        index /= 2;
+    }
+    proof
+}
+
+/// Checks a proof, as produced by `merkle_proof`, that `leaf` is at `index`
+/// in the tree with the given `root`
+pub fn verify_merkle_proof<H: Hash>(root: &H, leaf: &H, mut index: usize, proof: &[H]) -> bool {
+    let mut node = *leaf;
+    for sibling in proof {
+        node = if index & 1 == 0 {
+            combine(&node, sibling)
+        } else {
+            combine(sibling, &node)
+        };
+        index /= 2;
+    }
+    // An index beyond the depth of the proof would otherwise be accepted
+    index == 0 && node == *root
+}
+
+/// Computes a merkle root from leaves given one at a time, such as the
+/// txids of a block as it is parsed, without keeping all of them in memory.
+/// Only the root of each complete subtree seen so far is kept, at most one
+/// per level of the tree.
+#[derive(Clone, Debug, Default)]
+pub struct IncrementalMerkle<H: Hash> {
+    /// The root of the complete subtree at each level for which bit `level`
+    /// of `count` is set; other entries are stale
+    subtrees: Vec<H>,
+    /// Number of leaves pushed so far
+    count: u64,
+}
+
+impl<H: Hash> IncrementalMerkle<H> {
+    /// Constructs an empty tree
+    pub fn new() -> IncrementalMerkle<H> {
+        IncrementalMerkle {
+            subtrees: vec![],
+            count: 0,
+        }
+    }
+
+    /// Number of leaves pushed so far
+    pub fn len(&self) -> u64 {
+        self.count
+    }
+
+    /// Whether no leaves have been pushed yet
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Adds the next leaf, merging every complete subtree which it completes
+    pub fn push(&mut self, leaf: H) {
+        let mut node = leaf;
+        let mut level = 0;
+        while self.count & (1 << level) != 0 {
+            node = combine(&self.subtrees[level], &node);
+            level += 1;
+        }
+        if level == self.subtrees.len() {
+            self.subtrees.push(node);
+        } else {
+            self.subtrees[level] = node;
+        }
+        self.count += 1;
+    }
+
+    /// Computes the merkle root of the leaves pushed so far, the same as
+    /// `merkle_root` would give for them
+    pub fn root(&self) -> H {
+        if self.count == 0 {
+            return H::all_zeros();
+        }
+
+        // Start from the smallest complete subtree, then pair it with itself
+        // wherever it has no sibling, as a level with an odd number of nodes
+        // does
+        let mut count = self.count;
+        let mut level = count.trailing_zeros() as usize;
+        let mut node = self.subtrees[level];
+        while count != 1 << level {
+            node = combine(&node, &node);
+            count += 1 << level;
+            level += 1;
+            while count & (1 << level) == 0 {
+                node = combine(&self.subtrees[level], &node);
+                level += 1;
+            }
+        }
+        node
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hex::FromHex;
+    use merkle::{merkle_proof, merkle_root, verify_merkle_proof, IncrementalMerkle};
+    use {sha256, sha256d, Hash, HashEngine};
+
+    fn txid(s: &str) -> sha256d::Hash {
+        sha256d::Hash::from_hex(s).expect("parse txid")
+    }
+
+    #[test]
+    fn bitcoin_blocks() {
+        // The genesis block only contains its coinbase
+        let coinbase = txid("4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b");
+        assert_eq!(merkle_root(&[coinbase]), coinbase);
+        assert!(merkle_proof(&[coinbase], 0).is_empty());
+        assert!(verify_merkle_proof(&coinbase, &coinbase, 0, &[]));
+
+        // Block 170 contains the first transaction other than a coinbase
+        let txids = [
+            txid("b1fea52486ce0c62bb442b530a3f0132b826c74e473d1f2c220bfa78111c5082"),
+            txid("f4184fc596403b9d638783cf57adfe4c75c605f6356fbc91338530e9831e9e16"),
+        ];
+        let root = txid("7dac2c5666815c17a3b36427de37bb9d2e2c5ccec3f8633eb91a4205cb4c10ff");
+        assert_eq!(merkle_root(&txids), root);
+        assert_eq!(merkle_proof(&txids, 1), vec![txids[0]]);
+        assert!(verify_merkle_proof(&root, &txids[1], 1, &[txids[0]]));
+        assert!(!verify_merkle_proof(&root, &txids[1], 0, &[txids[0]]));
+    }
+
+    #[test]
+    fn odd_levels() {
+        let leaves: Vec<sha256::Hash> = (0..5u8).map(|i| sha256::Hash::hash(&[i])).collect();
+
+        let node = |l: &sha256::Hash, r: &sha256::Hash| {
+            let mut engine = sha256::Hash::engine();
+            engine.input(&l[..]);
+            engine.input(&r[..]);
+            sha256::Hash::from_engine(engine)
+        };
+        let n01 = node(&leaves[0], &leaves[1]);
+        let n23 = node(&leaves[2], &leaves[3]);
+        let n44 = node(&leaves[4], &leaves[4]);
+        let n0123 = node(&n01, &n23);
+        let n4444 = node(&n44, &n44);
+        let root = node(&n0123, &n4444);
+        assert_eq!(merkle_root(&leaves), root);
+        assert_eq!(merkle_proof(&leaves, 4), vec![leaves[4], n44, n0123]);
+
+        for (index, leaf) in leaves.iter().enumerate() {
+            let proof = merkle_proof(&leaves, index);
+            assert_eq!(proof.len(), 3);
+            assert!(verify_merkle_proof(&root, leaf, index, &proof));
+            assert!(!verify_merkle_proof(&root, leaf, index + 8, &proof));
+            assert!(!verify_merkle_proof(&root, &leaves[(index + 1) % 5], index, &proof));
+        }
+
+        assert_eq!(merkle_root::<sha256::Hash>(&[]), sha256::Hash::all_zeros());
+    }
+
+    #[test]
+    fn incremental() {
+        let leaves: Vec<sha256d::Hash> = (0..70u8).map(|i| sha256d::Hash::hash(&[i])).collect();
+
+        let mut tree = IncrementalMerkle::new();
+        assert!(tree.is_empty());
+        assert_eq!(tree.root(), sha256d::Hash::all_zeros());
+        for (n, leaf) in leaves.iter().enumerate() {
+            tree.push(*leaf);
+            assert_eq!(tree.len(), n as u64 + 1);
+            assert_eq!(tree.root(), merkle_root(&leaves[..n + 1]), "{} leaves", n + 1);
+        }
+
+        let mut tree = IncrementalMerkle::default();
+        tree.push(txid("b1fea52486ce0c62bb442b530a3f0132b826c74e473d1f2c220bfa78111c5082"));
+        tree.push(txid("f4184fc596403b9d638783cf57adfe4c75c605f6356fbc91338530e9831e9e16"));
+        assert_eq!(
+            tree.root(),
+            txid("7dac2c5666815c17a3b36427de37bb9d2e2c5ccec3f8633eb91a4205cb4c10ff"),
+        );
+    }
+}
@@ -0,0 +1,191 @@
+// Bitcoin Hashes Library
+// Written in 2019 by
+//   The rust-bitcoin developers
+//
+// To the extent possible under law, the author(s) have dedicated all
+// copyright and related and neighboring rights to this software to
+// the public domain worldwide. This software is distributed without
+// any warranty.
+//
+// You should have received a copy of the CC0 Public Domain Dedication
+// along with this software.
+// If not, see <http://creativecommons.org/publicdomain/zero/1.0/>.
+//
+
+//! # Multihasher
+//!
+//! Computes several hashes of the same data in a single pass over it. The
+//! input is fed to the engines in chunks small enough to stay in the CPU
+//! cache, so data which is too large for the cache is read from memory once
+//! rather than once per hash.
+//!
+//! ```rust
+//! use bitcoin_hashes::{ripemd160, sha256, Hash};
+//! use bitcoin_hashes::multihasher::Multihasher;
+//!
+//! let mut hasher = Multihasher::<(sha256::Hash, ripemd160::Hash)>::new();
+//! hasher.input(b"hello");
+//! hasher.input(b" world");
+//! let (sha, ripemd) = hasher.finalize();
+//! assert_eq!(sha, sha256::Hash::hash(b"hello world"));
+//! assert_eq!(ripemd, ripemd160::Hash::hash(b"hello world"));
+//! ```
+
+use {Hash, HashEngine};
+
+/// Size of the chunks of input given to each engine in turn
+const CHUNK_SIZE: usize = 4096;
+
+/// A tuple of up to eight hash types which a `Multihasher` can compute
+pub trait HashTuple: Sized {
+    /// The tuple of the engines of the hashes
+    type Engines: Clone;
+
+    /// Constructs a new engine for each hash
+    fn engines() -> Self::Engines;
+
+    /// Adds data to every engine
+    fn input(engines: &mut Self::Engines, data: &[u8]);
+
+    /// Produces the hashes from the states of their engines
+    fn from_engines(engines: Self::Engines) -> Self;
+}
+
+macro_rules! hash_tuple_impl(
+    ($($hash:ident $idx:tt),*) => (
+        impl<$($hash: Hash),*> HashTuple for ($($hash,)*) {
+            type Engines = ($($hash::Engine,)*);
+
+            fn engines() -> Self::Engines {
+                ($($hash::engine(),)*)
+            }
+
+            fn input(engines: &mut Self::Engines, data: &[u8]) {
+                $(engines.$idx.input(data);)*
+            }
+
+            fn from_engines(engines: Self::Engines) -> Self {
+                ($($hash::from_engine(engines.$idx),)*)
+            }
+        }
+    )
+);
+
+hash_tuple_impl!(H0 0);
+hash_tuple_impl!(H0 0, H1 1);
+hash_tuple_impl!(H0 0, H1 1, H2 2);
+hash_tuple_impl!(H0 0, H1 1, H2 2, H3 3);
+hash_tuple_impl!(H0 0, H1 1, H2 2, H3 3, H4 4);
+hash_tuple_impl!(H0 0, H1 1, H2 2, H3 3, H4 4, H5 5);
+hash_tuple_impl!(H0 0, H1 1, H2 2, H3 3, H4 4, H5 5, H6 6);
+hash_tuple_impl!(H0 0, H1 1, H2 2, H3 3, H4 4, H5 5, H6 6, H7 7);
+
+/// Engine computing every hash of the tuple `T` over the same data
+pub struct Multihasher<T: HashTuple> {
+    engines: T::Engines,
+}
+
+impl<T: HashTuple> Clone for Multihasher<T> {
+    fn clone(&self) -> Self {
+        Multihasher { engines: self.engines.clone() }
+    }
+}
+
+impl<T: HashTuple> Default for Multihasher<T> {
+    fn default() -> Self {
+        Multihasher::new()
+    }
+}
+
+impl<T: HashTuple> Multihasher<T> {
+    /// Constructs a multihasher with a new engine for each hash
+    pub fn new() -> Multihasher<T> {
+        Multihasher { engines: T::engines() }
+    }
+
+    /// Adds data to every engine
+    pub fn input(&mut self, data: &[u8]) {
+        for chunk in data.chunks(CHUNK_SIZE) {
+            T::input(&mut self.engines, chunk);
+        }
+    }
+
+    /// Produces the hashes of all data input so far
+    pub fn finalize(self) -> T {
+        T::from_engines(self.engines)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: HashTuple> ::std::io::Write for Multihasher<T> {
+    fn flush(&mut self) -> ::std::io::Result<()> {
+        Ok(())
+    }
+
+    fn write(&mut self, inp: &[u8]) -> ::std::io::Result<usize> {
+        self.input(inp);
+        Ok(inp.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use multihasher::Multihasher;
+    use {ripemd160, sha256, sha256d, sha512, Hash};
+
+    #[test]
+    fn multihasher() {
+        // Long enough to span several chunks, and not a multiple of them
+        let data: Vec<u8> = (0..10_000u32).map(|i| i as u8).collect();
+
+        let mut hasher = Multihasher::<(sha256::Hash, ripemd160::Hash, sha512::Hash)>::new();
+        hasher.input(&data[..5]);
+        hasher.write_all(&data[5..]).expect("write to multihasher");
+        let (sha, ripemd, sha512) = hasher.clone().finalize();
+        assert_eq!(sha, sha256::Hash::hash(&data));
+        assert_eq!(ripemd, ripemd160::Hash::hash(&data));
+        assert_eq!(sha512, sha512::Hash::hash(&data));
+
+        // The same type may appear more than once
+        let mut hasher = Multihasher::<(sha256d::Hash, sha256d::Hash)>::default();
+        hasher.input(b"abc");
+        let (first, second) = hasher.finalize();
+        assert_eq!(first, second);
+        assert_eq!(first, sha256d::Hash::hash(b"abc"));
+
+        let (empty,) = Multihasher::<(sha256::Hash,)>::new().finalize();
+        assert_eq!(empty, sha256::Hash::hash(&[]));
+    }
+}
+
+#[cfg(all(test, feature="unstable"))]
+mod benches {
+    use test::Bencher;
+
+    use multihasher::Multihasher;
+    use {ripemd160, sha256, sha512, Hash};
+
+    const INPUT_SIZE: usize = 10 * 1024 * 1024;
+
+    #[bench]
+    pub fn multihasher_10m(bh: & mut Bencher) {
+        let bytes = vec![1u8; INPUT_SIZE];
+        bh.iter( || {
+            let mut hasher = Multihasher::<(sha256::Hash, ripemd160::Hash, sha512::Hash)>::new();
+            hasher.input(&bytes);
+            hasher.finalize()
+        });
+        bh.bytes = bytes.len() as u64;
+    }
+
+    #[bench]
+    pub fn sequential_10m(bh: & mut Bencher) {
+        let bytes = vec![1u8; INPUT_SIZE];
+        bh.iter( || {
+            (sha256::Hash::hash(&bytes), ripemd160::Hash::hash(&bytes), sha512::Hash::hash(&bytes))
+        });
+        bh.bytes = bytes.len() as u64;
+    }
+}
@@ -0,0 +1,309 @@
+// Bitcoin Hashes Library
+// Written in 2019 by
+//   The rust-bitcoin developers
+//
+// To the extent possible under law, the author(s) have dedicated all
+// copyright and related and neighboring rights to this software to
+// the public domain worldwide. This software is distributed without
+// any warranty.
+//
+// You should have received a copy of the CC0 Public Domain Dedication
+// along with this software.
+// If not, see <http://creativecommons.org/publicdomain/zero/1.0/>.
+//
+
+//! # Hash newtypes
+//!
+//! Support for defining semantically distinct hash types, such as txids and
+//! block hashes, which behave like the hash type they wrap.
+
+/// Defines a newtype over an existing hash type which implements `Hash` and
+/// every trait the wrapped hash implements: hex formatting, `FromStr`,
+/// indexing, ordering and, with the `serde` feature, serialization.
+///
+/// The newtype is displayed in the same order as its inner hash unless
+/// `display_backward` is given. Note that this only affects hex encoding
+/// (including human-readable serde formats); indexing and `to_byte_array`
+/// always give the bytes in their internal order.
+///
+/// ```
+/// #[macro_use] extern crate bitcoin_hashes;
+/// use bitcoin_hashes::{sha256, sha256d};
+///
+/// impl_hash_newtype!(
+///     /// A transaction id, displayed backward like its inner `sha256d` hash
+///     Txid, sha256d::Hash
+/// );
+/// impl_hash_newtype!(
+///     /// A SHA256 hash displayed backward
+///     ReversedHash, sha256::Hash, display_backward: true
+/// );
+/// # fn main() {}
+/// ```
+#[macro_export]
+macro_rules! impl_hash_newtype {
+    ($(#[$attr:meta])* $newtype:ident, $hash:ty) => {
+        $crate::impl_hash_newtype!(
+            $(#[$attr])* $newtype, $hash,
+            display_backward: <$hash as $crate::Hash>::DISPLAY_BACKWARD
+        );
+    };
+    ($(#[$attr:meta])* $newtype:ident, $hash:ty, display_backward: $backward:expr) => {
+        $(#[$attr])*
+        #[derive(Copy, Clone, PartialEq, Eq, Default, PartialOrd, Ord, Hash)]
+        pub struct $newtype(pub $hash);
+
+        impl $crate::Hash for $newtype {
+            type Engine = <$hash as $crate::Hash>::Engine;
+            type Inner = <$hash as $crate::Hash>::Inner;
+
+            fn engine() -> Self::Engine {
+                <$hash as $crate::Hash>::engine()
+            }
+
+            fn from_engine(e: Self::Engine) -> $newtype {
+                $newtype(<$hash as $crate::Hash>::from_engine(e))
+            }
+
+            const LEN: usize = <$hash as $crate::Hash>::LEN;
+
+            fn from_slice(sl: &[u8]) -> Result<$newtype, $crate::Error> {
+                <$hash as $crate::Hash>::from_slice(sl).map($newtype)
+            }
+
+            const DISPLAY_BACKWARD: bool = $backward;
+
+            fn from_byte_array(bytes: Self::Inner) -> Self {
+                $newtype(<$hash as $crate::Hash>::from_byte_array(bytes))
+            }
+
+            fn to_byte_array(self) -> Self::Inner {
+                <$hash as $crate::Hash>::to_byte_array(self.0)
+            }
+        }
+
+        impl From<$hash> for $newtype {
+            fn from(hash: $hash) -> $newtype {
+                $newtype(hash)
+            }
+        }
+
+        impl From<$newtype> for $hash {
+            fn from(hash: $newtype) -> $hash {
+                hash.0
+            }
+        }
+
+        impl $crate::_export::fmt::LowerHex for $newtype {
+            fn fmt(&self, f: &mut $crate::_export::fmt::Formatter) -> $crate::_export::fmt::Result {
+                if <$newtype as $crate::Hash>::DISPLAY_BACKWARD {
+                    $crate::hex::format_hex_reverse(&self[..], f)
+                } else {
+                    $crate::hex::format_hex(&self[..], f)
+                }
+            }
+        }
+
+        impl $crate::_export::fmt::UpperHex for $newtype {
+            fn fmt(&self, f: &mut $crate::_export::fmt::Formatter) -> $crate::_export::fmt::Result {
+                if <$newtype as $crate::Hash>::DISPLAY_BACKWARD {
+                    $crate::hex::format_hex_reverse_uppercase(&self[..], f)
+                } else {
+                    $crate::hex::format_hex_uppercase(&self[..], f)
+                }
+            }
+        }
+
+        impl $crate::_export::fmt::Display for $newtype {
+            fn fmt(&self, f: &mut $crate::_export::fmt::Formatter) -> $crate::_export::fmt::Result {
+                $crate::_export::fmt::LowerHex::fmt(self, f)
+            }
+        }
+
+        impl $crate::_export::fmt::Debug for $newtype {
+            fn fmt(&self, f: &mut $crate::_export::fmt::Formatter) -> $crate::_export::fmt::Result {
+                $crate::_export::fmt::LowerHex::fmt(self, f)
+            }
+        }
+
+        impl $crate::_export::str::FromStr for $newtype {
+            type Err = $crate::Error;
+            fn from_str(s: &str) -> Result<$newtype, $crate::Error> {
+                $crate::hex::FromHex::from_hex(s)
+            }
+        }
+
+        impl $crate::_export::ops::Index<usize> for $newtype {
+            type Output = u8;
+            fn index(&self, index: usize) -> &u8 {
+                &self.0[index]
+            }
+        }
+
+        impl $crate::_export::ops::Index<$crate::_export::ops::Range<usize>> for $newtype {
+            type Output = [u8];
+            fn index(&self, index: $crate::_export::ops::Range<usize>) -> &[u8] {
+                &self.0[index]
+            }
+        }
+
+        impl $crate::_export::ops::Index<$crate::_export::ops::RangeFrom<usize>> for $newtype {
+            type Output = [u8];
+            fn index(&self, index: $crate::_export::ops::RangeFrom<usize>) -> &[u8] {
+                &self.0[index]
+            }
+        }
+
+        impl $crate::_export::ops::Index<$crate::_export::ops::RangeTo<usize>> for $newtype {
+            type Output = [u8];
+            fn index(&self, index: $crate::_export::ops::RangeTo<usize>) -> &[u8] {
+                &self.0[index]
+            }
+        }
+
+        impl $crate::_export::ops::Index<$crate::_export::ops::RangeFull> for $newtype {
+            type Output = [u8];
+            fn index(&self, index: $crate::_export::ops::RangeFull) -> &[u8] {
+                &self.0[index]
+            }
+        }
+
+        impl $crate::_export::borrow::Borrow<[u8]> for $newtype {
+            fn borrow(&self) -> &[u8] {
+                &self[..]
+            }
+        }
+
+        impl $crate::_export::convert::AsRef<[u8]> for $newtype {
+            fn as_ref(&self) -> &[u8] {
+                &self[..]
+            }
+        }
+
+        $crate::__hash_newtype_as_mut_impl!($newtype);
+        $crate::__hash_newtype_serde_impl!($newtype, $hash);
+    };
+}
+
+/// Implements `AsMut<[u8]>` for a hash newtype; empty without the `as_mut` feature
+#[cfg(feature = "as_mut")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __hash_newtype_as_mut_impl {
+    ($newtype:ident) => {
+        impl $crate::_export::convert::AsMut<[u8]> for $newtype {
+            fn as_mut(&mut self) -> &mut [u8] {
+                $crate::_export::convert::AsMut::as_mut(&mut self.0)
+            }
+        }
+    };
+}
+
+/// Implements `AsMut<[u8]>` for a hash newtype; empty without the `as_mut` feature
+#[cfg(not(feature = "as_mut"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __hash_newtype_as_mut_impl {
+    ($newtype:ident) => {};
+}
+
+/// Implements serde traits for a hash newtype; empty without the `serde` feature
+#[cfg(feature = "serde")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __hash_newtype_serde_impl {
+    ($newtype:ident, $hash:ty) => {
+        impl $crate::serde::Serialize for $newtype {
+            fn serialize<S: $crate::serde::Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+                if s.is_human_readable() {
+                    s.collect_str(self)
+                } else {
+                    $crate::serde::Serialize::serialize(&self.0, s)
+                }
+            }
+        }
+
+        impl<'de> $crate::serde::Deserialize<'de> for $newtype {
+            fn deserialize<D: $crate::serde::Deserializer<'de>>(d: D) -> Result<$newtype, D::Error> {
+                if d.is_human_readable() {
+                    // The inner hash parses the hex in its own display order,
+                    // which may be the reverse of ours
+                    let hash = <$hash as $crate::serde::Deserialize>::deserialize(d)?;
+                    let bytes = <$hash as $crate::Hash>::to_display_byte_array(hash);
+                    Ok(<$newtype as $crate::Hash>::from_display_byte_array(bytes))
+                } else {
+                    <$hash as $crate::serde::Deserialize>::deserialize(d).map($newtype)
+                }
+            }
+        }
+    };
+}
+
+/// Implements serde traits for a hash newtype; empty without the `serde` feature
+#[cfg(not(feature = "serde"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __hash_newtype_serde_impl {
+    ($newtype:ident, $hash:ty) => {};
+}
+
+#[cfg(test)]
+mod tests {
+    use hex::{FromHex, ToHex};
+    use {sha256d, Hash, HashEngine};
+
+    impl_hash_newtype!(
+        /// A transaction id
+        Txid, sha256d::Hash
+    );
+    impl_hash_newtype!(ForwardHash, sha256d::Hash, display_backward: false);
+
+    #[test]
+    fn newtype() {
+        let hash = sha256d::Hash::hash(b"abc");
+        let txid = Txid::hash(b"abc");
+        assert_eq!(txid, Txid::from(hash));
+        assert_eq!(sha256d::Hash::from(txid), hash);
+        assert_eq!(&txid[..], &hash[..]);
+        assert_eq!(txid.to_byte_array(), hash.to_byte_array());
+
+        // Inherits the display direction of the inner hash by default
+        assert_eq!(txid.to_hex(), hash.to_hex());
+        assert_eq!(format!("{:X}", txid), format!("{:X}", hash));
+        assert_eq!(txid.to_string().parse::<Txid>(), Ok(txid));
+        assert_eq!(Txid::from_hex(&hash.to_hex()), Ok(txid));
+
+        // ...but can override it
+        let fwd = ForwardHash::hash(b"abc");
+        assert_eq!(fwd.to_hex(), hash[..].to_hex());
+        assert_eq!(ForwardHash::from_hex(&hash[..].to_hex()), Ok(fwd));
+        assert_eq!(&fwd[..], &txid[..]);
+
+        let mut engine = Txid::engine();
+        engine.input(b"abc");
+        assert_eq!(Txid::from_engine(engine), txid);
+        assert_eq!(Txid::from_slice(&[0; 31]), Err(::Error::InvalidLength(32, 31)));
+        assert!(Txid::all_zeros() < txid);
+    }
+
+    #[cfg(feature="serde")]
+    #[test]
+    fn newtype_serde() {
+        use serde_test::{Configure, Token, assert_tokens};
+
+        static HASH_BYTES: [u8; 32] = [
+            0xef, 0x53, 0x7f, 0x25, 0xc8, 0x95, 0xbf, 0xa7,
+            0x82, 0x52, 0x65, 0x29, 0xa9, 0xb6, 0x3d, 0x97,
+            0xaa, 0x63, 0x15, 0x64, 0xd5, 0xd7, 0x89, 0xc2,
+            0xb7, 0x65, 0x44, 0x8c, 0x86, 0x35, 0xfb, 0x6c,
+        ];
+
+        let txid = Txid::from_slice(&HASH_BYTES).expect("right number of bytes");
+        assert_tokens(&txid.compact(), &[Token::BorrowedBytes(&HASH_BYTES[..])]);
+        assert_tokens(&txid.readable(), &[Token::Str("6cfb35868c4465b7c289d7d5641563aa973db6a929655282a7bf95c8257f53ef")]);
+
+        let fwd = ForwardHash::from_slice(&HASH_BYTES).expect("right number of bytes");
+        assert_tokens(&fwd.compact(), &[Token::BorrowedBytes(&HASH_BYTES[..])]);
+        assert_tokens(&fwd.readable(), &[Token::Str("ef537f25c895bfa782526529a9b63d97aa631564d5d789c2b765448c8635fb6c")]);
+    }
+}
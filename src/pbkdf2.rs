@@ -0,0 +1,122 @@
+// Bitcoin Hashes Library
+// Written in 2019 by
+//   The rust-bitcoin developers
+//
+// To the extent possible under law, the author(s) have dedicated all
+// copyright and related and neighboring rights to this software to
+// the public domain worldwide. This software is distributed without
+// any warranty.
+//
+// You should have received a copy of the CC0 Public Domain Dedication
+// along with this software.
+// If not, see <http://creativecommons.org/publicdomain/zero/1.0/>.
+//
+
+//! # PBKDF2
+//!
+//! The password-based key derivation function from RFC 8018 with HMAC as
+//! its pseudorandom function, as used by BIP39 to derive seeds from
+//! mnemonics.
+
+use byteorder::{ByteOrder, BigEndian};
+
+use hmac::{Hmac, HmacEngine};
+use {sha256, sha512, Hash, HashEngine};
+
+/// Fills `output` with the key derived from `password` and `salt` with
+/// `iterations` rounds of HMAC with the hash `H`. Any length of output may
+/// be derived; it is computed one hash-sized block at a time, without
+/// allocating.
+///
+/// Panics if `iterations` is zero.
+pub fn pbkdf2<H: Hash>(password: &[u8], salt: &[u8], iterations: u32, output: &mut [u8]) {
+    assert!(iterations > 0, "PBKDF2 needs at least one iteration");
+
+    // Keying HMAC hashes the password, so only do it once
+    let prf = HmacEngine::<H>::new(password);
+
+    for (i, block) in output.chunks_mut(H::LEN).enumerate() {
+        let mut index = [0; 4];
+        BigEndian::write_u32(&mut index, i as u32 + 1);
+
+        let mut engine = prf.clone();
+        engine.input(salt);
+        engine.input(&index);
+        let mut u = Hmac::<H>::from_engine(engine);
+        block.copy_from_slice(&u[..block.len()]);
+
+        for _ in 1..iterations {
+            let mut engine = prf.clone();
+            engine.input(&u[..]);
+            u = Hmac::from_engine(engine);
+            for (out, byte) in block.iter_mut().zip(u[..].iter()) {
+                *out ^= *byte;
+            }
+        }
+    }
+}
+
+/// PBKDF2 with HMAC-SHA256
+pub fn pbkdf2_hmac_sha256(password: &[u8], salt: &[u8], iterations: u32, output: &mut [u8]) {
+    pbkdf2::<sha256::Hash>(password, salt, iterations, output)
+}
+
+/// PBKDF2 with HMAC-SHA512, which BIP39 uses with 2048 iterations
+pub fn pbkdf2_hmac_sha512(password: &[u8], salt: &[u8], iterations: u32, output: &mut [u8]) {
+    pbkdf2::<sha512::Hash>(password, salt, iterations, output)
+}
+
+#[cfg(test)]
+mod tests {
+    use hex::FromHex;
+    use pbkdf2::{pbkdf2_hmac_sha256, pbkdf2_hmac_sha512};
+
+    #[test]
+    fn test_sha256() {
+        let tests = vec![
+            // Test vectors from RFC 7914, section 11
+            ("passwd", "salt", 1,
+             "55ac046e56e3089fec1691c22544b605f94185216dde0465e68b9d57c20dacbc\
+              49ca9cccf179b645991664b39d77ef317c71b845b1e30bd509112041d3a19783"),
+            // Commonly used vectors in the style of RFC 6070
+            ("password", "salt", 1, "120fb6cffcf8b32c43e7225256c4f837a86548c92ccc35480805987cb70be17b"),
+            ("password", "salt", 2, "ae4d0c95af6b46d32d0adff928f06dd02a303f8ef3c251dfd6e2d85a95474c43"),
+            ("password", "salt", 4096, "c5e478d59288c841aa530db6845c4c8d962893a001ce4e11a4963873aa98134a"),
+            // An output which is not a whole number of blocks
+            ("passwordPASSWORDpassword", "saltSALTsaltSALTsaltSALTsaltSALTsalt", 4096,
+             "348c89dbcbd32b2f32d814b8116e84cf2b17347ebc1800181c4e2a1fb8dd53e1c635518c7dac47e9"),
+        ];
+
+        for (password, salt, iterations, expected) in tests {
+            let expected = Vec::<u8>::from_hex(expected).expect("valid hex");
+            let mut output = vec![0; expected.len()];
+            pbkdf2_hmac_sha256(password.as_bytes(), salt.as_bytes(), iterations, &mut output);
+            assert_eq!(output, expected);
+        }
+    }
+
+    #[test]
+    fn test_bip39() {
+        let tests = vec![
+            // Test vectors from BIP39, with the passphrase "TREZOR"
+            ("abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+             "c55257c360c07c72029aebc1b53c05ed0362ada38ead3e3e9efa3708e5349553\
+              1f09a6987599d18264c1e1c92f2cf141630c7a3c4ab7c81b2f001698e7463b04"),
+            ("legal winner thank year wave sausage worth useful legal winner thank yellow",
+             "2e8905819b8723fe2c1d161860e5ee1830318dbf49a83bd451cfb8440c28bd6f\
+              a457fe1296106559a3c80937a1c1069be3a3a5bd381ee6260e8d9739fce1f607"),
+        ];
+
+        for (mnemonic, seed) in tests {
+            let mut output = [0; 64];
+            pbkdf2_hmac_sha512(mnemonic.as_bytes(), b"mnemonicTREZOR", 2048, &mut output);
+            assert_eq!(&output[..], &<[u8; 64]>::from_hex(seed).expect("valid hex")[..]);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "at least one iteration")]
+    fn zero_iterations() {
+        pbkdf2_hmac_sha256(b"password", b"salt", 0, &mut [0; 32]);
+    }
+}
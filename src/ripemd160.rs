@@ -35,6 +35,9 @@ pub struct HashEngine {
 }
 
 write_impl!(HashEngine);
+extend_impl!(HashEngine, Hash);
+engine_default_impl!(HashEngine, Hash);
+zeroize_engine_impl!(HashEngine);
 
 impl Clone for HashEngine {
     fn clone(&self) -> HashEngine {
@@ -64,6 +67,8 @@ impl EngineTrait for HashEngine {
     }
 
     const BLOCK_SIZE: usize = 64;
+
+    engine_input_impl!();
 }
 
 /// Output of the RIPEMD160 hash function
@@ -73,9 +78,22 @@ pub struct Hash([u8; 20]);
 hex_fmt_impl!(Debug, Hash);
 hex_fmt_impl!(Display, Hash);
 hex_fmt_impl!(LowerHex, Hash);
+hex_fmt_impl!(UpperHex, Hash);
 index_impl!(Hash);
 serde_impl!(Hash, 20);
 borrow_slice_impl!(Hash);
+from_str_impl!(Hash);
+try_from_impl!(Hash);
+from_bytes_impl!(Hash, 20);
+into_iter_impl!(Hash);
+eq_bytes_impl!(Hash, 20);
+deref_impl!(Hash, 20);
+inner_ref_impl!(Hash, 20);
+ct_eq_impl!(Hash);
+zeroize_impl!(Hash);
+rand_impl!(Hash);
+proptest_impl!(Hash);
+quickcheck_impl!(Hash);
 
 impl HashTrait for Hash {
     type Engine = HashEngine;
@@ -91,22 +109,21 @@ impl HashTrait for Hash {
 
     #[cfg(not(feature = "fuzztarget"))]
     fn from_engine(mut e: HashEngine) -> Hash {
-        use std::io::Write;
-        use byteorder::WriteBytesExt;
-
         // pad buffer with a single 1-bit then all 0s, until there are exactly 8 bytes remaining
         let data_len = e.length as u64;
 
         let zeroes = [0; BLOCK_SIZE - 8];
-        e.write_all(&[0x80]).unwrap();
+        e.input(&[0x80]);
         if e.length % BLOCK_SIZE > zeroes.len() {
-            e.write_all(&zeroes).unwrap();
+            e.input(&zeroes);
         }
         let pad_length = zeroes.len() - (e.length % BLOCK_SIZE);
-        e.write_all(&zeroes[..pad_length]).unwrap();
+        e.input(&zeroes[..pad_length]);
         debug_assert_eq!(e.length % BLOCK_SIZE, zeroes.len());
 
-        e.write_u64::<LittleEndian>(8 * data_len).unwrap();
+        let mut len_bytes = [0; 8];
+        LittleEndian::write_u64(&mut len_bytes, 8 * data_len);
+        e.input(&len_bytes);
         debug_assert_eq!(e.length % BLOCK_SIZE, 0);
 
         Hash(e.midstate())
@@ -131,7 +148,11 @@ impl HashTrait for Hash {
         }
     }
 
-    fn into_inner(self) -> Self::Inner {
+    fn from_byte_array(bytes: Self::Inner) -> Self {
+        Hash(bytes)
+    }
+
+    fn to_byte_array(self) -> Self::Inner {
         self.0
     }
 }
@@ -237,6 +258,11 @@ macro_rules! process_block(
 );
 
 impl HashEngine {
+    /// Returns the number of bytes input so far
+    pub fn n_bytes_hashed(&self) -> u64 {
+        self.length as u64
+    }
+
     fn process_block(&mut self) {
         debug_assert_eq!(self.buffer.len(), BLOCK_SIZE);
 
@@ -468,6 +494,73 @@ mod tests {
                 ],
                 output_str: "12a053384a9c0c88e405a06c27dcf49ada62eb2b"
             },
+            // Test vectors from the RIPEMD-160 reference page
+            Test {
+                input: "",
+                output: vec![
+                    0x9c, 0x11, 0x85, 0xa5,
+                    0xc5, 0xe9, 0xfc, 0x54,
+                    0x61, 0x28, 0x08, 0x97,
+                    0x7e, 0xe8, 0xf5, 0x48,
+                    0xb2, 0x25, 0x8d, 0x31,
+                ],
+                output_str: "9c1185a5c5e9fc54612808977ee8f548b2258d31"
+            },
+            Test {
+                input: "a",
+                output: vec![
+                    0x0b, 0xdc, 0x9d, 0x2d,
+                    0x25, 0x6b, 0x3e, 0xe9,
+                    0xda, 0xae, 0x34, 0x7b,
+                    0xe6, 0xf4, 0xdc, 0x83,
+                    0x5a, 0x46, 0x7f, 0xfe,
+                ],
+                output_str: "0bdc9d2d256b3ee9daae347be6f4dc835a467ffe"
+            },
+            Test {
+                input: "message digest",
+                output: vec![
+                    0x5d, 0x06, 0x89, 0xef,
+                    0x49, 0xd2, 0xfa, 0xe5,
+                    0x72, 0xb8, 0x81, 0xb1,
+                    0x23, 0xa8, 0x5f, 0xfa,
+                    0x21, 0x59, 0x5f, 0x36,
+                ],
+                output_str: "5d0689ef49d2fae572b881b123a85ffa21595f36"
+            },
+            Test {
+                input: "abcdefghijklmnopqrstuvwxyz",
+                output: vec![
+                    0xf7, 0x1c, 0x27, 0x10,
+                    0x9c, 0x69, 0x2c, 0x1b,
+                    0x56, 0xbb, 0xdc, 0xeb,
+                    0x5b, 0x9d, 0x28, 0x65,
+                    0xb3, 0x70, 0x8d, 0xbc,
+                ],
+                output_str: "f71c27109c692c1b56bbdceb5b9d2865b3708dbc"
+            },
+            Test {
+                input: "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789",
+                output: vec![
+                    0xb0, 0xe2, 0x0b, 0x6e,
+                    0x31, 0x16, 0x64, 0x02,
+                    0x86, 0xed, 0x3a, 0x87,
+                    0xa5, 0x71, 0x30, 0x79,
+                    0xb2, 0x1f, 0x51, 0x89,
+                ],
+                output_str: "b0e20b6e3116640286ed3a87a5713079b21f5189"
+            },
+            Test {
+                input: "12345678901234567890123456789012345678901234567890123456789012345678901234567890",
+                output: vec![
+                    0x9b, 0x75, 0x2e, 0x45,
+                    0x57, 0x3d, 0x4b, 0x39,
+                    0xf4, 0xdb, 0xd3, 0x32,
+                    0x3c, 0xab, 0x82, 0xbf,
+                    0x63, 0x32, 0x6b, 0xfb,
+                ],
+                output_str: "9b752e45573d4b39f4dbd3323cab82bf63326bfb"
+            },
             // Examples from wikipedia
             Test {
                 input: "The quick brown fox jumps over the lazy dog",
@@ -507,7 +600,7 @@ mod tests {
             }
             let manual_hash = ripemd160::Hash::from_engine(engine);
             assert_eq!(hash, manual_hash);
-            assert_eq!(hash.into_inner()[..].as_ref(), test.output.as_slice());
+            assert_eq!(hash.to_byte_array()[..].as_ref(), test.output.as_slice());
         }
     }
 
@@ -6,9 +6,8 @@ macro_rules! serde_impl(
     ($t:ident, $len:expr) => (
         impl ::serde::Serialize for $t {
             fn serialize<S: ::serde::Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
-                use hex::ToHex;
                 if s.is_human_readable() {
-                    s.serialize_str(&self.to_hex())
+                    s.collect_str(self)
                 } else {
                     s.serialize_bytes(&self[..])
                 }
@@ -25,7 +24,7 @@ macro_rules! serde_impl(
                     impl<'de> ::serde::de::Visitor<'de> for HexVisitor {
                         type Value = $t;
 
-                        fn expecting(&self, formatter: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                        fn expecting(&self, formatter: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
                             formatter.write_str("an ASCII hex string")
                         }
 
@@ -33,7 +32,7 @@ macro_rules! serde_impl(
                         where
                             E: ::serde::de::Error,
                         {
-                            if let Ok(hex) = ::std::str::from_utf8(v) {
+                            if let Ok(hex) = ::core::str::from_utf8(v) {
                                 $t::from_hex(hex).map_err(E::custom)
                             } else {
                                 return Err(E::invalid_value(::serde::de::Unexpected::Bytes(v), &self));
@@ -55,7 +54,7 @@ macro_rules! serde_impl(
                     impl<'de> ::serde::de::Visitor<'de> for BytesVisitor {
                         type Value = $t;
 
-                        fn expecting(&self, formatter: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                        fn expecting(&self, formatter: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
                             formatter.write_str("a bytestring")
                         }
 
@@ -13,6 +13,10 @@
 //
 
 //! # SHA1
+//!
+//! SHA1 is not collision resistant, and practical collisions are known, so
+//! it must not be used for anything new. It is provided for verifying
+//! existing data which uses it, most notably Bitcoin Script's `OP_SHA1`.
 
 use byteorder::{ByteOrder, BigEndian};
 
@@ -30,6 +34,9 @@ pub struct HashEngine {
 }
 
 write_impl!(HashEngine);
+extend_impl!(HashEngine, Hash);
+engine_default_impl!(HashEngine, Hash);
+zeroize_engine_impl!(HashEngine);
 
 impl Clone for HashEngine {
     fn clone(&self) -> HashEngine {
@@ -51,6 +58,8 @@ impl EngineTrait for HashEngine {
     }
 
     const BLOCK_SIZE: usize = 64;
+
+    engine_input_impl!();
 }
 
 /// Output of the SHA1 hash function
@@ -60,9 +69,22 @@ pub struct Hash([u8; 20]);
 hex_fmt_impl!(Debug, Hash);
 hex_fmt_impl!(Display, Hash);
 hex_fmt_impl!(LowerHex, Hash);
+hex_fmt_impl!(UpperHex, Hash);
 index_impl!(Hash);
 serde_impl!(Hash, 20);
 borrow_slice_impl!(Hash);
+from_str_impl!(Hash);
+try_from_impl!(Hash);
+from_bytes_impl!(Hash, 20);
+into_iter_impl!(Hash);
+eq_bytes_impl!(Hash, 20);
+deref_impl!(Hash, 20);
+inner_ref_impl!(Hash, 20);
+ct_eq_impl!(Hash);
+zeroize_impl!(Hash);
+rand_impl!(Hash);
+proptest_impl!(Hash);
+quickcheck_impl!(Hash);
 
 impl HashTrait for Hash {
     type Engine = HashEngine;
@@ -77,22 +99,21 @@ impl HashTrait for Hash {
     }
 
     fn from_engine(mut e: HashEngine) -> Hash {
-        use std::io::Write;
-        use byteorder::WriteBytesExt;
-
         // pad buffer with a single 1-bit then all 0s, until there are exactly 8 bytes remaining
         let data_len = e.length as u64;
 
         let zeroes = [0; BLOCK_SIZE - 8];
-        e.write_all(&[0x80]).unwrap();
+        e.input(&[0x80]);
         if e.length % BLOCK_SIZE > zeroes.len() {
-            e.write_all(&zeroes).unwrap();
+            e.input(&zeroes);
         }
         let pad_length = zeroes.len() - (e.length % BLOCK_SIZE);
-        e.write_all(&zeroes[..pad_length]).unwrap();
+        e.input(&zeroes[..pad_length]);
         debug_assert_eq!(e.length % BLOCK_SIZE, zeroes.len());
 
-        e.write_u64::<BigEndian>(8 * data_len).unwrap();
+        let mut len_bytes = [0; 8];
+        BigEndian::write_u64(&mut len_bytes, 8 * data_len);
+        e.input(&len_bytes);
         debug_assert_eq!(e.length % BLOCK_SIZE, 0);
 
         Hash(e.midstate())
@@ -110,12 +131,21 @@ impl HashTrait for Hash {
         }
     }
 
-    fn into_inner(self) -> Self::Inner {
+    fn from_byte_array(bytes: Self::Inner) -> Self {
+        Hash(bytes)
+    }
+
+    fn to_byte_array(self) -> Self::Inner {
         self.0
     }
 }
 
 impl HashEngine {
+    /// Returns the number of bytes input so far
+    pub fn n_bytes_hashed(&self) -> u64 {
+        self.length as u64
+    }
+
     // Basic unoptimized algorithm from Wikipedia
     fn process_block(&mut self) {
         debug_assert_eq!(self.buffer.len(), BLOCK_SIZE);
@@ -209,6 +239,29 @@ mod tests {
                 ],
                 output_str: "de9f2c7fd25e1b3afad3e85a0bd17d9b100db4b3",
             },
+            // Examples from FIPS 180-4
+            Test {
+                input: "abc",
+                output: vec![
+                    0xa9, 0x99, 0x3e, 0x36,
+                    0x47, 0x06, 0x81, 0x6a,
+                    0xba, 0x3e, 0x25, 0x71,
+                    0x78, 0x50, 0xc2, 0x6c,
+                    0x9c, 0xd0, 0xd8, 0x9d,
+                ],
+                output_str: "a9993e364706816aba3e25717850c26c9cd0d89d",
+            },
+            Test {
+                input: "abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq",
+                output: vec![
+                    0x84, 0x98, 0x3e, 0x44,
+                    0x1c, 0x3b, 0xd2, 0x6e,
+                    0xba, 0xae, 0x4a, 0xa1,
+                    0xf9, 0x51, 0x29, 0xe5,
+                    0xe5, 0x46, 0x70, 0xf1,
+                ],
+                output_str: "84983e441c3bd26ebaae4aa1f95129e5e54670f1",
+            },
         ];
 
         for test in tests {
@@ -225,7 +278,7 @@ mod tests {
             }
             let manual_hash = sha1::Hash::from_engine(engine);
             assert_eq!(hash, manual_hash);
-            assert_eq!(hash.into_inner()[..].as_ref(), test.output.as_slice());
+            assert_eq!(hash.to_byte_array()[..].as_ref(), test.output.as_slice());
         }
     }
 
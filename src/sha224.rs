@@ -0,0 +1,246 @@
+// Bitcoin Hashes Library
+// Written in 2019 by
+//   The rust-bitcoin developers
+//
+// To the extent possible under law, the author(s) have dedicated all
+// copyright and related and neighboring rights to this software to
+// the public domain worldwide. This software is distributed without
+// any warranty.
+//
+// You should have received a copy of the CC0 Public Domain Dedication
+// along with this software.
+// If not, see <http://creativecommons.org/publicdomain/zero/1.0/>.
+//
+
+//! # SHA224
+//!
+//! SHA256 started from a distinct initial state and truncated to 28 bytes,
+//! as specified in FIPS 180-4.
+
+use sha256;
+use HashEngine as EngineTrait;
+use Hash as HashTrait;
+use Error;
+
+/// Engine to compute SHA224 hash function
+#[derive(Clone)]
+pub struct HashEngine(sha256::HashEngine);
+
+write_impl!(HashEngine);
+extend_impl!(HashEngine, Hash);
+engine_default_impl!(HashEngine, Hash);
+zeroize_impl!(HashEngine);
+
+// The inner engine zeroizes itself when dropped
+#[cfg(feature = "zeroize")]
+impl ::zeroize::ZeroizeOnDrop for HashEngine {}
+
+impl HashEngine {
+    /// Returns the number of bytes input so far
+    pub fn n_bytes_hashed(&self) -> u64 {
+        self.0.n_bytes_hashed()
+    }
+}
+
+impl EngineTrait for HashEngine {
+    type MidState = [u8; 32];
+
+    fn midstate(&self) -> [u8; 32] {
+        self.0.midstate().to_byte_array()
+    }
+
+    const BLOCK_SIZE: usize = 64;
+
+    fn input(&mut self, data: &[u8]) {
+        self.0.input(data)
+    }
+}
+
+/// Output of the SHA224 hash function
+#[derive(Copy, Clone, PartialEq, Eq, Default, PartialOrd, Ord, Hash)]
+pub struct Hash([u8; 28]);
+
+hex_fmt_impl!(Debug, Hash);
+hex_fmt_impl!(Display, Hash);
+hex_fmt_impl!(LowerHex, Hash);
+hex_fmt_impl!(UpperHex, Hash);
+index_impl!(Hash);
+serde_impl!(Hash, 28);
+borrow_slice_impl!(Hash);
+from_str_impl!(Hash);
+try_from_impl!(Hash);
+from_bytes_impl!(Hash, 28);
+into_iter_impl!(Hash);
+eq_bytes_impl!(Hash, 28);
+deref_impl!(Hash, 28);
+inner_ref_impl!(Hash, 28);
+ct_eq_impl!(Hash);
+zeroize_impl!(Hash);
+rand_impl!(Hash);
+proptest_impl!(Hash);
+quickcheck_impl!(Hash);
+
+impl HashTrait for Hash {
+    type Engine = HashEngine;
+    type Inner = [u8; 28];
+
+    fn engine() -> HashEngine {
+        HashEngine(sha256::HashEngine::from_iv([
+            0xc1059ed8, 0x367cd507, 0x3070dd17, 0xf70e5939,
+            0xffc00b31, 0x68581511, 0x64f98fa7, 0xbefa4fa4,
+        ]))
+    }
+
+    fn from_engine(e: HashEngine) -> Hash {
+        let mut ret = [0; 28];
+        ret.copy_from_slice(&sha256::Hash::from_engine(e.0)[..28]);
+        Hash(ret)
+    }
+
+    const LEN: usize = 28;
+
+    fn from_slice(sl: &[u8]) -> Result<Hash, Error> {
+        if sl.len() != 28 {
+            Err(Error::InvalidLength(Self::LEN, sl.len()))
+        } else {
+            let mut ret = [0; 28];
+            ret.copy_from_slice(sl);
+            Ok(Hash(ret))
+        }
+    }
+
+    fn from_byte_array(bytes: Self::Inner) -> Self {
+        Hash(bytes)
+    }
+
+    fn to_byte_array(self) -> Self::Inner {
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use sha224;
+    use hex::{FromHex, ToHex};
+    use Hash;
+
+    #[derive(Clone)]
+    struct Test {
+        input: Vec<u8>,
+        output: Vec<u8>,
+        output_str: &'static str,
+    }
+
+    #[test]
+    fn test() {
+        let tests = vec![
+            // Test vectors from the NIST examples
+            Test {
+                input: vec![],
+                output: vec![
+                    0xd1, 0x4a, 0x02, 0x8c, 0x2a, 0x3a, 0x2b, 0xc9,
+                    0x47, 0x61, 0x02, 0xbb, 0x28, 0x82, 0x34, 0xc4,
+                    0x15, 0xa2, 0xb0, 0x1f, 0x82, 0x8e, 0xa6, 0x2a,
+                    0xc5, 0xb3, 0xe4, 0x2f,
+                ],
+                output_str: "d14a028c2a3a2bc9476102bb288234c415a2b01f828ea62ac5b3e42f",
+            },
+            Test {
+                input: b"abc".to_vec(),
+                output: vec![
+                    0x23, 0x09, 0x7d, 0x22, 0x34, 0x05, 0xd8, 0x22,
+                    0x86, 0x42, 0xa4, 0x77, 0xbd, 0xa2, 0x55, 0xb3,
+                    0x2a, 0xad, 0xbc, 0xe4, 0xbd, 0xa0, 0xb3, 0xf7,
+                    0xe3, 0x6c, 0x9d, 0xa7,
+                ],
+                output_str: "23097d223405d8228642a477bda255b32aadbce4bda0b3f7e36c9da7",
+            },
+            Test {
+                input: b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq".to_vec(),
+                output: vec![
+                    0x75, 0x38, 0x8b, 0x16, 0x51, 0x27, 0x76, 0xcc,
+                    0x5d, 0xba, 0x5d, 0xa1, 0xfd, 0x89, 0x01, 0x50,
+                    0xb0, 0xc6, 0x45, 0x5c, 0xb4, 0xf5, 0x8b, 0x19,
+                    0x52, 0x52, 0x25, 0x25,
+                ],
+                output_str: "75388b16512776cc5dba5da1fd890150b0c6455cb4f58b1952522525",
+            },
+        ];
+
+        for test in tests {
+            // Hash through high-level API, check hex encoding/decoding
+            let hash = sha224::Hash::hash(&test.input);
+            assert_eq!(hash, sha224::Hash::from_hex(test.output_str).expect("parse hex"));
+            assert_eq!(&hash[..], &test.output[..]);
+            assert_eq!(&hash.to_hex(), &test.output_str);
+
+            // Hash through engine, checking that we can input byte by byte
+            let mut engine = sha224::Hash::engine();
+            for ch in &test.input {
+                engine.write_all(&[*ch]).expect("write to engine");
+            }
+            let manual_hash = sha224::Hash::from_engine(engine);
+            assert_eq!(hash, manual_hash);
+            assert_eq!(hash.to_byte_array()[..].as_ref(), test.output.as_slice());
+        }
+    }
+
+    #[cfg(feature="serde")]
+    #[test]
+    fn sha224_serde() {
+        use serde_test::{Configure, Token, assert_tokens};
+
+        static HASH_BYTES: [u8; 28] = [
+            0x23, 0x09, 0x7d, 0x22, 0x34, 0x05, 0xd8, 0x22,
+            0x86, 0x42, 0xa4, 0x77, 0xbd, 0xa2, 0x55, 0xb3,
+            0x2a, 0xad, 0xbc, 0xe4, 0xbd, 0xa0, 0xb3, 0xf7,
+            0xe3, 0x6c, 0x9d, 0xa7,
+        ];
+
+        let hash = sha224::Hash::from_slice(&HASH_BYTES).expect("right number of bytes");
+        assert_tokens(&hash.compact(), &[Token::BorrowedBytes(&HASH_BYTES[..])]);
+        assert_tokens(&hash.readable(), &[Token::Str("23097d223405d8228642a477bda255b32aadbce4bda0b3f7e36c9da7")]);
+    }
+}
+
+#[cfg(all(test, feature="unstable"))]
+mod benches {
+    use std::io::Write;
+    use test::Bencher;
+
+    use sha224;
+    use Hash;
+
+    #[bench]
+    pub fn sha224_10(bh: & mut Bencher) {
+        let mut engine = sha224::Hash::engine();
+        let bytes = [1u8; 10];
+        bh.iter( || {
+            engine.write_all(&bytes).expect("write");
+        });
+        bh.bytes = bytes.len() as u64;
+    }
+
+    #[bench]
+    pub fn sha224_1k(bh: & mut Bencher) {
+        let mut engine = sha224::Hash::engine();
+        let bytes = [1u8; 1024];
+        bh.iter( || {
+            engine.write_all(&bytes).expect("write");
+        });
+        bh.bytes = bytes.len() as u64;
+    }
+
+    #[bench]
+    pub fn sha224_64k(bh: & mut Bencher) {
+        let mut engine = sha224::Hash::engine();
+        let bytes = [1u8; 65536];
+        bh.iter( || {
+            engine.write_all(&bytes).expect("write");
+        });
+        bh.bytes = bytes.len() as u64;
+    }
+
+}
@@ -16,20 +16,37 @@
 
 use byteorder::{ByteOrder, BigEndian};
 
+use hex;
 use HashEngine as EngineTrait;
 use Hash as HashTrait;
 use Error;
 
 const BLOCK_SIZE: usize = 64;
 
+/// The padding of a message of exactly one block: a one bit, zero bits and
+/// the message length of 512 bits
+#[cfg(not(feature = "fuzztarget"))]
+const PAIR_PADDING: [u8; BLOCK_SIZE] = [
+    0x80, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x02, 0,
+];
+
 /// Engine to compute SHA256 hash function
 pub struct HashEngine {
     buffer: [u8; BLOCK_SIZE],
     h: [u32; 8],
     length: usize,
+    // The state `reset` returns to
+    initial_h: [u32; 8],
+    initial_length: usize,
 }
 
 write_impl!(HashEngine);
+extend_impl!(HashEngine, Hash);
+engine_default_impl!(HashEngine, Hash);
+zeroize_engine_impl!(HashEngine, initial_h, initial_length);
 
 impl Clone for HashEngine {
     fn clone(&self) -> HashEngine {
@@ -37,28 +54,237 @@ impl Clone for HashEngine {
             h: self.h,
             length: self.length,
             buffer: self.buffer,
+            initial_h: self.initial_h,
+            initial_length: self.initial_length,
+        }
+    }
+}
+
+impl HashEngine {
+    /// Constructs an engine starting from the given initial state instead of
+    /// the SHA256 IV, as SHA224 does.
+    ///
+    /// **Warning:** with any other IV than the standard one (or SHA224's)
+    /// this no longer computes SHA256, and the security of the result has
+    /// not been analysed. It is only meant for protocols which specify their
+    /// own IV, such as some hash gadgets in zero-knowledge proof systems,
+    /// and for research. Resuming a hash from a checkpoint is what
+    /// `from_midstate` is for.
+    ///
+    /// Hashes produced from such an engine are still typed as `sha256::Hash`,
+    /// so callers should take care not to mix them with real SHA256 hashes.
+    /// For this reason the function is hidden from the documentation.
+    #[doc(hidden)]
+    pub fn from_iv(iv: [u32; 8]) -> HashEngine {
+        HashEngine {
+            buffer: [0; BLOCK_SIZE],
+            h: iv,
+            length: 0,
+            initial_h: iv,
+            initial_length: 0,
         }
     }
+
+    /// Create a new engine from a midstate, as output by `midstate`, and the
+    /// number of bytes which were hashed to produce it. This allows a long
+    /// hashing operation to be checkpointed and resumed later.
+    ///
+    /// Since the midstate does not cover data buffered in a partial block,
+    /// `length` must be a multiple of the block size. Otherwise an
+    /// `InvalidLength` error is returned, giving the last block boundary
+    /// before `length` as the expected length.
+    pub fn from_midstate(midstate: Midstate, length: u64) -> Result<HashEngine, Error> {
+        let length = length as usize;
+        let partial = length % BLOCK_SIZE;
+        if partial != 0 {
+            return Err(Error::InvalidLength(length - partial, length));
+        }
+
+        let mut h = [0; 8];
+        BigEndian::read_u32_into(&midstate[..], &mut h);
+        Ok(HashEngine {
+            buffer: [0; BLOCK_SIZE],
+            h,
+            length,
+            initial_h: h,
+            initial_length: length,
+        })
+    }
+
+    /// Returns the number of bytes input so far, such as for reporting the
+    /// progress of hashing a large file
+    pub fn n_bytes_hashed(&self) -> u64 {
+        self.length as u64
+    }
+
+    /// Returns the engine to the state it was constructed in, discarding all
+    /// data input since, so that it can be reused for another message. An
+    /// engine from `from_midstate` or `sha256t::tag_engine` returns to that
+    /// midstate rather than to a plain SHA256 engine, so a tagged engine
+    /// keeps its tag.
+    pub fn reset(&mut self) {
+        self.buffer = [0; BLOCK_SIZE];
+        self.h = self.initial_h;
+        self.length = self.initial_length;
+    }
+
+    /// Makes the current state the one which `reset` returns to, such as
+    /// after inputting a tag prefix
+    pub(crate) fn set_initial_state(&mut self) {
+        self.initial_h = self.h;
+        self.initial_length = self.length;
+    }
+}
+
+/// Formats the state of the engine for debugging, as the hex of the midstate
+/// followed by a colon and the hex of the data buffered in a partial block.
+/// The format may change between versions, so it should not be parsed.
+#[cfg(feature = "debug-engine")]
+impl ::core::fmt::LowerHex for HashEngine {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        hex::format_hex(&self.midstate()[..], &mut *f)?;
+        f.write_str(":")?;
+        hex::format_hex(&self.buffer[..self.length % BLOCK_SIZE], f)
+    }
+}
+
+/// Formats the state of the engine for debugging, as `LowerHex` does but
+/// with uppercase digits
+#[cfg(feature = "debug-engine")]
+impl ::core::fmt::UpperHex for HashEngine {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        hex::format_hex_uppercase(&self.midstate()[..], &mut *f)?;
+        f.write_str(":")?;
+        hex::format_hex_uppercase(&self.buffer[..self.length % BLOCK_SIZE], f)
+    }
 }
 
 impl EngineTrait for HashEngine {
-    type MidState = [u8; 32];
+    type MidState = Midstate;
 
     #[cfg(not(feature = "fuzztarget"))]
-    fn midstate(&self) -> [u8; 32] {
+    fn midstate(&self) -> Midstate {
         let mut ret = [0; 32];
         BigEndian::write_u32_into(&self.h, &mut ret);
-        ret
+        Midstate(ret)
     }
 
     #[cfg(feature = "fuzztarget")]
-    fn midstate(&self) -> [u8; 32] {
+    fn midstate(&self) -> Midstate {
         let mut ret = [0; 32];
         ret.copy_from_slice(&self.buffer[..32]);
-        ret
+        Midstate(ret)
     }
 
     const BLOCK_SIZE: usize = 64;
+
+    engine_input_impl!();
+}
+
+/// Length of a serialized engine, not counting its partial block
+#[cfg(feature = "serde")]
+const CHECKPOINT_LEN: usize = 80;
+
+/// Serialization of an engine, so that long hashing operations can be
+/// checkpointed and resumed: its chaining values and the number of bytes
+/// input, the same for the state `reset` returns to, and then the bytes
+/// of the partial block. Lengths are 8-byte big-endian integers.
+#[cfg(feature = "serde")]
+impl HashEngine {
+    fn to_checkpoint(&self, out: &mut [u8; CHECKPOINT_LEN + BLOCK_SIZE]) -> usize {
+        let partial = self.length % BLOCK_SIZE;
+        BigEndian::write_u32_into(&self.h, &mut out[..32]);
+        BigEndian::write_u64(&mut out[32..40], self.length as u64);
+        BigEndian::write_u32_into(&self.initial_h, &mut out[40..72]);
+        BigEndian::write_u64(&mut out[72..80], self.initial_length as u64);
+        out[CHECKPOINT_LEN..CHECKPOINT_LEN + partial].copy_from_slice(&self.buffer[..partial]);
+        CHECKPOINT_LEN + partial
+    }
+
+    fn from_checkpoint(sl: &[u8]) -> Result<HashEngine, Error> {
+        if sl.len() < CHECKPOINT_LEN {
+            return Err(Error::InvalidLength(CHECKPOINT_LEN, sl.len()));
+        }
+        let length = BigEndian::read_u64(&sl[32..40]) as usize;
+        let partial = length % BLOCK_SIZE;
+        if sl.len() != CHECKPOINT_LEN + partial {
+            return Err(Error::InvalidLength(CHECKPOINT_LEN + partial, sl.len()));
+        }
+
+        let mut engine = HashEngine::from_iv([0; 8]);
+        BigEndian::read_u32_into(&sl[..32], &mut engine.h);
+        engine.length = length;
+        BigEndian::read_u32_into(&sl[40..72], &mut engine.initial_h);
+        engine.initial_length = BigEndian::read_u64(&sl[72..80]) as usize;
+        engine.buffer[..partial].copy_from_slice(&sl[CHECKPOINT_LEN..]);
+        Ok(engine)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl ::serde::Serialize for HashEngine {
+    fn serialize<S: ::serde::Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        let mut buf = [0; CHECKPOINT_LEN + BLOCK_SIZE];
+        let len = self.to_checkpoint(&mut buf);
+        if s.is_human_readable() {
+            s.collect_str(&hex::display_hex(&buf[..len]))
+        } else {
+            s.serialize_bytes(&buf[..len])
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> ::serde::Deserialize<'de> for HashEngine {
+    fn deserialize<D: ::serde::Deserializer<'de>>(d: D) -> Result<HashEngine, D::Error> {
+        use core::fmt;
+        use serde::de;
+
+        struct CheckpointVisitor {
+            human_readable: bool,
+        }
+
+        impl<'de> de::Visitor<'de> for CheckpointVisitor {
+            type Value = HashEngine;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                if self.human_readable {
+                    formatter.write_str("a hex string of a checkpointed SHA256 engine")
+                } else {
+                    formatter.write_str("the bytes of a checkpointed SHA256 engine")
+                }
+            }
+
+            fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<HashEngine, E> {
+                if !self.human_readable {
+                    return HashEngine::from_checkpoint(v).map_err(E::custom);
+                }
+                match ::core::str::from_utf8(v) {
+                    Ok(hex) => self.visit_str(hex),
+                    Err(_) => Err(E::invalid_value(de::Unexpected::Bytes(v), &self)),
+                }
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<HashEngine, E> {
+                let mut iter = hex::HexIterator::new(v).map_err(E::custom)?;
+                let mut buf = [0; CHECKPOINT_LEN + BLOCK_SIZE];
+                let len = iter.len();
+                if len > buf.len() {
+                    return Err(E::invalid_length(len, &self));
+                }
+                iter.fill(&mut buf[..len]).map_err(E::custom)?;
+                HashEngine::from_checkpoint(&buf[..len]).map_err(E::custom)
+            }
+        }
+
+        let human_readable = d.is_human_readable();
+        let visitor = CheckpointVisitor { human_readable };
+        if human_readable {
+            d.deserialize_str(visitor)
+        } else {
+            d.deserialize_bytes(visitor)
+        }
+    }
 }
 
 /// Output of the SHA256 hash function
@@ -68,48 +294,123 @@ pub struct Hash([u8; 32]);
 hex_fmt_impl!(Debug, Hash);
 hex_fmt_impl!(Display, Hash);
 hex_fmt_impl!(LowerHex, Hash);
+hex_fmt_impl!(UpperHex, Hash);
 index_impl!(Hash);
 serde_impl!(Hash, 32);
 borrow_slice_impl!(Hash);
+from_str_impl!(Hash);
+try_from_impl!(Hash);
+from_bytes_impl!(Hash, 32);
+into_iter_impl!(Hash);
+eq_bytes_impl!(Hash, 32);
+deref_impl!(Hash, 32);
+inner_ref_impl!(Hash, 32);
+bitops_impl!(Hash);
+ct_eq_impl!(Hash);
+zeroize_impl!(Hash);
+rand_impl!(Hash);
+proptest_impl!(Hash);
+quickcheck_impl!(Hash);
+
+/// Internal state of a SHA256 engine after hashing some number of full blocks
+#[derive(Copy, Clone, PartialEq, Eq, Default, PartialOrd, Ord, Hash)]
+pub struct Midstate(pub [u8; 32]);
+
+hex_fmt_impl!(Debug, Midstate);
+hex_fmt_impl!(Display, Midstate);
+hex_fmt_impl!(LowerHex, Midstate);
+hex_fmt_impl!(UpperHex, Midstate);
+index_impl!(Midstate);
+serde_impl!(Midstate, 32);
+borrow_slice_impl!(Midstate);
+
+impl Midstate {
+    /// Length of the midstate, in bytes
+    pub const LEN: usize = 32;
+
+    /// Flag indicating whether user-visible serializations of this midstate
+    /// should be backward. Unlike `sha256d` hashes they are not.
+    pub const DISPLAY_BACKWARD: bool = false;
+
+    /// Constructs a midstate from its bytes
+    pub fn from_byte_array(bytes: [u8; 32]) -> Midstate {
+        Midstate(bytes)
+    }
+
+    /// Construct a new midstate from the inner value
+    #[deprecated(note = "use `from_byte_array`, which takes the same bytes")]
+    pub fn from_inner(inner: [u8; 32]) -> Midstate {
+        Midstate(inner)
+    }
+
+    /// Copies a byte slice into a midstate object
+    pub fn from_slice(sl: &[u8]) -> Result<Midstate, Error> {
+        if sl.len() != Self::LEN {
+            Err(Error::InvalidLength(Self::LEN, sl.len()))
+        } else {
+            let mut ret = [0; 32];
+            ret.copy_from_slice(sl);
+            Ok(Midstate(ret))
+        }
+    }
+
+    /// Returns the bytes of the midstate
+    pub fn to_byte_array(self) -> [u8; 32] {
+        self.0
+    }
+
+    /// Unwraps the midstate and returns the underlying byte array
+    #[deprecated(note = "use `to_byte_array`, which returns the same bytes")]
+    pub fn into_inner(self) -> [u8; 32] {
+        self.0
+    }
+}
+
+impl hex::FromHex for Midstate {
+    fn from_hex(s: &str) -> Result<Midstate, Error> {
+        let mut bytes: [u8; 32] = hex::FromHex::from_hex(s)?;
+        if Self::DISPLAY_BACKWARD {
+            bytes.reverse();
+        }
+        Ok(Midstate(bytes))
+    }
+}
 
 impl HashTrait for Hash {
     type Engine = HashEngine;
     type Inner = [u8; 32];
 
     fn engine() -> HashEngine {
-        HashEngine {
-            h: [0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19],
-            length: 0,
-            buffer: [0; BLOCK_SIZE],
-        }
+        HashEngine::from_iv([
+            0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+        ])
     }
 
     #[cfg(not(feature = "fuzztarget"))]
     fn from_engine(mut e: HashEngine) -> Hash {
-        use std::io::Write;
-        use byteorder::WriteBytesExt;
-
         // pad buffer with a single 1-bit then all 0s, until there are exactly 8 bytes remaining
         let data_len = e.length as u64;
 
         let zeroes = [0; BLOCK_SIZE - 8];
-        e.write_all(&[0x80]).unwrap();
+        e.input(&[0x80]);
         if e.length % BLOCK_SIZE > zeroes.len() {
-            e.write_all(&zeroes).unwrap();
+            e.input(&zeroes);
         }
         let pad_length = zeroes.len() - (e.length % BLOCK_SIZE);
-        e.write_all(&zeroes[..pad_length]).unwrap();
+        e.input(&zeroes[..pad_length]);
         debug_assert_eq!(e.length % BLOCK_SIZE, zeroes.len());
 
-        e.write_u64::<BigEndian>(8 * data_len).unwrap();
+        let mut len_bytes = [0; 8];
+        BigEndian::write_u64(&mut len_bytes, 8 * data_len);
+        e.input(&len_bytes);
         debug_assert_eq!(e.length % BLOCK_SIZE, 0);
 
-        Hash(e.midstate())
+        Hash(e.midstate().to_byte_array())
     }
 
     #[cfg(feature = "fuzztarget")]
     fn from_engine(e: HashEngine) -> Hash {
-        Hash(e.midstate())
+        Hash(e.midstate().to_byte_array())
     }
 
     const LEN: usize = 32;
@@ -124,11 +425,184 @@ impl HashTrait for Hash {
         }
     }
 
-    fn into_inner(self) -> Self::Inner {
+    fn from_byte_array(bytes: Self::Inner) -> Self {
+        Hash(bytes)
+    }
+
+    fn to_byte_array(self) -> Self::Inner {
         self.0
     }
 }
 
+impl Hash {
+    /// Computes the BIP340 tagged hash `SHA256(SHA256(tag) || SHA256(tag) || data)`,
+    /// hashing the tag at runtime. To hash with the same tag repeatedly, use
+    /// a `TagCache`, or implement `sha256t::Tag` with a precomputed midstate.
+    pub fn tagged(tag: &str, data: &[u8]) -> Hash {
+        let mut engine = ::sha256t::tag_engine(tag);
+        engine.input(data);
+        Hash::from_engine(engine)
+    }
+
+    /// Hashes the concatenation of two hashes, as for the parent of two
+    /// nodes of a Merkle tree. The 64 bytes fill exactly one block, so this
+    /// compresses that block straight from the IV and then the padding
+    /// block, which is the same for every pair, without the buffering and
+    /// padding which `hash_two` goes through.
+    #[cfg(not(feature = "fuzztarget"))]
+    pub fn from_engine_pair(left: Hash, right: Hash) -> Hash {
+        let mut engine = Hash::engine();
+        engine.buffer[..32].copy_from_slice(&left.0);
+        engine.buffer[32..].copy_from_slice(&right.0);
+        engine.process_block();
+        engine.buffer = PAIR_PADDING;
+        engine.process_block();
+        Hash(engine.midstate().to_byte_array())
+    }
+
+    /// Hashes the concatenation of two hashes, as for the parent of two
+    /// nodes of a Merkle tree
+    #[cfg(feature = "fuzztarget")]
+    pub fn from_engine_pair(left: Hash, right: Hash) -> Hash {
+        Hash::hash_two(left, right)
+    }
+}
+
+/// Tagged hashing with tags chosen at runtime, remembering the midstate
+/// after each tag prefix so that it is only hashed once per tag
+#[cfg(feature = "std")]
+#[derive(Clone, Debug, Default)]
+pub struct TagCache {
+    tags: ::std::collections::HashMap<String, ::sha256t::DynamicTag>,
+}
+
+#[cfg(feature = "std")]
+impl TagCache {
+    /// Constructs an empty cache
+    pub fn new() -> TagCache {
+        TagCache::default()
+    }
+
+    /// Returns a hash engine which has already been fed the prefix for `tag`
+    pub fn engine(&mut self, tag: &str) -> HashEngine {
+        if let Some(dynamic) = self.tags.get(tag) {
+            return dynamic.engine();
+        }
+        let dynamic = ::sha256t::DynamicTag::from_tag_string(tag);
+        self.tags.insert(tag.to_owned(), dynamic);
+        dynamic.engine()
+    }
+
+    /// Computes the tagged hash of `data`, as `Hash::tagged` does
+    pub fn tagged(&mut self, tag: &str, data: &[u8]) -> Hash {
+        let mut engine = self.engine(tag);
+        engine.input(data);
+        Hash::from_engine(engine)
+    }
+
+    /// Number of tags whose midstates are cached
+    pub fn len(&self) -> usize {
+        self.tags.len()
+    }
+
+    /// Whether no tags are cached
+    pub fn is_empty(&self) -> bool {
+        self.tags.is_empty()
+    }
+}
+
+/// Hashes each of `inputs`, returning the hashes in the same order.
+///
+/// On x86-64 CPUs with AVX2, up to eight inputs are hashed at once. This is
+/// much faster for many short inputs of similar lengths, such as the leaves
+/// of a Merkle tree. Elsewhere, and for fewer than four inputs, they are
+/// hashed one after the other, as they are without the `std` feature.
+#[cfg(feature = "alloc")]
+pub fn batch_hash(inputs: &[&[u8]]) -> ::prelude::Vec<Hash> {
+    let mut ret = ::prelude::Vec::with_capacity(inputs.len());
+
+    #[cfg(all(feature = "std", target_arch = "x86_64", not(feature = "fuzztarget")))]
+    {
+        if inputs.len() >= 4 && ::sha256_avx2::is_supported() {
+            for chunk in inputs.chunks(::sha256_avx2::LANES) {
+                if chunk.len() >= 4 {
+                    batch_hash_avx2(chunk, &mut ret);
+                } else {
+                    ret.extend(chunk.iter().map(|data| Hash::hash(data)));
+                }
+            }
+            return ret;
+        }
+    }
+
+    ret.extend(inputs.iter().map(|data| Hash::hash(data)));
+    ret
+}
+
+/// Hashes up to eight inputs in parallel, appending their hashes to `out`
+#[cfg(all(feature = "std", target_arch = "x86_64", not(feature = "fuzztarget")))]
+fn batch_hash_avx2(inputs: &[&[u8]], out: &mut ::prelude::Vec<Hash>) {
+    use sha256_avx2::{self, LANES};
+
+    let iv = Hash::engine().h;
+    let mut h = [iv; LANES];
+    let mut done = [iv; LANES];
+    let mut blocks = [[0; BLOCK_SIZE]; LANES];
+
+    // Unused lanes hash nothing, and lanes which are done keep hashing
+    // their last block; neither affects the other lanes
+    let mut n_blocks = [0; LANES];
+    for (n, data) in n_blocks.iter_mut().zip(inputs.iter()) {
+        *n = padded_blocks(data.len());
+    }
+    let max_blocks = n_blocks.iter().cloned().max().unwrap_or(0);
+
+    for idx in 0..max_blocks {
+        for (lane, data) in inputs.iter().enumerate() {
+            if idx < n_blocks[lane] {
+                padded_block(data, idx, &mut blocks[lane]);
+            }
+        }
+        // Safe since the CPU was checked to support the instructions
+        unsafe { sha256_avx2::process_blocks(&mut h, &blocks) }
+        for lane in 0..inputs.len() {
+            if idx + 1 == n_blocks[lane] {
+                done[lane] = h[lane];
+            }
+        }
+    }
+
+    for state in &done[..inputs.len()] {
+        let mut ret = [0; 32];
+        BigEndian::write_u32_into(state, &mut ret);
+        out.push(Hash(ret));
+    }
+}
+
+/// Number of blocks of a message of `len` bytes once it is padded
+#[cfg(all(feature = "std", target_arch = "x86_64", not(feature = "fuzztarget")))]
+fn padded_blocks(len: usize) -> usize {
+    // The padding adds at least a 0x80 byte and the 8-byte length
+    (len + 8) / BLOCK_SIZE + 1
+}
+
+/// Writes the block `idx` of the padded message `data` into `block`
+#[cfg(all(feature = "std", target_arch = "x86_64", not(feature = "fuzztarget")))]
+fn padded_block(data: &[u8], idx: usize, block: &mut [u8; BLOCK_SIZE]) {
+    let start = idx * BLOCK_SIZE;
+    *block = [0; BLOCK_SIZE];
+    if start < data.len() {
+        let len = ::core::cmp::min(BLOCK_SIZE, data.len() - start);
+        block[..len].copy_from_slice(&data[start..start + len]);
+    }
+    if start <= data.len() && data.len() - start < BLOCK_SIZE {
+        block[data.len() - start] = 0x80;
+    }
+    if idx + 1 == padded_blocks(data.len()) {
+        BigEndian::write_u64(&mut block[BLOCK_SIZE - 8..], 8 * data.len() as u64);
+    }
+}
+
 macro_rules! Ch( ($x:expr, $y:expr, $z:expr) => ($z ^ ($x & ($y ^ $z))) );
 macro_rules! Maj( ($x:expr, $y:expr, $z:expr) => (($x & $y) | ($z & ($x | $y))) );
 macro_rules! Sigma0( ($x:expr) => (circular_lshift32!(30, $x) ^ circular_lshift32!(19, $x) ^ circular_lshift32!(10, $x)) ); macro_rules! Sigma1( ($x:expr) => (circular_lshift32!(26, $x) ^ circular_lshift32!(21, $x) ^ circular_lshift32!(7, $x)) );
@@ -151,8 +625,23 @@ macro_rules! round(
 );
 
 impl HashEngine {
-    // Algorithm copied from libsecp256k1
+    #[cfg(all(feature = "sha-ni", target_arch = "x86_64"))]
     fn process_block(&mut self) {
+        if ::sha256_ni::is_supported() {
+            // Safe since the CPU was checked to support the instructions
+            unsafe { ::sha256_ni::process_block(&mut self.h, &self.buffer) }
+        } else {
+            self.process_block_soft()
+        }
+    }
+
+    #[cfg(not(all(feature = "sha-ni", target_arch = "x86_64")))]
+    fn process_block(&mut self) {
+        self.process_block_soft()
+    }
+
+    // Algorithm copied from libsecp256k1
+    fn process_block_soft(&mut self) {
         debug_assert_eq!(self.buffer.len(), BLOCK_SIZE);
 
         let mut w = [0u32; 16];
@@ -250,9 +739,9 @@ impl HashEngine {
 mod tests {
     use std::io::Write;
 
-    use sha256;
+    use {sha256, sha256t};
     use hex::{FromHex, ToHex};
-    use {Hash, HashEngine};
+    use {Error, Hash, HashEngine};
 
     #[derive(Clone)]
     struct Test {
@@ -311,7 +800,7 @@ mod tests {
             }
             let manual_hash = sha256::Hash::from_engine(engine);
             assert_eq!(hash, manual_hash);
-            assert_eq!(hash.into_inner()[..].as_ref(), test.output.as_slice());
+            assert_eq!(hash.to_byte_array()[..].as_ref(), test.output.as_slice());
         }
     }
 
@@ -332,15 +821,192 @@ mod tests {
         assert_eq!(
             engine.midstate(),
             // RPC output
-            [
+            sha256::Midstate::from_byte_array([
                 0x0b, 0xcf, 0xe0, 0xe5, 0x4e, 0x6c, 0xc7, 0xd3,
                 0x4f, 0x4f, 0x7c, 0x1d, 0xf0, 0xb0, 0xf5, 0x03,
                 0xf2, 0xf7, 0x12, 0x91, 0x2a, 0x06, 0x05, 0xb4,
-                0x14, 0xed, 0x33, 0x7f, 0x7f, 0x03, 0x2e, 0x03, 
-            ]
+                0x14, 0xed, 0x33, 0x7f, 0x7f, 0x03, 0x2e, 0x03,
+            ])
+        );
+    }
+
+    #[test]
+    fn from_iv() {
+        let mut engine = sha256::HashEngine::from_iv([
+            0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+        ]);
+        engine.input(b"The quick brown fox jumps over the lazy dog");
+        assert_eq!(
+            sha256::Hash::from_engine(engine).to_hex(),
+            "d7a8fbb307d7809469ca9abcb0082e4f8d5651e46d3cdb762d02d0bf37c9e592",
+        );
+
+        // SHA224 is SHA256 with another IV, truncated
+        let mut engine = sha256::HashEngine::from_iv([
+            0xc1059ed8, 0x367cd507, 0x3070dd17, 0xf70e5939, 0xffc00b31, 0x68581511, 0x64f98fa7, 0xbefa4fa4,
+        ]);
+        engine.input(b"abc");
+        assert_eq!(&sha256::Hash::from_engine(engine.clone())[..28], &::sha224::Hash::hash(b"abc")[..]);
+
+        // Resetting returns to the given IV
+        engine.reset();
+        assert_eq!(engine.n_bytes_hashed(), 0);
+        engine.input(b"abc");
+        assert_eq!(&sha256::Hash::from_engine(engine)[..28], &::sha224::Hash::hash(b"abc")[..]);
+    }
+
+    #[cfg(feature = "debug-engine")]
+    #[test]
+    fn engine_hex() {
+        let mut engine = sha256::Hash::engine();
+        assert_eq!(
+            format!("{:x}", engine),
+            "6a09e667bb67ae853c6ef372a54ff53a510e527f9b05688c1f83d9ab5be0cd19:",
+        );
+
+        engine.input(&[0xab; 65]);
+        let midstate = engine.midstate().to_hex();
+        assert_eq!(format!("{:x}", engine), format!("{}:ab", midstate));
+        assert_eq!(format!("{:X}", engine), format!("{}:AB", midstate.to_uppercase()));
+    }
+
+    #[test]
+    fn n_bytes_hashed() {
+        let mut engine = sha256::Hash::engine();
+        assert_eq!(engine.n_bytes_hashed(), 0);
+        engine.input(&[0; 100]);
+        engine.input(b"abc");
+        assert_eq!(engine.n_bytes_hashed(), 103);
+
+        // Resumed engines count the bytes hashed before the checkpoint
+        let engine = sha256::HashEngine::from_midstate(engine.midstate(), 64).unwrap();
+        assert_eq!(engine.n_bytes_hashed(), 64);
+    }
+
+    #[test]
+    fn from_engine_pair() {
+        let left = sha256::Hash::hash(b"left");
+        let right = sha256::Hash::hash(b"right");
+        let mut engine = sha256::Hash::engine();
+        engine.input(&left[..]);
+        engine.input(&right[..]);
+        assert_eq!(sha256::Hash::from_engine_pair(left, right), sha256::Hash::from_engine(engine));
+        assert!(sha256::Hash::from_engine_pair(right, left) != sha256::Hash::from_engine_pair(left, right));
+
+        // sha256("") concatenated with itself
+        let empty = sha256::Hash::hash(&[]);
+        let mut both = [0; 64];
+        both[..32].copy_from_slice(&empty[..]);
+        both[32..].copy_from_slice(&empty[..]);
+        assert_eq!(sha256::Hash::from_engine_pair(empty, empty), sha256::Hash::hash(&both));
+    }
+
+    #[test]
+    fn tagged() {
+        use taproot::ChallengeHash;
+
+        let data = b"abc";
+        let tag_hash = sha256::Hash::hash(b"BIP0340/challenge");
+        let mut engine = sha256::Hash::engine();
+        engine.input(&tag_hash[..]);
+        engine.input(&tag_hash[..]);
+        engine.input(data);
+        let expected = sha256::Hash::from_engine(engine);
+
+        let hash = sha256::Hash::tagged("BIP0340/challenge", data);
+        assert_eq!(hash, expected);
+        assert_eq!(&hash[..], &ChallengeHash::hash(data)[..]);
+
+        let mut cache = sha256::TagCache::new();
+        assert!(cache.is_empty());
+        assert_eq!(cache.tagged("BIP0340/challenge", data), expected);
+        assert_eq!(cache.tagged("BIP0340/challenge", data), expected);
+        assert_eq!(cache.len(), 1);
+        assert_eq!(cache.tagged("other", data), sha256::Hash::tagged("other", data));
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.engine("other").n_bytes_hashed(), 64);
+    }
+
+    #[cfg(all(feature = "sha-ni", target_arch = "x86_64"))]
+    #[test]
+    fn sha_ni_matches_software() {
+        if !::sha256_ni::is_supported() {
+            return;
+        }
+
+        let mut hw = sha256::Hash::engine();
+        let mut sw = sha256::Hash::engine();
+        for i in 0..1000u32 {
+            for (j, b) in hw.buffer.iter_mut().enumerate() {
+                *b = (i.wrapping_mul(31) as u8) ^ (j as u8);
+            }
+            sw.buffer = hw.buffer;
+            hw.process_block();
+            sw.process_block_soft();
+            assert_eq!(hw.h, sw.h);
+        }
+    }
+
+    #[test]
+    fn reset() {
+        let mut engine = sha256::Hash::engine();
+        engine.input(&[1; 100]);
+        engine.reset();
+        engine.input(b"abc");
+        assert_eq!(sha256::Hash::from_engine(engine.clone()), sha256::Hash::hash(b"abc"));
+
+        // Tagged engines keep their tag, however they were constructed
+        let expected = sha256::Hash::tagged("BIP0340/challenge", b"abc");
+        let midstate = sha256t::tag_engine("BIP0340/challenge").midstate();
+        for engine in [
+            sha256t::tag_engine("BIP0340/challenge"),
+            sha256::HashEngine::from_midstate(midstate, 64).unwrap(),
+        ].iter() {
+            let mut engine = engine.clone();
+            engine.input(&[2; 70]);
+            engine.reset();
+            assert_eq!(engine.n_bytes_hashed(), 64);
+            engine.input(b"abc");
+            assert_eq!(sha256::Hash::from_engine(engine), expected);
+        }
+    }
+
+    #[test]
+    fn resume_from_midstate() {
+        let data: Vec<u8> = (0..200).map(|i| i as u8).collect();
+        let hash = sha256::Hash::hash(&data);
+
+        // Checkpoint after two full blocks, then resume from a hex-encoded midstate
+        let mut engine = sha256::Hash::engine();
+        engine.input(&data[..128]);
+        let checkpoint = engine.midstate().to_hex();
+
+        let midstate = sha256::Midstate::from_hex(&checkpoint).expect("parse midstate");
+        let mut engine = sha256::HashEngine::from_midstate(midstate, 128).unwrap();
+        engine.input(&data[128..]);
+        assert_eq!(sha256::Hash::from_engine(engine), hash);
+    }
+
+    #[test]
+    fn from_midstate_partial_block() {
+        assert_eq!(
+            sha256::HashEngine::from_midstate(sha256::Midstate::default(), 100).err(),
+            Some(Error::InvalidLength(64, 100)),
         );
     }
 
+    #[test]
+    fn batch_hash() {
+        // Mixed lengths around the block boundaries, and a tail of lanes
+        let data: Vec<u8> = (0..300).map(|i| i as u8).collect();
+        let lens = [0, 1, 55, 56, 63, 64, 65, 119, 120, 128, 200, 300, 3, 64, 64, 64, 64, 10, 20];
+        for n in 0..lens.len() {
+            let inputs: Vec<&[u8]> = lens[..n].iter().map(|&len| &data[..len]).collect();
+            let expected: Vec<sha256::Hash> = inputs.iter().map(|d| sha256::Hash::hash(d)).collect();
+            assert_eq!(sha256::batch_hash(&inputs), expected);
+        }
+    }
+
     #[cfg(feature="serde")]
     #[test]
     fn sha256_serde() {
@@ -357,6 +1023,125 @@ mod tests {
         assert_tokens(&hash.compact(), &[Token::BorrowedBytes(&HASH_BYTES[..])]);
         assert_tokens(&hash.readable(), &[Token::Str("ef537f25c895bfa782526529a9b63d97aa631564d5d789c2b765448c8635fb6c")]);
     }
+
+    #[cfg(feature="serde")]
+    #[test]
+    fn engine_serde() {
+        use serde::de::IntoDeserializer;
+        use serde::de::value::{BytesDeserializer, Error as ValueError, StrDeserializer};
+        use serde::Deserialize;
+        use serde_test::{assert_ser_tokens, Configure, Token};
+
+        let data: Vec<u8> = (0..2000u32).map(|i| i as u8).collect();
+        let mut engine = sha256::Hash::engine();
+        engine.input(&data[..1000]);
+
+        // Chaining values and length, the initial state, then the 40 bytes
+        // of the partial block
+        let mut expected = engine.midstate()[..].to_vec();
+        expected.extend_from_slice(&[0, 0, 0, 0, 0, 0, 0x03, 0xe8]);
+        expected.extend_from_slice(&sha256::Hash::engine().midstate()[..]);
+        expected.extend_from_slice(&[0; 8]);
+        expected.extend_from_slice(&data[960..1000]);
+        // Tokens must borrow for `'static`
+        let expected: &'static [u8] = Box::leak(expected.into_boxed_slice());
+        let expected_hex: &'static str = Box::leak(expected.to_hex().into_boxed_str());
+        assert_ser_tokens(&engine.clone().compact(), &[Token::Bytes(expected)]);
+        assert_ser_tokens(&engine.clone().readable(), &[Token::Str(expected_hex)]);
+
+        // Resuming from the serialization gives the same hash
+        let de: StrDeserializer<ValueError> = expected_hex.into_deserializer();
+        let mut resumed = sha256::HashEngine::deserialize(de).expect("valid checkpoint");
+        resumed.input(&data[1000..]);
+        assert_eq!(sha256::Hash::from_engine(resumed), sha256::Hash::hash(&data));
+
+        // Human-readable formats also accept the hex string as bytes
+        let de = BytesDeserializer::<ValueError>::new(expected_hex.as_bytes());
+        let mut resumed = sha256::HashEngine::deserialize(de).expect("valid checkpoint");
+        resumed.input(&data[1000..]);
+        assert_eq!(sha256::Hash::from_engine(resumed), sha256::Hash::hash(&data));
+
+        // The partial block must match the length
+        let de: StrDeserializer<ValueError> = expected_hex[..expected_hex.len() - 2].into_deserializer();
+        assert_eq!(
+            sha256::HashEngine::deserialize(de).err().expect("too short").to_string(),
+            "invalid length: expected 120, got 119",
+        );
+
+        // The compact serialization round trips, keeping the tag of a
+        // tagged engine
+        let mut engine = ::sha256t::tag_engine("BIP0340/challenge");
+        engine.input(&data[..10]);
+        let mut buf = [0; super::CHECKPOINT_LEN + 64];
+        let len = engine.to_checkpoint(&mut buf);
+        let mut resumed = sha256::HashEngine::from_checkpoint(&buf[..len]).expect("valid");
+        resumed.reset();
+        resumed.input(b"abc");
+        assert_eq!(sha256::Hash::from_engine(resumed), sha256::Hash::tagged("BIP0340/challenge", b"abc"));
+    }
+
+    #[cfg(feature="serde")]
+    #[test]
+    fn midstate_serde() {
+        use serde_test::{Configure, Token, assert_tokens, assert_de_tokens, assert_de_tokens_error};
+
+        // Midstate from the `midstate` test above
+        static MIDSTATE_BYTES: [u8; 32] = [
+            0x0b, 0xcf, 0xe0, 0xe5, 0x4e, 0x6c, 0xc7, 0xd3,
+            0x4f, 0x4f, 0x7c, 0x1d, 0xf0, 0xb0, 0xf5, 0x03,
+            0xf2, 0xf7, 0x12, 0x91, 0x2a, 0x06, 0x05, 0xb4,
+            0x14, 0xed, 0x33, 0x7f, 0x7f, 0x03, 0x2e, 0x03,
+        ];
+        static MIDSTATE_HEX: &str = "0bcfe0e54e6cc7d34f4f7c1df0b0f503f2f712912a0605b414ed337f7f032e03";
+
+        // Serialized in the same way as `sha256::Hash`
+        let midstate = sha256::Midstate::from_byte_array(MIDSTATE_BYTES);
+        assert_tokens(&midstate.compact(), &[Token::BorrowedBytes(&MIDSTATE_BYTES[..])]);
+        assert_tokens(&midstate.readable(), &[Token::Str(MIDSTATE_HEX)]);
+        // Human-readable formats also accept the hex string as bytes
+        assert_de_tokens(&midstate.readable(), &[Token::Bytes(MIDSTATE_HEX.as_bytes())]);
+        assert_de_tokens_error::<serde_test::Readable<sha256::Midstate>>(
+            &[Token::Str(&MIDSTATE_HEX[2..])],
+            "invalid length: expected 64, got 62",
+        );
+        assert_de_tokens_error::<serde_test::Compact<sha256::Midstate>>(
+            &[Token::Bytes(&MIDSTATE_BYTES[1..])],
+            "invalid length 31, expected 32",
+        );
+
+        // A checkpoint restored from its serialization can be resumed
+        let mut engine = sha256::HashEngine::from_midstate(midstate, 64).unwrap();
+        engine.input(b"abc");
+        let mut expected = sha256::Hash::engine();
+        expected.input(&[
+            0x9d, 0xd0, 0x1b, 0x56, 0xb1, 0x56, 0x45, 0x14,
+            0x3e, 0xad, 0x15, 0x8d, 0xec, 0x19, 0xf8, 0xce,
+            0xa9, 0x0b, 0xd0, 0xa9, 0xb2, 0xf8, 0x1d, 0x21,
+            0xff, 0xa3, 0xa4, 0xc6, 0x44, 0x81, 0xd4, 0x1c,
+        ]);
+        expected.input(&[0; 32]);
+        expected.input(b"abc");
+        assert_eq!(sha256::Hash::from_engine(engine), sha256::Hash::from_engine(expected));
+    }
+
+    #[cfg(feature="rand")]
+    #[test]
+    fn sha256_rand() {
+        use rand::Rng;
+        use rand::rngs::mock::StepRng;
+
+        // Yields the bytes 0, 1, 2, ..
+        let mut rng = StepRng::new(0x0706050403020100, 0x0808080808080808);
+        let hash = sha256::Hash::from_rng(&mut rng);
+        for (i, byte) in hash[..].iter().enumerate() {
+            assert_eq!(*byte, i as u8);
+        }
+
+        let hash: sha256::Hash = rng.gen();
+        assert_eq!(hash[0], 32);
+        assert_eq!(hash[31], 63);
+        assert_ne!(rng.gen::<sha256::Hash>(), hash);
+    }
 }
 
 #[cfg(all(test, feature="unstable"))]
@@ -367,6 +1152,57 @@ mod benches {
     use sha256;
     use Hash;
 
+    fn batch(bh: &mut Bencher, n: usize, batched: bool) {
+        let bytes = [1u8; 64];
+        let inputs = vec![&bytes[..]; n];
+        bh.iter( || {
+            if batched {
+                sha256::batch_hash(&inputs)
+            } else {
+                inputs.iter().map(|data| sha256::Hash::hash(data)).collect()
+            }
+        });
+        bh.bytes = (n * bytes.len()) as u64;
+    }
+
+    #[bench] pub fn sha256_batch_1(bh: & mut Bencher) { batch(bh, 1, true) }
+    #[bench] pub fn sha256_batch_4(bh: & mut Bencher) { batch(bh, 4, true) }
+    #[bench] pub fn sha256_batch_8(bh: & mut Bencher) { batch(bh, 8, true) }
+    #[bench] pub fn sha256_batch_16(bh: & mut Bencher) { batch(bh, 16, true) }
+    #[bench] pub fn sha256_batch_64(bh: & mut Bencher) { batch(bh, 64, true) }
+    #[bench] pub fn sha256_sequential_1(bh: & mut Bencher) { batch(bh, 1, false) }
+    #[bench] pub fn sha256_sequential_4(bh: & mut Bencher) { batch(bh, 4, false) }
+    #[bench] pub fn sha256_sequential_8(bh: & mut Bencher) { batch(bh, 8, false) }
+    #[bench] pub fn sha256_sequential_16(bh: & mut Bencher) { batch(bh, 16, false) }
+    #[bench] pub fn sha256_sequential_64(bh: & mut Bencher) { batch(bh, 64, false) }
+
+    #[bench]
+    pub fn sha256_hash_two(bh: & mut Bencher) {
+        let (a, b) = ([1u8; 32], [2u8; 32]);
+        bh.iter( || {
+            sha256::Hash::hash_two(&a, &b)
+        });
+        bh.bytes = 64;
+    }
+
+    #[bench]
+    pub fn sha256_from_engine_pair(bh: & mut Bencher) {
+        let (a, b) = (sha256::Hash::from_byte_array([1; 32]), sha256::Hash::from_byte_array([2; 32]));
+        bh.iter( || {
+            sha256::Hash::from_engine_pair(a, b)
+        });
+        bh.bytes = 64;
+    }
+
+    #[bench]
+    pub fn sha256_hash_concat(bh: & mut Bencher) {
+        let (a, b) = ([1u8; 32], [2u8; 32]);
+        bh.iter( || {
+            sha256::Hash::hash(&[&a[..], &b[..]].concat())
+        });
+        bh.bytes = 64;
+    }
+
     #[bench]
     pub fn sha256_10(bh: & mut Bencher) {
         let mut engine = sha256::Hash::engine();
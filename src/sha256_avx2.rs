@@ -0,0 +1,111 @@
+// Bitcoin Hashes Library
+// Written in 2019 by
+//   The rust-bitcoin developers
+//
+// To the extent possible under law, the author(s) have dedicated all
+// copyright and related and neighboring rights to this software to
+// the public domain worldwide. This software is distributed without
+// any warranty.
+//
+// You should have received a copy of the CC0 Public Domain Dedication
+// along with this software.
+// If not, see <http://creativecommons.org/publicdomain/zero/1.0/>.
+//
+
+//! # 8-way SHA256 with AVX2
+//!
+//! The SHA256 compression function run on eight independent states at once,
+//! with each 32-bit lane of the AVX2 registers holding a word of a different
+//! state. Callers must check `is_supported` first.
+
+use std::arch::x86_64::*;
+
+use byteorder::{ByteOrder, BigEndian};
+
+const K: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+/// Number of states compressed at once
+pub const LANES: usize = 8;
+
+/// Whether the running CPU supports every instruction used here
+pub fn is_supported() -> bool {
+    is_x86_feature_detected!("avx2")
+}
+
+macro_rules! add( ($x:expr, $y:expr) => (_mm256_add_epi32($x, $y)) );
+macro_rules! xor( ($x:expr, $y:expr) => (_mm256_xor_si256($x, $y)) );
+macro_rules! rotr(
+    ($x:expr, $n:expr) => (_mm256_or_si256(_mm256_srli_epi32($x, $n), _mm256_slli_epi32($x, 32 - $n)))
+);
+macro_rules! Ch( ($x:expr, $y:expr, $z:expr) => (xor!($z, _mm256_and_si256($x, xor!($y, $z)))) );
+macro_rules! Maj(
+    ($x:expr, $y:expr, $z:expr) => (_mm256_or_si256(_mm256_and_si256($x, $y), _mm256_and_si256($z, _mm256_or_si256($x, $y))))
+);
+macro_rules! Sigma0( ($x:expr) => (xor!(xor!(rotr!($x, 2), rotr!($x, 13)), rotr!($x, 22))) );
+macro_rules! Sigma1( ($x:expr) => (xor!(xor!(rotr!($x, 6), rotr!($x, 11)), rotr!($x, 25))) );
+macro_rules! sigma0( ($x:expr) => (xor!(xor!(rotr!($x, 7), rotr!($x, 18)), _mm256_srli_epi32($x, 3))) );
+macro_rules! sigma1( ($x:expr) => (xor!(xor!(rotr!($x, 17), rotr!($x, 19)), _mm256_srli_epi32($x, 10))) );
+
+/// Compresses `blocks[i]` into the state `h[i]` for each of the eight lanes
+#[target_feature(enable = "avx2")]
+pub unsafe fn process_blocks(h: &mut [[u32; 8]; LANES], blocks: &[[u8; 64]; LANES]) {
+    // Transpose, so that each register holds one word of every lane
+    let mut w = [_mm256_setzero_si256(); 16];
+    for (i, w) in w.iter_mut().enumerate() {
+        let mut words = [0u32; LANES];
+        for (word, block) in words.iter_mut().zip(blocks.iter()) {
+            *word = BigEndian::read_u32(&block[4 * i..]);
+        }
+        *w = _mm256_loadu_si256(words.as_ptr() as *const __m256i);
+    }
+    let mut s = [_mm256_setzero_si256(); 8];
+    for (i, s) in s.iter_mut().enumerate() {
+        let mut words = [0u32; LANES];
+        for (word, h) in words.iter_mut().zip(h.iter()) {
+            *word = h[i];
+        }
+        *s = _mm256_loadu_si256(words.as_ptr() as *const __m256i);
+    }
+
+    let (mut a, mut b, mut c, mut d) = (s[0], s[1], s[2], s[3]);
+    let (mut e, mut f, mut g, mut hh) = (s[4], s[5], s[6], s[7]);
+    for (i, &k) in K.iter().enumerate() {
+        if i >= 16 {
+            w[i % 16] = add!(
+                add!(sigma1!(w[(i - 2) % 16]), w[(i - 7) % 16]),
+                add!(sigma0!(w[(i - 15) % 16]), w[i % 16])
+            );
+        }
+        let t1 = add!(
+            add!(hh, Sigma1!(e)),
+            add!(Ch!(e, f, g), add!(_mm256_set1_epi32(k as i32), w[i % 16]))
+        );
+        let t2 = add!(Sigma0!(a), Maj!(a, b, c));
+        hh = g;
+        g = f;
+        f = e;
+        e = add!(d, t1);
+        d = c;
+        c = b;
+        b = a;
+        a = add!(t1, t2);
+    }
+
+    // Transpose back, adding to the previous state
+    for (i, &x) in [a, b, c, d, e, f, g, hh].iter().enumerate() {
+        let mut words = [0u32; LANES];
+        _mm256_storeu_si256(words.as_mut_ptr() as *mut __m256i, x);
+        for (word, h) in words.iter().zip(h.iter_mut()) {
+            h[i] = h[i].wrapping_add(*word);
+        }
+    }
+}
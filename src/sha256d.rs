@@ -13,6 +13,10 @@
 //
 
 //! # SHA256d
+//!
+//! Double SHA256, `SHA256(SHA256(data))`, as used for txids and block hashes.
+//! Data is input into a plain `sha256::HashEngine`; the second pass is done
+//! when the engine is finalized.
 
 use sha256;
 use Hash as HashTrait;
@@ -25,9 +29,23 @@ pub struct Hash([u8; 32]);
 hex_fmt_impl!(Debug, Hash);
 hex_fmt_impl!(Display, Hash);
 hex_fmt_impl!(LowerHex, Hash);
+hex_fmt_impl!(UpperHex, Hash);
 index_impl!(Hash);
 serde_impl!(Hash, 32);
 borrow_slice_impl!(Hash);
+from_str_impl!(Hash);
+try_from_impl!(Hash);
+from_bytes_impl!(Hash, 32);
+into_iter_impl!(Hash);
+eq_bytes_impl!(Hash, 32);
+deref_impl!(Hash, 32);
+inner_ref_impl!(Hash, 32);
+bitops_impl!(Hash);
+ct_eq_impl!(Hash);
+zeroize_impl!(Hash);
+rand_impl!(Hash);
+proptest_impl!(Hash);
+quickcheck_impl!(Hash);
 
 impl HashTrait for Hash {
     type Engine = sha256::HashEngine;
@@ -39,11 +57,7 @@ impl HashTrait for Hash {
 
     fn from_engine(e: sha256::HashEngine) -> Hash {
         let sha2 = sha256::Hash::from_engine(e);
-        let sha2d = sha256::Hash::hash(&sha2[..]);
-
-        let mut ret = [0; 32];
-        ret.copy_from_slice(&sha2d[..]);
-        Hash(ret)
+        Hash(sha256::Hash::hash(&sha2[..]).to_byte_array())
     }
 
     const LEN: usize = 32;
@@ -60,16 +74,43 @@ impl HashTrait for Hash {
 
     const DISPLAY_BACKWARD: bool = true;
 
-    fn into_inner(self) -> Self::Inner {
+    fn from_byte_array(bytes: Self::Inner) -> Self {
+        Hash(bytes)
+    }
+
+    fn to_byte_array(self) -> Self::Inner {
         self.0
     }
 }
 
+/// Hashes consensus-encoded data, such as a transaction or block header,
+/// into its txid or block hash. This is the same as `sha256d::Hash::hash`,
+/// named after the consensus encoding of rust-bitcoin.
+pub fn consensus_hash(data: &[u8]) -> Hash {
+    <Hash as HashTrait>::hash(data)
+}
+
+/// Applies the second SHA256 pass: the result is the SHA256d hash of the
+/// data `hash` is the SHA256 hash of
+impl From<sha256::Hash> for Hash {
+    fn from(hash: sha256::Hash) -> Hash {
+        Hash(sha256::Hash::hash(&hash[..]).to_byte_array())
+    }
+}
+
+/// Reinterprets the bytes of a SHA256d hash as a SHA256 hash, without any
+/// further hashing
+impl From<Hash> for sha256::Hash {
+    fn from(hash: Hash) -> sha256::Hash {
+        sha256::Hash::from_slice(&hash.0).expect("right number of bytes")
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::io::Write;
 
-    use sha256d;
+    use {sha256, sha256d};
     use hex::{FromHex, ToHex};
     use Hash;
 
@@ -94,6 +135,16 @@ input: &'static str,
                 ],
                 output_str: "56944c5d3f98413ef45cf54545538103cc9f298e0575820ad3591376e2e0f65d",
             },
+            Test {
+                input: "The quick brown fox jumps over the lazy dog",
+                output: vec![
+                    0x6d, 0x37, 0x79, 0x50, 0x21, 0xe5, 0x44, 0xd8,
+                    0x2b, 0x41, 0x85, 0x0e, 0xdf, 0x7a, 0xab, 0xab,
+                    0x9a, 0x0e, 0xbe, 0x27, 0x4e, 0x54, 0xa5, 0x19,
+                    0x84, 0x0c, 0x46, 0x66, 0xf3, 0x5b, 0x39, 0x37,
+                ],
+                output_str: "37395bf366460c8419a5544e27be0e9aabab7adf0e85412bd844e5215079376d",
+            },
         ];
 
         for test in tests {
@@ -110,10 +161,45 @@ input: &'static str,
             }
             let manual_hash = sha256d::Hash::from_engine(engine);
             assert_eq!(hash, manual_hash);
-            assert_eq!(hash.into_inner()[..].as_ref(), test.output.as_slice());
+            assert_eq!(hash.to_byte_array()[..].as_ref(), test.output.as_slice());
+
+            // Equal to hashing twice with SHA256
+            let sha2 = sha256::Hash::hash(test.input.as_bytes());
+            assert_eq!(&hash[..], &sha256::Hash::hash(&sha2[..])[..]);
         }
     }
 
+    #[test]
+    fn sha256_conversions() {
+        let data = b"The quick brown fox jumps over the lazy dog";
+        let sha2 = sha256::Hash::hash(data);
+        let sha2d = sha256d::Hash::hash(data);
+
+        // Converting from SHA256 does the second pass...
+        assert_eq!(sha256d::Hash::from(sha2), sha2d);
+        // ...while converting back only copies the bytes
+        let bytes = sha256::Hash::from(sha2d);
+        assert_eq!(&bytes[..], &sha2d[..]);
+        assert_eq!(bytes, sha256::Hash::hash(&sha2[..]));
+        assert_ne!(sha256d::Hash::from(bytes), sha2d);
+    }
+
+    #[test]
+    fn consensus_hash() {
+        // The coinbase transaction of the genesis block
+        let tx = Vec::<u8>::from_hex(
+            "01000000010000000000000000000000000000000000000000000000000000000000000000ffffffff\
+             4d04ffff001d0104455468652054696d65732030332f4a616e2f32303039204368616e63656c6c6f72\
+             206f6e206272696e6b206f66207365636f6e64206261696c6f757420666f722062616e6b73ffffffff\
+             0100f2052a01000000434104678afdb0fe5548271967f1a67130b7105cd6a828e03909a67962e0ea1f\
+             61deb649f6bc3f4cef38c4f35504e51ec112de5c384df7ba0b8d578a4c702b6bf11d5fac00000000"
+        ).expect("valid hex");
+
+        let txid = sha256d::consensus_hash(&tx);
+        assert_eq!(txid.to_hex(), "4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b");
+        assert_eq!(txid, sha256d::Hash::hash(&tx));
+    }
+
     #[cfg(feature="serde")]
     #[test]
     fn sha256_serde() {
@@ -0,0 +1,619 @@
+// Bitcoin Hashes Library
+// Written in 2019 by
+//   The rust-bitcoin developers
+//
+// To the extent possible under law, the author(s) have dedicated all
+// copyright and related and neighboring rights to this software to
+// the public domain worldwide. This software is distributed without
+// any warranty.
+//
+// You should have received a copy of the CC0 Public Domain Dedication
+// along with this software.
+// If not, see <http://creativecommons.org/publicdomain/zero/1.0/>.
+//
+
+//! # SHA256t (tagged SHA256)
+//!
+//! Tagged hashes as defined in BIP340: `SHA256(SHA256(tag) || SHA256(tag) || msg)`.
+//! The tag is fixed per hash type by implementing `Tag` for a marker type;
+//! since the tag prefix fills exactly one block, its midstate can be
+//! precomputed (see the `bitcoin_hashes_macros` crate).
+
+use core::{borrow, cmp, fmt, hash, marker, ops, str};
+
+use hex::{self, FromHex};
+use sha256;
+use {Error, HashEngine as EngineTrait};
+use Hash as HashTrait;
+
+/// Trait representing a tag which can be used as a context for SHA256t hashes
+pub trait Tag {
+    /// Returns a hash engine which has already been fed the tag prefix
+    fn engine() -> sha256::HashEngine;
+
+    /// Name of the tag, used when debug-printing hashes. Defaults to
+    /// `"sha256t"`; the macros defining tags override it with the name of the
+    /// tag type.
+    fn name() -> &'static str {
+        "sha256t"
+    }
+}
+
+/// Defines a marker type implementing `Tag` for the given tag string, and
+/// optionally an alias for the tagged hash type. The tag is hashed each time
+/// an engine is constructed; `#[derive(Tag)]` from the
+/// `bitcoin_hashes_macros` crate precomputes it instead.
+///
+/// ```rust
+/// #[macro_use] extern crate bitcoin_hashes;
+/// use bitcoin_hashes::{sha256t, Hash};
+///
+/// tagged_hash_engine!(
+///     /// The BIP340 challenge tag
+///     pub Challenge, pub ChallengeHash, "BIP0340/challenge"
+/// );
+/// tagged_hash_engine!(TapLeaf, "TapLeaf");
+///
+/// # fn main() {
+/// let hash: ChallengeHash = ChallengeHash::hash(b"message");
+/// let leaf = sha256t::Hash::<TapLeaf>::hash(b"script");
+/// # let _ = (hash, leaf);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! tagged_hash_engine {
+    ($(#[$attr:meta])* $vis:vis $tag:ident, $hash_vis:vis $hash:ident, $tag_str:expr) => {
+        $crate::tagged_hash_engine!($(#[$attr])* $vis $tag, $tag_str);
+
+        /// Tagged SHA256 hash with the tag defined alongside it
+        $hash_vis type $hash = $crate::sha256t::Hash<$tag>;
+    };
+    ($(#[$attr:meta])* $vis:vis $tag:ident, $tag_str:expr) => {
+        $(#[$attr])*
+        #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Default, Hash, Debug)]
+        $vis struct $tag;
+
+        impl $crate::sha256t::Tag for $tag {
+            fn engine() -> $crate::sha256::HashEngine {
+                $crate::sha256t::tag_engine($tag_str)
+            }
+
+            fn name() -> &'static str {
+                stringify!($tag)
+            }
+        }
+    };
+}
+
+/// Constructs an engine which has been fed the prefix for the given tag,
+/// hashing the tag at runtime
+pub fn tag_engine(tag: &str) -> sha256::HashEngine {
+    let tag_hash = sha256::Hash::hash(tag.as_bytes());
+    let mut engine = sha256::Hash::engine();
+    engine.input(&tag_hash[..]);
+    engine.input(&tag_hash[..]);
+    engine.set_initial_state();
+    engine
+}
+
+/// A tag chosen at runtime, for when the tag is not known at compile time.
+///
+/// Since `Tag::engine` has no access to any instance data this cannot
+/// implement `Tag`; its engine produces plain `sha256::Hash`es instead.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct DynamicTag {
+    midstate: sha256::Midstate,
+}
+
+impl DynamicTag {
+    /// Constructs a tag from its string, hashing it at runtime
+    pub fn from_tag_string(tag: &str) -> DynamicTag {
+        DynamicTag::from_tag_hash(sha256::Hash::hash(tag.as_bytes()))
+    }
+
+    /// Constructs a tag from the SHA256 hash of its string
+    pub fn from_tag_hash(tag_hash: sha256::Hash) -> DynamicTag {
+        let mut engine = sha256::Hash::engine();
+        engine.input(&tag_hash[..]);
+        engine.input(&tag_hash[..]);
+        DynamicTag::from_midstate(engine.midstate())
+    }
+
+    /// Constructs a tag from the midstate of an engine which has been fed
+    /// the 64-byte tag prefix
+    pub fn from_midstate(midstate: sha256::Midstate) -> DynamicTag {
+        DynamicTag {
+            midstate,
+        }
+    }
+
+    /// Returns the midstate after the tag prefix
+    pub fn midstate(&self) -> sha256::Midstate {
+        self.midstate
+    }
+
+    /// Returns a hash engine which has already been fed the tag prefix
+    pub fn engine(&self) -> sha256::HashEngine {
+        sha256::HashEngine::from_midstate(self.midstate, 64).expect("the tag prefix is one block")
+    }
+
+    /// Computes the tagged hash of some data
+    pub fn hash(&self, data: &[u8]) -> sha256::Hash {
+        let mut engine = self.engine();
+        engine.input(data);
+        sha256::Hash::from_engine(engine)
+    }
+}
+
+/// Output of the SHA256t hash function
+pub struct Hash<T: Tag>([u8; 32], marker::PhantomData<T>);
+
+impl<T: Tag> Hash<T> {
+    /// The all-zero hash, which is also the `Default`. Producing it as the
+    /// output of the hash function would require finding a SHA256 preimage,
+    /// so it can be used as a sentinel for a hash not yet computed.
+    pub const ZERO: Hash<T> = Hash([0; 32], marker::PhantomData);
+
+    fn internal_new(arr: [u8; 32]) -> Hash<T> {
+        Hash(arr, marker::PhantomData)
+    }
+
+    /// Returns a reference to the bytes of the hash, keeping their length
+    /// in the type unlike `as_ref`
+    pub fn inner_ref(&self) -> &[u8; 32] {
+        &self.0
+    }
+
+    /// Returns a mutable reference to the bytes of the hash. Once they are
+    /// changed the hash no longer is the hash of anything, so like `AsMut`
+    /// this needs the `as_mut` feature.
+    #[cfg(feature = "as_mut")]
+    pub fn inner_mut(&mut self) -> &mut [u8; 32] {
+        &mut self.0
+    }
+
+    /// Compares the bytes of two hashes which may have different tags, for
+    /// the rare cases where that is intended. Such hashes cannot be compared
+    /// with `Ord` or `==`:
+    ///
+    /// ```compile_fail
+    /// # #[macro_use] extern crate bitcoin_hashes;
+    /// # use bitcoin_hashes::Hash;
+    /// tagged_hash_engine!(TagA, HashA, "A");
+    /// tagged_hash_engine!(TagB, HashB, "B");
+    /// # fn main() {
+    /// assert!(HashA::hash(b"") != HashB::hash(b""));
+    /// # }
+    /// ```
+    pub fn cmp_as_bytes<U: Tag>(&self, other: &Hash<U>) -> cmp::Ordering {
+        self.0.cmp(&other.0)
+    }
+
+    /// Whether this is the all-zero `Default` hash, rather than the output
+    /// of the hash function
+    pub fn is_default(&self) -> bool {
+        self.0 == [0; 32]
+    }
+}
+
+impl<T: Tag> Copy for Hash<T> {}
+
+impl<T: Tag> Clone for Hash<T> {
+    fn clone(&self) -> Hash<T> {
+        *self
+    }
+}
+
+impl<T: Tag> PartialEq for Hash<T> {
+    fn eq(&self, other: &Hash<T>) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<T: Tag> Eq for Hash<T> {}
+
+/// The all-zero hash, `Hash::ZERO`
+impl<T: Tag> Default for Hash<T> {
+    fn default() -> Hash<T> {
+        Hash::ZERO
+    }
+}
+
+impl<T: Tag> PartialOrd for Hash<T> {
+    fn partial_cmp(&self, other: &Hash<T>) -> Option<cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Compares the bytes in their internal order, as `sha256::Hash` does.
+/// Hashes with different tags are different types and cannot be compared;
+/// see `cmp_as_bytes`.
+impl<T: Tag> Ord for Hash<T> {
+    fn cmp(&self, other: &Hash<T>) -> cmp::Ordering {
+        self.0.cmp(&other.0)
+    }
+}
+
+impl<T: Tag> hash::Hash for Hash<T> {
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        self.0.hash(state)
+    }
+}
+
+/// Formats as `Tag(hex)`, or with `{:#?}` as a struct naming the tag
+impl<T: Tag> fmt::Debug for Hash<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if f.alternate() {
+            f.debug_struct("Hash")
+                .field("tag", &T::name())
+                .field("bytes", &format_args!("\"{:x}\"", sha256::Hash::from_byte_array(self.0)))
+                .finish()
+        } else {
+            write!(f, "{}(", T::name())?;
+            hex::format_hex(&self.0, &mut *f)?;
+            f.write_str(")")
+        }
+    }
+}
+
+impl<T: Tag> fmt::Display for Hash<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        hex::format_hex(&self.0, f)
+    }
+}
+
+impl<T: Tag> fmt::LowerHex for Hash<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        hex::format_hex(&self.0, f)
+    }
+}
+
+impl<T: Tag> fmt::UpperHex for Hash<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        hex::format_hex_uppercase(&self.0, f)
+    }
+}
+
+impl<T: Tag> ops::Index<usize> for Hash<T> {
+    type Output = u8;
+    fn index(&self, index: usize) -> &u8 {
+        &self.0[index]
+    }
+}
+
+impl<T: Tag> ops::Index<ops::Range<usize>> for Hash<T> {
+    type Output = [u8];
+    fn index(&self, index: ops::Range<usize>) -> &[u8] {
+        &self.0[index]
+    }
+}
+
+impl<T: Tag> ops::Index<ops::RangeFrom<usize>> for Hash<T> {
+    type Output = [u8];
+    fn index(&self, index: ops::RangeFrom<usize>) -> &[u8] {
+        &self.0[index]
+    }
+}
+
+impl<T: Tag> ops::Index<ops::RangeTo<usize>> for Hash<T> {
+    type Output = [u8];
+    fn index(&self, index: ops::RangeTo<usize>) -> &[u8] {
+        &self.0[index]
+    }
+}
+
+impl<T: Tag> ops::Index<ops::RangeFull> for Hash<T> {
+    type Output = [u8];
+    fn index(&self, index: ops::RangeFull) -> &[u8] {
+        &self.0[index]
+    }
+}
+
+/// Dereferences to the bytes, as for the other hash types
+impl<T: Tag> ops::Deref for Hash<T> {
+    type Target = [u8; 32];
+    fn deref(&self) -> &[u8; 32] {
+        &self.0
+    }
+}
+
+impl<T: Tag> borrow::Borrow<[u8]> for Hash<T> {
+    fn borrow(&self) -> &[u8] {
+        &self[..]
+    }
+}
+
+impl<T: Tag> AsRef<[u8]> for Hash<T> {
+    fn as_ref(&self) -> &[u8] {
+        &self.0[..]
+    }
+}
+
+#[cfg(feature = "as_mut")]
+impl<T: Tag> AsMut<[u8]> for Hash<T> {
+    fn as_mut(&mut self) -> &mut [u8] {
+        &mut self.0[..]
+    }
+}
+
+/// Parses the hex of a hash, ignoring surrounding whitespace such as the
+/// newline at the end of a line read from a file
+impl<T: Tag> str::FromStr for Hash<T> {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Hash<T>, Error> {
+        FromHex::from_hex(s.trim())
+    }
+}
+
+impl<T: Tag> HashTrait for Hash<T> {
+    type Engine = sha256::HashEngine;
+    type Inner = [u8; 32];
+
+    fn engine() -> sha256::HashEngine {
+        T::engine()
+    }
+
+    fn from_engine(e: sha256::HashEngine) -> Hash<T> {
+        Hash::internal_new(sha256::Hash::from_engine(e).to_byte_array())
+    }
+
+    const LEN: usize = 32;
+
+    fn from_slice(sl: &[u8]) -> Result<Hash<T>, Error> {
+        if sl.len() != 32 {
+            Err(Error::InvalidLength(Self::LEN, sl.len()))
+        } else {
+            let mut ret = [0; 32];
+            ret.copy_from_slice(sl);
+            Ok(Hash::internal_new(ret))
+        }
+    }
+
+    fn from_byte_array(bytes: Self::Inner) -> Self {
+        Hash::internal_new(bytes)
+    }
+
+    fn to_byte_array(self) -> Self::Inner {
+        self.0
+    }
+}
+
+/// Reinterprets the bytes of a SHA256 hash as a tagged hash. Nothing is
+/// hashed: this only asserts that the bytes are a hash with the tag `T`.
+impl<T: Tag> From<sha256::Hash> for Hash<T> {
+    fn from(hash: sha256::Hash) -> Hash<T> {
+        Hash::internal_new(hash.to_byte_array())
+    }
+}
+
+/// Reinterprets the bytes of a tagged hash as a plain SHA256 hash, without
+/// any further hashing
+impl<T: Tag> From<Hash<T>> for sha256::Hash {
+    fn from(hash: Hash<T>) -> sha256::Hash {
+        sha256::Hash::from_slice(&hash.0).expect("right number of bytes")
+    }
+}
+
+#[cfg(feature = "subtle")]
+impl<T: Tag> ::subtle::ConstantTimeEq for Hash<T> {
+    fn ct_eq(&self, other: &Hash<T>) -> ::subtle::Choice {
+        ::subtle::ConstantTimeEq::ct_eq(&self.0[..], &other.0[..])
+    }
+}
+
+#[cfg(feature="serde")]
+impl<T: Tag> ::serde::Serialize for Hash<T> {
+    fn serialize<S: ::serde::Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        // Tagged hashes are displayed forward, exactly like plain SHA256 hashes
+        let hash = sha256::Hash::from_slice(&self[..]).expect("right number of bytes");
+        ::serde::Serialize::serialize(&hash, s)
+    }
+}
+
+#[cfg(feature="serde")]
+impl<'de, T: Tag> ::serde::Deserialize<'de> for Hash<T> {
+    fn deserialize<D: ::serde::Deserializer<'de>>(d: D) -> Result<Hash<T>, D::Error> {
+        let hash: sha256::Hash = ::serde::Deserialize::deserialize(d)?;
+        Ok(Hash::internal_new(hash.to_byte_array()))
+    }
+}
+
+/// Generates hashes of uniformly random bytes, for any tag
+#[cfg(feature = "proptest")]
+impl<T: Tag> ::proptest::arbitrary::Arbitrary for Hash<T> {
+    type Parameters = ();
+    type Strategy = ::proptest::strategy::Map<
+        <[u8; 32] as ::proptest::arbitrary::Arbitrary>::Strategy,
+        fn([u8; 32]) -> Hash<T>,
+    >;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        ::proptest::strategy::Strategy::prop_map(
+            ::proptest::arbitrary::any::<[u8; 32]>(),
+            Hash::internal_new as fn(_) -> _,
+        )
+    }
+}
+
+#[cfg(feature = "quickcheck")]
+impl<T: 'static + Tag> ::quickcheck::Arbitrary for Hash<T> {
+    fn arbitrary(g: &mut ::quickcheck::Gen) -> Hash<T> {
+        ::util::quickcheck_arbitrary(g)
+    }
+
+    fn shrink(&self) -> ::std::boxed::Box<dyn Iterator<Item = Hash<T>>> {
+        ::util::quickcheck_shrink(*self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hex::ToHex;
+    use sha256;
+    use sha256t;
+    use {Hash, HashEngine};
+
+    /// The BIP340 challenge tag
+    pub struct Challenge;
+
+    impl sha256t::Tag for Challenge {
+        fn engine() -> sha256::HashEngine {
+            sha256t::tag_engine("BIP0340/challenge")
+        }
+
+        fn name() -> &'static str {
+            "Challenge"
+        }
+    }
+
+    #[test]
+    fn default_tag_name() {
+        struct Unnamed;
+
+        impl sha256t::Tag for Unnamed {
+            fn engine() -> sha256::HashEngine {
+                sha256t::tag_engine("unnamed")
+            }
+        }
+
+        let hash = sha256t::Hash::<Unnamed>::hash(b"message");
+        assert_eq!(format!("{:?}", hash), format!("sha256t({})", hash));
+    }
+
+    #[test]
+    fn dynamic_tag() {
+        let tag = sha256t::DynamicTag::from_tag_string("BIP0340/challenge");
+        assert_eq!(tag.midstate(), sha256t::tag_engine("BIP0340/challenge").midstate());
+        assert_eq!(tag, sha256t::DynamicTag::from_tag_hash(sha256::Hash::hash(b"BIP0340/challenge")));
+        assert_eq!(tag, sha256t::DynamicTag::from_midstate(tag.midstate()));
+
+        let hash = sha256t::Hash::<Challenge>::hash(b"message");
+        assert_eq!(&tag.hash(b"message")[..], &hash[..]);
+
+        let mut engine = tag.engine();
+        engine.input(b"mess");
+        engine.input(b"age");
+        assert_eq!(&sha256::Hash::from_engine(engine)[..], &hash[..]);
+    }
+
+    #[test]
+    fn tagged_hash() {
+        let tag_hash = sha256::Hash::hash(b"BIP0340/challenge");
+        let mut engine = sha256::Hash::engine();
+        engine.input(&tag_hash[..]);
+        engine.input(&tag_hash[..]);
+        engine.input(b"message");
+        let expected = sha256::Hash::from_engine(engine);
+
+        let hash = sha256t::Hash::<Challenge>::hash(b"message");
+        assert_eq!(&hash[..], &expected[..]);
+        assert_eq!(hash.to_hex(), expected.to_hex());
+        assert_eq!(hash.to_string().parse::<sha256t::Hash<Challenge>>(), Ok(hash));
+
+        // Only the debug output names the tag
+        assert_eq!(format!("{:?}", hash), format!("Challenge({})", expected));
+        assert_eq!(format!("{}", hash), format!("{}", expected));
+        assert_eq!(
+            format!("{:#?}", hash),
+            format!("Hash {{\n    tag: \"Challenge\",\n    bytes: \"{}\",\n}}", expected),
+        );
+    }
+
+    #[test]
+    fn sha256_conversions() {
+        let hash = sha256t::Hash::<Challenge>::hash(b"message");
+
+        // The bytes are carried over unchanged in both directions
+        let plain = sha256::Hash::from(hash);
+        assert_eq!(&plain[..], &hash[..]);
+        assert_eq!(sha256t::Hash::<Challenge>::from(plain), hash);
+    }
+
+    #[test]
+    fn zero() {
+        const UNSET: sha256t::Hash<Challenge> = sha256t::Hash::ZERO;
+        assert_eq!(UNSET, sha256t::Hash::default());
+        assert_eq!(&UNSET[..], &[0; 32][..]);
+        assert!(UNSET.is_default());
+        assert!(!sha256t::Hash::<Challenge>::hash(b"").is_default());
+    }
+
+    #[test]
+    fn from_str() {
+        fn parse<T: sha256t::Tag>(s: &str) -> Result<sha256t::Hash<T>, ::Error> {
+            s.parse()
+        }
+
+        let hash = sha256t::Hash::<Challenge>::hash(b"message");
+        assert_eq!(parse::<Challenge>(&hash.to_string()), Ok(hash));
+        assert_eq!(parse::<Challenge>(&hash.to_hex().to_uppercase()), Ok(hash));
+        // Surrounding whitespace is ignored, but not whitespace within
+        assert_eq!(parse::<Challenge>(&format!(" {}\n", hash)), Ok(hash));
+        assert!(parse::<Challenge>(&format!("{} {}", &hash.to_hex()[..32], &hash.to_hex()[32..])).is_err());
+        assert_eq!(parse::<Challenge>("00"), Err(::Error::InvalidLength(64, 2)));
+    }
+
+    tagged_hash_engine!(
+        /// The BIP341 leaf tag
+        TapLeaf, TapLeafHash, "TapLeaf"
+    );
+    tagged_hash_engine!(pub(crate) Unaliased, "BIP0340/challenge");
+
+    #[test]
+    fn tag_macro() {
+        use sha256t::Tag;
+
+        assert_eq!(TapLeaf::engine().midstate(), sha256t::tag_engine("TapLeaf").midstate());
+        assert_eq!(TapLeaf::name(), "TapLeaf");
+        let tag: TapLeaf = Default::default();
+        assert_eq!(tag, TapLeaf);
+
+        let hash = TapLeafHash::hash(b"script");
+        assert_eq!(format!("{:?}", hash), format!("TapLeaf({})", hash));
+
+        let hash = sha256t::Hash::<Unaliased>::hash(b"message");
+        assert_eq!(&hash[..], &sha256t::Hash::<Challenge>::hash(b"message")[..]);
+    }
+
+    #[test]
+    fn cmp_as_bytes() {
+        use std::cmp::Ordering;
+
+        let leaf = TapLeafHash::hash(b"message");
+        let challenge = sha256t::Hash::<Challenge>::hash(b"message");
+        let rebranded = sha256t::Hash::<Challenge>::from(sha256::Hash::from(leaf));
+        assert_eq!(leaf.cmp_as_bytes(&rebranded), Ordering::Equal);
+        assert_eq!(leaf.cmp_as_bytes(&challenge), leaf[..].cmp(&challenge[..]));
+        assert_eq!(rebranded.cmp(&challenge), sha256::Hash::from(rebranded).cmp(&challenge.into()));
+    }
+
+    #[cfg(feature = "proptest")]
+    ::proptest::proptest! {
+        #[test]
+        fn arbitrary(
+            hash in ::proptest::arbitrary::any::<sha256::Hash>(),
+            tagged in ::proptest::arbitrary::any::<sha256t::Hash<Challenge>>(),
+        ) {
+            let rebranded = sha256t::Hash::<Challenge>::from(hash);
+            ::proptest::prop_assert_eq!(&rebranded[..], &hash[..]);
+            ::proptest::prop_assert_eq!(sha256::Hash::from(tagged).to_byte_array(), tagged.to_byte_array());
+        }
+    }
+
+    #[cfg(feature = "quickcheck")]
+    #[test]
+    fn quickcheck_shrink() {
+        use quickcheck::Arbitrary;
+
+        let nonzero = |hash: &sha256t::Hash<Challenge>| hash.iter().filter(|b| **b != 0).count();
+
+        let hash = sha256t::Hash::<Challenge>::hash(b"message");
+        let shrunk: Vec<_> = hash.shrink().collect();
+        assert_eq!(shrunk[0], sha256t::Hash::all_zeros());
+        assert!(shrunk.iter().all(|s| nonzero(s) < nonzero(&hash)));
+        assert!(shrunk.iter().any(|s| nonzero(s) == nonzero(&hash) - 1));
+
+        assert_eq!(sha256t::Hash::<Challenge>::all_zeros().shrink().count(), 0);
+        assert_eq!(sha256::Hash::from(hash).shrink().count(), shrunk.len());
+    }
+}
@@ -0,0 +1,223 @@
+// Bitcoin Hashes Library
+// Written in 2019 by
+//   The rust-bitcoin developers
+//
+// To the extent possible under law, the author(s) have dedicated all
+// copyright and related and neighboring rights to this software to
+// the public domain worldwide. This software is distributed without
+// any warranty.
+//
+// You should have received a copy of the CC0 Public Domain Dedication
+// along with this software.
+// If not, see <http://creativecommons.org/publicdomain/zero/1.0/>.
+//
+
+//! # SHA3-256
+//!
+//! The FIPS 202 SHA3-256 function. Note that this differs from the original
+//! Keccak-256 used by Ethereum in its padding.
+
+use keccak::{self, Sponge};
+use HashEngine as EngineTrait;
+use Hash as HashTrait;
+use Error;
+
+const BLOCK_SIZE: usize = 136;
+
+/// Engine to compute SHA3-256 hash function
+#[derive(Clone)]
+pub struct HashEngine(Sponge);
+
+write_impl!(HashEngine);
+extend_impl!(HashEngine, Hash);
+engine_default_impl!(HashEngine, Hash);
+zeroize_impl!(HashEngine);
+
+// The sponge zeroizes itself when dropped
+#[cfg(feature = "zeroize")]
+impl ::zeroize::ZeroizeOnDrop for HashEngine {}
+
+impl EngineTrait for HashEngine {
+    type MidState = [u8; 200];
+
+    fn midstate(&self) -> [u8; 200] {
+        self.0.midstate()
+    }
+
+    const BLOCK_SIZE: usize = BLOCK_SIZE;
+
+    fn input(&mut self, data: &[u8]) {
+        self.0.absorb(data)
+    }
+}
+
+/// Output of the SHA3-256 hash function
+#[derive(Copy, Clone, PartialEq, Eq, Default, PartialOrd, Ord, Hash)]
+pub struct Hash([u8; 32]);
+
+hex_fmt_impl!(Debug, Hash);
+hex_fmt_impl!(Display, Hash);
+hex_fmt_impl!(LowerHex, Hash);
+hex_fmt_impl!(UpperHex, Hash);
+index_impl!(Hash);
+serde_impl!(Hash, 32);
+borrow_slice_impl!(Hash);
+from_str_impl!(Hash);
+try_from_impl!(Hash);
+from_bytes_impl!(Hash, 32);
+into_iter_impl!(Hash);
+eq_bytes_impl!(Hash, 32);
+deref_impl!(Hash, 32);
+inner_ref_impl!(Hash, 32);
+bitops_impl!(Hash);
+ct_eq_impl!(Hash);
+zeroize_impl!(Hash);
+rand_impl!(Hash);
+proptest_impl!(Hash);
+quickcheck_impl!(Hash);
+
+impl HashTrait for Hash {
+    type Engine = HashEngine;
+    type Inner = [u8; 32];
+
+    fn engine() -> HashEngine {
+        HashEngine(Sponge::new(BLOCK_SIZE))
+    }
+
+    fn from_engine(mut e: HashEngine) -> Hash {
+        let mut ret = [0; 32];
+        e.0.pad(keccak::SHA3_DOMAIN);
+        e.0.squeeze(&mut ret);
+        Hash(ret)
+    }
+
+    const LEN: usize = 32;
+
+    fn from_slice(sl: &[u8]) -> Result<Hash, Error> {
+        if sl.len() != 32 {
+            Err(Error::InvalidLength(Self::LEN, sl.len()))
+        } else {
+            let mut ret = [0; 32];
+            ret.copy_from_slice(sl);
+            Ok(Hash(ret))
+        }
+    }
+
+    fn from_byte_array(bytes: Self::Inner) -> Self {
+        Hash(bytes)
+    }
+
+    fn to_byte_array(self) -> Self::Inner {
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use sha3_256;
+    use hex::{FromHex, ToHex};
+    use Hash;
+
+    #[derive(Clone)]
+    struct Test {
+        input: Vec<u8>,
+        output_str: &'static str,
+    }
+
+    #[test]
+    fn test() {
+        let tests = vec![
+            // Test vectors from the NIST examples, plus one spanning two blocks
+            Test {
+                input: vec![],
+                output_str: "a7ffc6f8bf1ed76651c14756a061d662f580ff4de43b49fa82d80a4b80f8434a",
+            },
+            Test {
+                input: b"abc".to_vec(),
+                output_str: "3a985da74fe225b2045c172d6bd390bd855f086e3e9d525b46bfe24511431532",
+            },
+            Test {
+                input: b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq".to_vec(),
+                output_str: "41c0dba2a9d6240849100376a8235e2c82e1b9998a999e21db32dd97496d3376",
+            },
+            Test {
+                input: (0..200).collect(),
+                output_str: "5f728f63bf5ee48c77f453c0490398fa645b8d4c4e56be9a41cfec344d6ca899",
+            },
+        ];
+
+        for test in tests {
+            // Hash through high-level API, check hex encoding/decoding
+            let hash = sha3_256::Hash::hash(&test.input);
+            assert_eq!(hash, sha3_256::Hash::from_hex(test.output_str).expect("parse hex"));
+            assert_eq!(&hash.to_hex(), &test.output_str);
+
+            // Hash through engine, checking that we can input byte by byte
+            let mut engine = sha3_256::Hash::engine();
+            for ch in &test.input {
+                engine.write_all(&[*ch]).expect("write to engine");
+            }
+            let manual_hash = sha3_256::Hash::from_engine(engine);
+            assert_eq!(hash, manual_hash);
+        }
+    }
+
+    #[cfg(feature="serde")]
+    #[test]
+    fn sha3_256_serde() {
+        use serde_test::{Configure, Token, assert_tokens};
+
+        static HASH_BYTES: [u8; 32] = [
+            0x3a, 0x98, 0x5d, 0xa7, 0x4f, 0xe2, 0x25, 0xb2,
+            0x04, 0x5c, 0x17, 0x2d, 0x6b, 0xd3, 0x90, 0xbd,
+            0x85, 0x5f, 0x08, 0x6e, 0x3e, 0x9d, 0x52, 0x5b,
+            0x46, 0xbf, 0xe2, 0x45, 0x11, 0x43, 0x15, 0x32,
+        ];
+
+        let hash = sha3_256::Hash::from_slice(&HASH_BYTES).expect("right number of bytes");
+        assert_tokens(&hash.compact(), &[Token::BorrowedBytes(&HASH_BYTES[..])]);
+        assert_tokens(&hash.readable(), &[Token::Str("3a985da74fe225b2045c172d6bd390bd855f086e3e9d525b46bfe24511431532")]);
+    }
+}
+
+#[cfg(all(test, feature="unstable"))]
+mod benches {
+    use std::io::Write;
+    use test::Bencher;
+
+    use sha3_256;
+    use Hash;
+
+    #[bench]
+    pub fn sha3_256_10(bh: & mut Bencher) {
+        let mut engine = sha3_256::Hash::engine();
+        let bytes = [1u8; 10];
+        bh.iter( || {
+            engine.write_all(&bytes).expect("write");
+        });
+        bh.bytes = bytes.len() as u64;
+    }
+
+    #[bench]
+    pub fn sha3_256_1k(bh: & mut Bencher) {
+        let mut engine = sha3_256::Hash::engine();
+        let bytes = [1u8; 1024];
+        bh.iter( || {
+            engine.write_all(&bytes).expect("write");
+        });
+        bh.bytes = bytes.len() as u64;
+    }
+
+    #[bench]
+    pub fn sha3_256_64k(bh: & mut Bencher) {
+        let mut engine = sha3_256::Hash::engine();
+        let bytes = [1u8; 65536];
+        bh.iter( || {
+            engine.write_all(&bytes).expect("write");
+        });
+        bh.bytes = bytes.len() as u64;
+    }
+
+}
@@ -0,0 +1,279 @@
+// Bitcoin Hashes Library
+// Written in 2019 by
+//   The rust-bitcoin developers
+//
+// To the extent possible under law, the author(s) have dedicated all
+// copyright and related and neighboring rights to this software to
+// the public domain worldwide. This software is distributed without
+// any warranty.
+//
+// You should have received a copy of the CC0 Public Domain Dedication
+// along with this software.
+// If not, see <http://creativecommons.org/publicdomain/zero/1.0/>.
+//
+
+//! # SHA3-512
+//!
+//! The FIPS 202 SHA3-512 function.
+
+use core::hash;
+
+use keccak::{self, Sponge};
+use HashEngine as EngineTrait;
+use Hash as HashTrait;
+use Error;
+
+const BLOCK_SIZE: usize = 72;
+
+/// Engine to compute SHA3-512 hash function
+#[derive(Clone)]
+pub struct HashEngine(Sponge);
+
+write_impl!(HashEngine);
+extend_impl!(HashEngine, Hash);
+engine_default_impl!(HashEngine, Hash);
+zeroize_impl!(HashEngine);
+
+// The sponge zeroizes itself when dropped
+#[cfg(feature = "zeroize")]
+impl ::zeroize::ZeroizeOnDrop for HashEngine {}
+
+impl EngineTrait for HashEngine {
+    type MidState = [u8; 200];
+
+    fn midstate(&self) -> [u8; 200] {
+        self.0.midstate()
+    }
+
+    const BLOCK_SIZE: usize = BLOCK_SIZE;
+
+    fn input(&mut self, data: &[u8]) {
+        self.0.absorb(data)
+    }
+}
+
+/// Output of the SHA3-512 hash function
+pub struct Hash([u8; 64]);
+
+impl Copy for Hash {}
+
+impl Clone for Hash {
+    fn clone(&self) -> Hash {
+        *self
+    }
+}
+
+impl PartialEq for Hash {
+    fn eq(&self, other: &Hash) -> bool {
+        self.0[..] == other.0[..]
+    }
+}
+
+impl Eq for Hash {}
+
+impl Default for Hash {
+    fn default() -> Hash {
+        Hash([0; 64])
+    }
+}
+
+use core::cmp::Ordering;
+
+impl PartialOrd for Hash {
+    fn partial_cmp(&self, other: &Hash) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Hash {
+    fn cmp(&self, other: &Hash) -> Ordering {
+        self.0[..].cmp(&other.0[..])
+    }
+}
+
+impl hash::Hash for Hash {
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        self.0[..].hash(state)
+    }
+}
+
+hex_fmt_impl!(Debug, Hash);
+hex_fmt_impl!(Display, Hash);
+hex_fmt_impl!(LowerHex, Hash);
+hex_fmt_impl!(UpperHex, Hash);
+index_impl!(Hash);
+serde_impl!(Hash, 64);
+borrow_slice_impl!(Hash);
+from_str_impl!(Hash);
+try_from_impl!(Hash);
+from_bytes_impl!(Hash, 64);
+into_iter_impl!(Hash);
+eq_bytes_impl!(Hash, 64);
+deref_impl!(Hash, 64);
+inner_ref_impl!(Hash, 64);
+bitops_impl!(Hash);
+ct_eq_impl!(Hash);
+zeroize_impl!(Hash);
+rand_impl!(Hash);
+proptest_impl!(Hash);
+quickcheck_impl!(Hash);
+
+impl HashTrait for Hash {
+    type Engine = HashEngine;
+    type Inner = [u8; 64];
+
+    fn engine() -> HashEngine {
+        HashEngine(Sponge::new(BLOCK_SIZE))
+    }
+
+    fn from_engine(mut e: HashEngine) -> Hash {
+        let mut ret = [0; 64];
+        e.0.pad(keccak::SHA3_DOMAIN);
+        e.0.squeeze(&mut ret);
+        Hash(ret)
+    }
+
+    const LEN: usize = 64;
+
+    fn from_slice(sl: &[u8]) -> Result<Hash, Error> {
+        if sl.len() != 64 {
+            Err(Error::InvalidLength(Self::LEN, sl.len()))
+        } else {
+            let mut ret = [0; 64];
+            ret.copy_from_slice(sl);
+            Ok(Hash(ret))
+        }
+    }
+
+    fn from_byte_array(bytes: Self::Inner) -> Self {
+        Hash(bytes)
+    }
+
+    fn to_byte_array(self) -> Self::Inner {
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use sha3_512;
+    use hex::{FromHex, ToHex};
+    use Hash;
+
+    #[derive(Clone)]
+    struct Test {
+        input: Vec<u8>,
+        output_str: &'static str,
+    }
+
+    #[test]
+    fn test() {
+        let tests = vec![
+            // Test vectors from the NIST examples, plus one spanning two blocks
+            Test {
+                input: vec![],
+                output_str: "a69f73cca23a9ac5c8b567dc185a756e97c982164fe25859e0d1dcc1475c80a6\
+                             15b2123af1f5f94c11e3e9402c3ac558f500199d95b6d3e301758586281dcd26",
+            },
+            Test {
+                input: b"abc".to_vec(),
+                output_str: "b751850b1a57168a5693cd924b6b096e08f621827444f70d884f5d0240d2712e\
+                             10e116e9192af3c91a7ec57647e3934057340b4cf408d5a56592f8274eec53f0",
+            },
+            Test {
+                input: b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq".to_vec(),
+                output_str: "04a371e84ecfb5b8b77cb48610fca8182dd457ce6f326a0fd3d7ec2f1e91636d\
+                             ee691fbe0c985302ba1b0d8dc78c086346b533b49c030d99a27daf1139d6e75e",
+            },
+            Test {
+                input: (0..200).collect(),
+                output_str: "ea5d05f19348dd589793354793a15f37a73b4c0bb4e750b9a00757dfce2f8b65\
+                             a64191bb9b137de00feef6474cfd47abf7880efbc51614a5715df12cfe0caee3",
+            },
+        ];
+
+        for test in tests {
+            // Hash through high-level API, check hex encoding/decoding
+            let hash = sha3_512::Hash::hash(&test.input);
+            assert_eq!(hash, sha3_512::Hash::from_hex(test.output_str).expect("parse hex"));
+            assert_eq!(&hash.to_hex(), &test.output_str);
+
+            // Hash through engine, checking that we can input byte by byte
+            let mut engine = sha3_512::Hash::engine();
+            for ch in &test.input {
+                engine.write_all(&[*ch]).expect("write to engine");
+            }
+            let manual_hash = sha3_512::Hash::from_engine(engine);
+            assert_eq!(hash, manual_hash);
+        }
+    }
+
+    #[cfg(feature="serde")]
+    #[test]
+    fn sha3_512_serde() {
+        use serde_test::{Configure, Token, assert_tokens};
+
+        static HASH_BYTES: [u8; 64] = [
+            0xb7, 0x51, 0x85, 0x0b, 0x1a, 0x57, 0x16, 0x8a,
+            0x56, 0x93, 0xcd, 0x92, 0x4b, 0x6b, 0x09, 0x6e,
+            0x08, 0xf6, 0x21, 0x82, 0x74, 0x44, 0xf7, 0x0d,
+            0x88, 0x4f, 0x5d, 0x02, 0x40, 0xd2, 0x71, 0x2e,
+            0x10, 0xe1, 0x16, 0xe9, 0x19, 0x2a, 0xf3, 0xc9,
+            0x1a, 0x7e, 0xc5, 0x76, 0x47, 0xe3, 0x93, 0x40,
+            0x57, 0x34, 0x0b, 0x4c, 0xf4, 0x08, 0xd5, 0xa5,
+            0x65, 0x92, 0xf8, 0x27, 0x4e, 0xec, 0x53, 0xf0,
+        ];
+
+        let hash = sha3_512::Hash::from_slice(&HASH_BYTES).expect("right number of bytes");
+        assert_tokens(&hash.compact(), &[Token::BorrowedBytes(&HASH_BYTES[..])]);
+        assert_tokens(
+            &hash.readable(),
+            &[Token::Str(
+                "b751850b1a57168a5693cd924b6b096e08f621827444f70d884f5d0240d2712e\
+                 10e116e9192af3c91a7ec57647e3934057340b4cf408d5a56592f8274eec53f0"
+            )],
+        );
+    }
+}
+
+#[cfg(all(test, feature="unstable"))]
+mod benches {
+    use std::io::Write;
+    use test::Bencher;
+
+    use sha3_512;
+    use Hash;
+
+    #[bench]
+    pub fn sha3_512_10(bh: & mut Bencher) {
+        let mut engine = sha3_512::Hash::engine();
+        let bytes = [1u8; 10];
+        bh.iter( || {
+            engine.write_all(&bytes).expect("write");
+        });
+        bh.bytes = bytes.len() as u64;
+    }
+
+    #[bench]
+    pub fn sha3_512_1k(bh: & mut Bencher) {
+        let mut engine = sha3_512::Hash::engine();
+        let bytes = [1u8; 1024];
+        bh.iter( || {
+            engine.write_all(&bytes).expect("write");
+        });
+        bh.bytes = bytes.len() as u64;
+    }
+
+    #[bench]
+    pub fn sha3_512_64k(bh: & mut Bencher) {
+        let mut engine = sha3_512::Hash::engine();
+        let bytes = [1u8; 65536];
+        bh.iter( || {
+            engine.write_all(&bytes).expect("write");
+        });
+        bh.bytes = bytes.len() as u64;
+    }
+
+}
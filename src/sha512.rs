@@ -19,10 +19,11 @@
 
 //! # SHA512
 
-use std::hash;
+use core::hash;
 
 use byteorder::{ByteOrder, BigEndian};
 
+use hex;
 use HashEngine as EngineTrait;
 use Hash as HashTrait;
 use Error;
@@ -38,6 +39,9 @@ pub struct HashEngine {
 }
 
 write_impl!(HashEngine);
+extend_impl!(HashEngine, Hash);
+engine_default_impl!(HashEngine, Hash);
+zeroize_engine_impl!(HashEngine);
 
 impl Clone for HashEngine {
     fn clone(&self) -> HashEngine {
@@ -49,24 +53,68 @@ impl Clone for HashEngine {
     }
 }
 
+impl HashEngine {
+    /// Construct an engine starting from the given initial state, as used
+    /// by the truncated SHA512 variants
+    pub(crate) fn with_iv(iv: [u64; 8]) -> HashEngine {
+        HashEngine {
+            h: iv,
+            length: 0,
+            buffer: [0; BLOCK_SIZE],
+        }
+    }
+
+    /// Create a new engine from a midstate, as output by `midstate`, and the
+    /// number of bytes which were hashed to produce it. This allows a long
+    /// hashing operation to be checkpointed and resumed later.
+    ///
+    /// Since the midstate does not cover data buffered in a partial block,
+    /// `length` must be a multiple of the block size. Otherwise an
+    /// `InvalidLength` error is returned, giving the last block boundary
+    /// before `length` as the expected length.
+    pub fn from_midstate(midstate: Midstate, length: usize) -> Result<HashEngine, Error> {
+        let partial = length % BLOCK_SIZE;
+        if partial != 0 {
+            return Err(Error::InvalidLength(length - partial, length));
+        }
+
+        let mut h = [0; 8];
+        BigEndian::read_u64_into(&midstate[..], &mut h);
+        Ok(HashEngine {
+            buffer: [0; BLOCK_SIZE],
+            h,
+            length,
+        })
+    }
+
+    /// Returns the number of bytes input so far, such as for reporting the
+    /// progress of hashing a large file. SHA512 pads messages with a 128-bit
+    /// length, hence the return type.
+    pub fn n_bytes_hashed(&self) -> u128 {
+        self.length as u128
+    }
+}
+
 impl EngineTrait for HashEngine {
-    type MidState = [u8; 64];
+    type MidState = Midstate;
 
     #[cfg(not(feature = "fuzztarget"))]
-    fn midstate(&self) -> [u8; 64] {
+    fn midstate(&self) -> Midstate {
         let mut ret = [0; 64];
         BigEndian::write_u64_into(&self.h, &mut ret);
-        ret
+        Midstate(ret)
     }
 
     #[cfg(feature = "fuzztarget")]
-    fn midstate(&self) -> [u8; 64] {
+    fn midstate(&self) -> Midstate {
         let mut ret = [0; 64];
         ret.copy_from_slice(&self.buffer[..64]);
-        ret
+        Midstate(ret)
     }
 
     const BLOCK_SIZE: usize = 128;
+
+    engine_input_impl!();
 }
 
 /// Output of the SHA256 hash function
@@ -96,7 +144,7 @@ impl Default for Hash {
     }
 }
 
-use std::cmp::Ordering;
+use core::cmp::Ordering;
 
 impl PartialOrd for Hash {
     fn partial_cmp(&self, other: &Hash) -> Option<Ordering> {
@@ -119,52 +167,163 @@ impl hash::Hash for Hash {
 hex_fmt_impl!(Debug, Hash);
 hex_fmt_impl!(Display, Hash);
 hex_fmt_impl!(LowerHex, Hash);
+hex_fmt_impl!(UpperHex, Hash);
 index_impl!(Hash);
 serde_impl!(Hash, 64);
 borrow_slice_impl!(Hash);
+from_str_impl!(Hash);
+try_from_impl!(Hash);
+from_bytes_impl!(Hash, 64);
+into_iter_impl!(Hash);
+eq_bytes_impl!(Hash, 64);
+deref_impl!(Hash, 64);
+inner_ref_impl!(Hash, 64);
+bitops_impl!(Hash);
+ct_eq_impl!(Hash);
+zeroize_impl!(Hash);
+rand_impl!(Hash);
+proptest_impl!(Hash);
+quickcheck_impl!(Hash);
+
+/// Internal state of a SHA512 engine after hashing some number of full blocks
+pub struct Midstate(pub [u8; 64]);
+
+impl Copy for Midstate {}
+
+impl Clone for Midstate {
+    fn clone(&self) -> Midstate {
+        *self
+    }
+}
+
+impl PartialEq for Midstate {
+    fn eq(&self, other: &Midstate) -> bool {
+        self.0[..] == other.0[..]
+    }
+}
+
+impl Eq for Midstate {}
+
+impl Default for Midstate {
+    fn default() -> Midstate {
+        Midstate([0; 64])
+    }
+}
+
+impl PartialOrd for Midstate {
+    fn partial_cmp(&self, other: &Midstate) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Midstate {
+    fn cmp(&self, other: &Midstate) -> Ordering {
+        self.0.cmp(&other.0)
+    }
+}
+
+impl hash::Hash for Midstate {
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        self.0.hash(state)
+    }
+}
+
+hex_fmt_impl!(Debug, Midstate);
+hex_fmt_impl!(Display, Midstate);
+hex_fmt_impl!(LowerHex, Midstate);
+hex_fmt_impl!(UpperHex, Midstate);
+index_impl!(Midstate);
+serde_impl!(Midstate, 64);
+borrow_slice_impl!(Midstate);
+
+impl Midstate {
+    /// Length of the midstate, in bytes
+    pub const LEN: usize = 64;
+
+    /// Flag indicating whether user-visible serializations of this midstate
+    /// should be backward. They are not.
+    pub const DISPLAY_BACKWARD: bool = false;
+
+    /// Constructs a midstate from its bytes
+    pub fn from_byte_array(bytes: [u8; 64]) -> Midstate {
+        Midstate(bytes)
+    }
+
+    /// Construct a new midstate from the inner value
+    #[deprecated(note = "use `from_byte_array`, which takes the same bytes")]
+    pub fn from_inner(inner: [u8; 64]) -> Midstate {
+        Midstate(inner)
+    }
+
+    /// Copies a byte slice into a midstate object
+    pub fn from_slice(sl: &[u8]) -> Result<Midstate, Error> {
+        if sl.len() != Self::LEN {
+            Err(Error::InvalidLength(Self::LEN, sl.len()))
+        } else {
+            let mut ret = [0; 64];
+            ret.copy_from_slice(sl);
+            Ok(Midstate(ret))
+        }
+    }
+
+    /// Returns the bytes of the midstate
+    pub fn to_byte_array(self) -> [u8; 64] {
+        self.0
+    }
+
+    /// Unwraps the midstate and returns the underlying byte array
+    #[deprecated(note = "use `to_byte_array`, which returns the same bytes")]
+    pub fn into_inner(self) -> [u8; 64] {
+        self.0
+    }
+}
+
+impl hex::FromHex for Midstate {
+    fn from_hex(s: &str) -> Result<Midstate, Error> {
+        let mut bytes: [u8; 64] = hex::FromHex::from_hex(s)?;
+        if Self::DISPLAY_BACKWARD {
+            bytes.reverse();
+        }
+        Ok(Midstate(bytes))
+    }
+}
 
 impl HashTrait for Hash {
     type Engine = HashEngine;
     type Inner = [u8; 64];
 
     fn engine() -> HashEngine {
-        HashEngine {
-            h: [
-                0x6a09e667f3bcc908, 0xbb67ae8584caa73b, 0x3c6ef372fe94f82b, 0xa54ff53a5f1d36f1,
-                0x510e527fade682d1, 0x9b05688c2b3e6c1f, 0x1f83d9abfb41bd6b, 0x5be0cd19137e2179,
-            ],
-            length: 0,
-            buffer: [0; BLOCK_SIZE],
-        }
+        HashEngine::with_iv([
+            0x6a09e667f3bcc908, 0xbb67ae8584caa73b, 0x3c6ef372fe94f82b, 0xa54ff53a5f1d36f1,
+            0x510e527fade682d1, 0x9b05688c2b3e6c1f, 0x1f83d9abfb41bd6b, 0x5be0cd19137e2179,
+        ])
     }
 
     #[cfg(not(feature = "fuzztarget"))]
     fn from_engine(mut e: HashEngine) -> Hash {
-        use std::io::Write;
-        use byteorder::WriteBytesExt;
-
         // pad buffer with a single 1-bit then all 0s, until there are exactly 16 bytes remaining
         let data_len = e.length as u64;
 
         let zeroes = [0; BLOCK_SIZE - 16];
-        e.write_all(&[0x80]).unwrap();
+        e.input(&[0x80]);
         if e.length % BLOCK_SIZE > zeroes.len() {
-            e.write_all(&zeroes).unwrap();
+            e.input(&zeroes);
         }
         let pad_length = zeroes.len() - (e.length % BLOCK_SIZE);
-        e.write_all(&zeroes[..pad_length]).unwrap();
+        e.input(&zeroes[..pad_length]);
         debug_assert_eq!(e.length % BLOCK_SIZE, zeroes.len());
 
-        e.write_u64::<BigEndian>(0).unwrap();
-        e.write_u64::<BigEndian>(8 * data_len).unwrap();
+        let mut len_bytes = [0; 16];
+        BigEndian::write_u64(&mut len_bytes[8..], 8 * data_len);
+        e.input(&len_bytes);
         debug_assert_eq!(e.length % BLOCK_SIZE, 0);
 
-        Hash(e.midstate())
+        Hash(e.midstate().0)
     }
 
     #[cfg(feature = "fuzztarget")]
     fn from_engine(e: HashEngine) -> Hash {
-        let mut hash = e.midstate();
+        let mut hash = e.midstate().0;
         hash[0] ^= 0xff; // Make this distinct from SHA-256
         Hash(hash)
     }
@@ -181,7 +340,11 @@ impl HashTrait for Hash {
         }
     }
 
-    fn into_inner(self) -> Self::Inner {
+    fn from_byte_array(bytes: Self::Inner) -> Self {
+        Hash(bytes)
+    }
+
+    fn to_byte_array(self) -> Self::Inner {
         self.0
     }
 }
@@ -327,7 +490,7 @@ mod tests {
 
     use sha512;
     use hex::{FromHex, ToHex};
-    use Hash;
+    use {Error, Hash, HashEngine};
 
     #[derive(Clone)]
     struct Test {
@@ -398,10 +561,34 @@ mod tests {
             }
             let manual_hash = sha512::Hash::from_engine(engine);
             assert_eq!(hash, manual_hash);
-            assert_eq!(hash.into_inner()[..].as_ref(), test.output.as_slice());
+            assert_eq!(hash.to_byte_array()[..].as_ref(), test.output.as_slice());
         }
     }
 
+    #[test]
+    fn resume_from_midstate() {
+        let data: Vec<u8> = (0..300).map(|i| i as u8).collect();
+        let hash = sha512::Hash::hash(&data);
+
+        // Checkpoint after two full blocks, then resume from a hex-encoded midstate
+        let mut engine = sha512::Hash::engine();
+        engine.input(&data[..256]);
+        let checkpoint = engine.midstate().to_hex();
+
+        let midstate = sha512::Midstate::from_hex(&checkpoint).expect("parse midstate");
+        let mut engine = sha512::HashEngine::from_midstate(midstate, 256).expect("full blocks");
+        assert_eq!(engine.n_bytes_hashed(), 256);
+        engine.input(&data[256..]);
+        assert_eq!(engine.n_bytes_hashed(), 300);
+        assert_eq!(sha512::Hash::from_engine(engine), hash);
+
+        // The midstate does not cover partial blocks
+        assert_eq!(
+            sha512::HashEngine::from_midstate(midstate, 300).err(),
+            Some(Error::InvalidLength(256, 300)),
+        );
+    }
+
     #[cfg(feature="serde")]
     #[test]
     fn sha512_serde() {
@@ -0,0 +1,247 @@
+// Bitcoin Hashes Library
+// Written in 2019 by
+//   The rust-bitcoin developers
+//
+// To the extent possible under law, the author(s) have dedicated all
+// copyright and related and neighboring rights to this software to
+// the public domain worldwide. This software is distributed without
+// any warranty.
+//
+// You should have received a copy of the CC0 Public Domain Dedication
+// along with this software.
+// If not, see <http://creativecommons.org/publicdomain/zero/1.0/>.
+//
+
+//! # SHA512/224
+//!
+//! SHA512 started from a distinct initial state and truncated to 28 bytes,
+//! as specified in FIPS 180-4. On 64-bit platforms this is usually faster
+//! than SHA256.
+
+use sha512;
+use HashEngine as EngineTrait;
+use Hash as HashTrait;
+use Error;
+
+/// Engine to compute SHA512/224 hash function
+#[derive(Clone)]
+pub struct HashEngine(sha512::HashEngine);
+
+write_impl!(HashEngine);
+extend_impl!(HashEngine, Hash);
+engine_default_impl!(HashEngine, Hash);
+zeroize_impl!(HashEngine);
+
+// The inner engine zeroizes itself when dropped
+#[cfg(feature = "zeroize")]
+impl ::zeroize::ZeroizeOnDrop for HashEngine {}
+
+impl HashEngine {
+    /// Returns the number of bytes input so far
+    pub fn n_bytes_hashed(&self) -> u128 {
+        self.0.n_bytes_hashed()
+    }
+}
+
+impl EngineTrait for HashEngine {
+    type MidState = [u8; 64];
+
+    fn midstate(&self) -> [u8; 64] {
+        self.0.midstate().to_byte_array()
+    }
+
+    const BLOCK_SIZE: usize = 128;
+
+    fn input(&mut self, data: &[u8]) {
+        self.0.input(data)
+    }
+}
+
+/// Output of the SHA512/224 hash function
+#[derive(Copy, Clone, PartialEq, Eq, Default, PartialOrd, Ord, Hash)]
+pub struct Hash([u8; 28]);
+
+hex_fmt_impl!(Debug, Hash);
+hex_fmt_impl!(Display, Hash);
+hex_fmt_impl!(LowerHex, Hash);
+hex_fmt_impl!(UpperHex, Hash);
+index_impl!(Hash);
+serde_impl!(Hash, 28);
+borrow_slice_impl!(Hash);
+from_str_impl!(Hash);
+try_from_impl!(Hash);
+from_bytes_impl!(Hash, 28);
+into_iter_impl!(Hash);
+eq_bytes_impl!(Hash, 28);
+deref_impl!(Hash, 28);
+inner_ref_impl!(Hash, 28);
+ct_eq_impl!(Hash);
+zeroize_impl!(Hash);
+rand_impl!(Hash);
+proptest_impl!(Hash);
+quickcheck_impl!(Hash);
+
+impl HashTrait for Hash {
+    type Engine = HashEngine;
+    type Inner = [u8; 28];
+
+    fn engine() -> HashEngine {
+        HashEngine(sha512::HashEngine::with_iv([
+            0x8c3d37c819544da2, 0x73e1996689dcd4d6, 0x1dfab7ae32ff9c82, 0x679dd514582f9fcf,
+            0x0f6d2b697bd44da8, 0x77e36f7304c48942, 0x3f9d85a86a1d36c8, 0x1112e6ad91d692a1,
+        ]))
+    }
+
+    fn from_engine(e: HashEngine) -> Hash {
+        let mut ret = [0; 28];
+        ret.copy_from_slice(&sha512::Hash::from_engine(e.0)[..28]);
+        Hash(ret)
+    }
+
+    const LEN: usize = 28;
+
+    fn from_slice(sl: &[u8]) -> Result<Hash, Error> {
+        if sl.len() != 28 {
+            Err(Error::InvalidLength(Self::LEN, sl.len()))
+        } else {
+            let mut ret = [0; 28];
+            ret.copy_from_slice(sl);
+            Ok(Hash(ret))
+        }
+    }
+
+    fn from_byte_array(bytes: Self::Inner) -> Self {
+        Hash(bytes)
+    }
+
+    fn to_byte_array(self) -> Self::Inner {
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use sha512_224;
+    use hex::{FromHex, ToHex};
+    use Hash;
+
+    #[derive(Clone)]
+    struct Test {
+        input: Vec<u8>,
+        output: Vec<u8>,
+        output_str: &'static str,
+    }
+
+    #[test]
+    fn test() {
+        let tests = vec![
+            // Test vectors from the NIST examples
+            Test {
+                input: vec![],
+                output: vec![
+                    0x6e, 0xd0, 0xdd, 0x02, 0x80, 0x6f, 0xa8, 0x9e,
+                    0x25, 0xde, 0x06, 0x0c, 0x19, 0xd3, 0xac, 0x86,
+                    0xca, 0xbb, 0x87, 0xd6, 0xa0, 0xdd, 0xd0, 0x5c,
+                    0x33, 0x3b, 0x84, 0xf4,
+                ],
+                output_str: "6ed0dd02806fa89e25de060c19d3ac86cabb87d6a0ddd05c333b84f4",
+            },
+            Test {
+                input: b"abc".to_vec(),
+                output: vec![
+                    0x46, 0x34, 0x27, 0x0f, 0x70, 0x7b, 0x6a, 0x54,
+                    0xda, 0xae, 0x75, 0x30, 0x46, 0x08, 0x42, 0xe2,
+                    0x0e, 0x37, 0xed, 0x26, 0x5c, 0xee, 0xe9, 0xa4,
+                    0x3e, 0x89, 0x24, 0xaa,
+                ],
+                output_str: "4634270f707b6a54daae7530460842e20e37ed265ceee9a43e8924aa",
+            },
+            Test {
+                input: b"abcdefghbcdefghicdefghijdefghijkefghijklfghijklmghijklmnhijklmnoijklmnopjklmnopqklmnopqrlmnopqrsmnopqrstnopqrstu".to_vec(),
+                output: vec![
+                    0x23, 0xfe, 0xc5, 0xbb, 0x94, 0xd6, 0x0b, 0x23,
+                    0x30, 0x81, 0x92, 0x64, 0x0b, 0x0c, 0x45, 0x33,
+                    0x35, 0xd6, 0x64, 0x73, 0x4f, 0xe4, 0x0e, 0x72,
+                    0x68, 0x67, 0x4a, 0xf9,
+                ],
+                output_str: "23fec5bb94d60b23308192640b0c453335d664734fe40e7268674af9",
+            },
+        ];
+
+        for test in tests {
+            // Hash through high-level API, check hex encoding/decoding
+            let hash = sha512_224::Hash::hash(&test.input);
+            assert_eq!(hash, sha512_224::Hash::from_hex(test.output_str).expect("parse hex"));
+            assert_eq!(&hash[..], &test.output[..]);
+            assert_eq!(&hash.to_hex(), &test.output_str);
+
+            // Hash through engine, checking that we can input byte by byte
+            let mut engine = sha512_224::Hash::engine();
+            for ch in &test.input {
+                engine.write_all(&[*ch]).expect("write to engine");
+            }
+            let manual_hash = sha512_224::Hash::from_engine(engine);
+            assert_eq!(hash, manual_hash);
+            assert_eq!(hash.to_byte_array()[..].as_ref(), test.output.as_slice());
+        }
+    }
+
+    #[cfg(feature="serde")]
+    #[test]
+    fn sha512_224_serde() {
+        use serde_test::{Configure, Token, assert_tokens};
+
+        static HASH_BYTES: [u8; 28] = [
+            0x46, 0x34, 0x27, 0x0f, 0x70, 0x7b, 0x6a, 0x54,
+            0xda, 0xae, 0x75, 0x30, 0x46, 0x08, 0x42, 0xe2,
+            0x0e, 0x37, 0xed, 0x26, 0x5c, 0xee, 0xe9, 0xa4,
+            0x3e, 0x89, 0x24, 0xaa,
+        ];
+
+        let hash = sha512_224::Hash::from_slice(&HASH_BYTES).expect("right number of bytes");
+        assert_tokens(&hash.compact(), &[Token::BorrowedBytes(&HASH_BYTES[..])]);
+        assert_tokens(&hash.readable(), &[Token::Str("4634270f707b6a54daae7530460842e20e37ed265ceee9a43e8924aa")]);
+    }
+}
+
+#[cfg(all(test, feature="unstable"))]
+mod benches {
+    use std::io::Write;
+    use test::Bencher;
+
+    use sha512_224;
+    use Hash;
+
+    #[bench]
+    pub fn sha512_224_10(bh: & mut Bencher) {
+        let mut engine = sha512_224::Hash::engine();
+        let bytes = [1u8; 10];
+        bh.iter( || {
+            engine.write_all(&bytes).expect("write");
+        });
+        bh.bytes = bytes.len() as u64;
+    }
+
+    #[bench]
+    pub fn sha512_224_1k(bh: & mut Bencher) {
+        let mut engine = sha512_224::Hash::engine();
+        let bytes = [1u8; 1024];
+        bh.iter( || {
+            engine.write_all(&bytes).expect("write");
+        });
+        bh.bytes = bytes.len() as u64;
+    }
+
+    #[bench]
+    pub fn sha512_224_64k(bh: & mut Bencher) {
+        let mut engine = sha512_224::Hash::engine();
+        let bytes = [1u8; 65536];
+        bh.iter( || {
+            engine.write_all(&bytes).expect("write");
+        });
+        bh.bytes = bytes.len() as u64;
+    }
+
+}
@@ -0,0 +1,392 @@
+// Bitcoin Hashes Library
+// Written in 2019 by
+//   The rust-bitcoin developers
+//
+// To the extent possible under law, the author(s) have dedicated all
+// copyright and related and neighboring rights to this software to
+// the public domain worldwide. This software is distributed without
+// any warranty.
+//
+// You should have received a copy of the CC0 Public Domain Dedication
+// along with this software.
+// If not, see <http://creativecommons.org/publicdomain/zero/1.0/>.
+//
+
+//! # SHA512t (tagged SHA512)
+//!
+//! Tagged hashes in the style of BIP340, for protocols which need domain
+//! separation with a 512-bit output: `SHA512(SHA512(tag) || SHA512(tag) || msg)`.
+//! As with `sha256t`, the tag prefix fills exactly one block.
+//!
+//! ```rust
+//! use bitcoin_hashes::{sha512, sha512t, Hash};
+//!
+//! /// Tag for deriving per-session keys
+//! pub struct SessionKey;
+//!
+//! impl sha512t::Tag for SessionKey {
+//!     fn engine() -> sha512::HashEngine {
+//!         sha512t::tag_engine("example/session-key")
+//!     }
+//!
+//!     fn name() -> &'static str {
+//!         "SessionKey"
+//!     }
+//! }
+//!
+//! let key = sha512t::Hash::<SessionKey>::hash(b"session id");
+//! assert_eq!(key.len(), 64);
+//! ```
+
+use core::{borrow, cmp, fmt, hash, marker, ops, str};
+
+use hex::{self, FromHex};
+use sha512;
+use {Error, HashEngine as EngineTrait};
+use Hash as HashTrait;
+
+/// Trait representing a tag which can be used as a context for SHA512t hashes
+pub trait Tag {
+    /// Returns a hash engine which has already been fed the tag prefix
+    fn engine() -> sha512::HashEngine;
+
+    /// Name of the tag, used when debug-printing hashes. Defaults to
+    /// `"sha512t"`, as `sha256t::Tag::name` defaults to `"sha256t"`.
+    fn name() -> &'static str {
+        "sha512t"
+    }
+}
+
+/// Constructs an engine which has been fed the prefix for the given tag,
+/// hashing the tag at runtime
+pub fn tag_engine(tag: &str) -> sha512::HashEngine {
+    let tag_hash = sha512::Hash::hash(tag.as_bytes());
+    let mut engine = sha512::Hash::engine();
+    engine.input(&tag_hash[..]);
+    engine.input(&tag_hash[..]);
+    engine
+}
+
+/// Output of the SHA512t hash function
+pub struct Hash<T: Tag>([u8; 64], marker::PhantomData<T>);
+
+impl<T: Tag> Hash<T> {
+    fn internal_new(arr: [u8; 64]) -> Hash<T> {
+        Hash(arr, marker::PhantomData)
+    }
+
+    /// Returns a reference to the bytes of the hash, keeping their length
+    /// in the type unlike `as_ref`
+    pub fn inner_ref(&self) -> &[u8; 64] {
+        &self.0
+    }
+
+    /// Returns a mutable reference to the bytes of the hash. Once they are
+    /// changed the hash no longer is the hash of anything, so like `AsMut`
+    /// this needs the `as_mut` feature.
+    #[cfg(feature = "as_mut")]
+    pub fn inner_mut(&mut self) -> &mut [u8; 64] {
+        &mut self.0
+    }
+}
+
+impl<T: Tag> Copy for Hash<T> {}
+
+impl<T: Tag> Clone for Hash<T> {
+    fn clone(&self) -> Hash<T> {
+        *self
+    }
+}
+
+impl<T: Tag> PartialEq for Hash<T> {
+    fn eq(&self, other: &Hash<T>) -> bool {
+        self.0[..] == other.0[..]
+    }
+}
+
+impl<T: Tag> Eq for Hash<T> {}
+
+impl<T: Tag> Default for Hash<T> {
+    fn default() -> Hash<T> {
+        Hash::internal_new([0; 64])
+    }
+}
+
+impl<T: Tag> PartialOrd for Hash<T> {
+    fn partial_cmp(&self, other: &Hash<T>) -> Option<cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: Tag> Ord for Hash<T> {
+    fn cmp(&self, other: &Hash<T>) -> cmp::Ordering {
+        self.0[..].cmp(&other.0[..])
+    }
+}
+
+impl<T: Tag> hash::Hash for Hash<T> {
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        self.0[..].hash(state)
+    }
+}
+
+/// Formats as `Tag(hex)`, or with `{:#?}` as a struct naming the tag
+impl<T: Tag> fmt::Debug for Hash<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if f.alternate() {
+            f.debug_struct("Hash")
+                .field("tag", &T::name())
+                .field("bytes", &format_args!("\"{:x}\"", sha512::Hash::from_byte_array(self.0)))
+                .finish()
+        } else {
+            write!(f, "{}(", T::name())?;
+            hex::format_hex(&self.0, &mut *f)?;
+            f.write_str(")")
+        }
+    }
+}
+
+impl<T: Tag> fmt::Display for Hash<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        hex::format_hex(&self.0, f)
+    }
+}
+
+impl<T: Tag> fmt::LowerHex for Hash<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        hex::format_hex(&self.0, f)
+    }
+}
+
+impl<T: Tag> fmt::UpperHex for Hash<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        hex::format_hex_uppercase(&self.0, f)
+    }
+}
+
+impl<T: Tag> ops::Index<usize> for Hash<T> {
+    type Output = u8;
+    fn index(&self, index: usize) -> &u8 {
+        &self.0[index]
+    }
+}
+
+impl<T: Tag> ops::Index<ops::Range<usize>> for Hash<T> {
+    type Output = [u8];
+    fn index(&self, index: ops::Range<usize>) -> &[u8] {
+        &self.0[index]
+    }
+}
+
+impl<T: Tag> ops::Index<ops::RangeFrom<usize>> for Hash<T> {
+    type Output = [u8];
+    fn index(&self, index: ops::RangeFrom<usize>) -> &[u8] {
+        &self.0[index]
+    }
+}
+
+impl<T: Tag> ops::Index<ops::RangeTo<usize>> for Hash<T> {
+    type Output = [u8];
+    fn index(&self, index: ops::RangeTo<usize>) -> &[u8] {
+        &self.0[index]
+    }
+}
+
+impl<T: Tag> ops::Index<ops::RangeFull> for Hash<T> {
+    type Output = [u8];
+    fn index(&self, index: ops::RangeFull) -> &[u8] {
+        &self.0[index]
+    }
+}
+
+/// Dereferences to the bytes, as for the other hash types
+impl<T: Tag> ops::Deref for Hash<T> {
+    type Target = [u8; 64];
+    fn deref(&self) -> &[u8; 64] {
+        &self.0
+    }
+}
+
+impl<T: Tag> borrow::Borrow<[u8]> for Hash<T> {
+    fn borrow(&self) -> &[u8] {
+        &self[..]
+    }
+}
+
+impl<T: Tag> AsRef<[u8]> for Hash<T> {
+    fn as_ref(&self) -> &[u8] {
+        &self.0[..]
+    }
+}
+
+#[cfg(feature = "as_mut")]
+impl<T: Tag> AsMut<[u8]> for Hash<T> {
+    fn as_mut(&mut self) -> &mut [u8] {
+        &mut self.0[..]
+    }
+}
+
+impl<T: Tag> str::FromStr for Hash<T> {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Hash<T>, Error> {
+        FromHex::from_hex(s)
+    }
+}
+
+impl<T: Tag> HashTrait for Hash<T> {
+    type Engine = sha512::HashEngine;
+    type Inner = [u8; 64];
+
+    fn engine() -> sha512::HashEngine {
+        T::engine()
+    }
+
+    fn from_engine(e: sha512::HashEngine) -> Hash<T> {
+        Hash::internal_new(sha512::Hash::from_engine(e).to_byte_array())
+    }
+
+    const LEN: usize = 64;
+
+    fn from_slice(sl: &[u8]) -> Result<Hash<T>, Error> {
+        if sl.len() != 64 {
+            Err(Error::InvalidLength(Self::LEN, sl.len()))
+        } else {
+            let mut ret = [0; 64];
+            ret.copy_from_slice(sl);
+            Ok(Hash::internal_new(ret))
+        }
+    }
+
+    fn from_byte_array(bytes: Self::Inner) -> Self {
+        Hash::internal_new(bytes)
+    }
+
+    fn to_byte_array(self) -> Self::Inner {
+        self.0
+    }
+}
+
+/// Reinterprets the bytes of a SHA512 hash as a tagged hash. Nothing is
+/// hashed: this only asserts that the bytes are a hash with the tag `T`.
+impl<T: Tag> From<sha512::Hash> for Hash<T> {
+    fn from(hash: sha512::Hash) -> Hash<T> {
+        Hash::internal_new(hash.to_byte_array())
+    }
+}
+
+/// Reinterprets the bytes of a tagged hash as a plain SHA512 hash, without
+/// any further hashing
+impl<T: Tag> From<Hash<T>> for sha512::Hash {
+    fn from(hash: Hash<T>) -> sha512::Hash {
+        sha512::Hash::from_byte_array(hash.0)
+    }
+}
+
+#[cfg(feature = "subtle")]
+impl<T: Tag> ::subtle::ConstantTimeEq for Hash<T> {
+    fn ct_eq(&self, other: &Hash<T>) -> ::subtle::Choice {
+        ::subtle::ConstantTimeEq::ct_eq(&self.0[..], &other.0[..])
+    }
+}
+
+#[cfg(feature="serde")]
+impl<T: Tag> ::serde::Serialize for Hash<T> {
+    fn serialize<S: ::serde::Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        // Tagged hashes are displayed forward, exactly like plain SHA512 hashes
+        ::serde::Serialize::serialize(&sha512::Hash::from_byte_array(self.0), s)
+    }
+}
+
+#[cfg(feature="serde")]
+impl<'de, T: Tag> ::serde::Deserialize<'de> for Hash<T> {
+    fn deserialize<D: ::serde::Deserializer<'de>>(d: D) -> Result<Hash<T>, D::Error> {
+        let hash: sha512::Hash = ::serde::Deserialize::deserialize(d)?;
+        Ok(Hash::internal_new(hash.to_byte_array()))
+    }
+}
+
+/// Generates hashes of uniformly random bytes, for any tag
+#[cfg(feature = "proptest")]
+impl<T: Tag> ::proptest::arbitrary::Arbitrary for Hash<T> {
+    type Parameters = ();
+    type Strategy = ::proptest::strategy::Map<
+        <[u8; 64] as ::proptest::arbitrary::Arbitrary>::Strategy,
+        fn([u8; 64]) -> Hash<T>,
+    >;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        ::proptest::strategy::Strategy::prop_map(
+            ::proptest::arbitrary::any::<[u8; 64]>(),
+            Hash::internal_new as fn(_) -> _,
+        )
+    }
+}
+
+#[cfg(feature = "quickcheck")]
+impl<T: 'static + Tag> ::quickcheck::Arbitrary for Hash<T> {
+    fn arbitrary(g: &mut ::quickcheck::Gen) -> Hash<T> {
+        ::util::quickcheck_arbitrary(g)
+    }
+
+    fn shrink(&self) -> ::std::boxed::Box<dyn Iterator<Item = Hash<T>>> {
+        ::util::quickcheck_shrink(*self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hex::ToHex;
+    use sha512;
+    use sha512t;
+    use {Hash, HashEngine};
+
+    pub struct Session;
+
+    impl sha512t::Tag for Session {
+        fn engine() -> sha512::HashEngine {
+            sha512t::tag_engine("example/session")
+        }
+
+        fn name() -> &'static str {
+            "Session"
+        }
+    }
+
+    #[test]
+    fn tagged_hash() {
+        let tag_hash = sha512::Hash::hash(b"example/session");
+        let mut engine = sha512::Hash::engine();
+        engine.input(&tag_hash[..]);
+        engine.input(&tag_hash[..]);
+        // The prefix is exactly one block
+        assert_eq!(engine.n_bytes_hashed(), 128);
+        engine.input(b"message");
+        let expected = sha512::Hash::from_engine(engine);
+
+        let hash = sha512t::Hash::<Session>::hash(b"message");
+        assert_eq!(&hash[..], &expected[..]);
+        assert_eq!(hash.to_hex(), expected.to_hex());
+        assert_eq!(hash.to_string().parse::<sha512t::Hash<Session>>(), Ok(hash));
+        assert_eq!(sha512::Hash::from(hash), expected);
+        assert_eq!(sha512t::Hash::<Session>::from(expected), hash);
+
+        assert_eq!(format!("{:?}", hash), format!("Session({})", expected));
+        assert_eq!(
+            format!("{:#?}", hash),
+            format!("Hash {{\n    tag: \"Session\",\n    bytes: \"{}\",\n}}", expected),
+        );
+        assert_eq!(&sha512t::Hash::<Session>::default()[..], &[0; 64][..]);
+    }
+
+    #[cfg(feature="serde")]
+    #[test]
+    fn sha512t_serde() {
+        use serde_test::{Configure, Token, assert_tokens};
+
+        let hash = sha512t::Hash::<Session>::hash(b"message");
+        let hex: &'static str = Box::leak(hash.to_hex().into_boxed_str());
+        let bytes: &'static [u8] = Box::leak(hash[..].to_vec().into_boxed_slice());
+        assert_tokens(&hash.compact(), &[Token::BorrowedBytes(bytes)]);
+        assert_tokens(&hash.readable(), &[Token::Str(hex)]);
+    }
+}
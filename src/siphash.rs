@@ -0,0 +1,264 @@
+// Bitcoin Hashes Library
+// Written in 2019 by
+//   The rust-bitcoin developers
+//
+// To the extent possible under law, the author(s) have dedicated all
+// copyright and related and neighboring rights to this software to
+// the public domain worldwide. This software is distributed without
+// any warranty.
+//
+// You should have received a copy of the CC0 Public Domain Dedication
+// along with this software.
+// If not, see <http://creativecommons.org/publicdomain/zero/1.0/>.
+//
+
+//! # SipHash-2-4
+//!
+//! A keyed hash with a 64-bit output, as used for the short transaction IDs
+//! of BIP152 compact blocks. It is a pseudorandom function suitable for hash
+//! tables with untrusted keys, but its output is too short for it to be a
+//! collision-resistant hash function, so it does not implement `Hash`.
+
+use core::{fmt, hash};
+
+use byteorder::{ByteOrder, LittleEndian};
+
+use HashEngine as EngineTrait;
+
+const BLOCK_SIZE: usize = 8;
+
+macro_rules! sipround(
+    ($v:expr) => ({
+        $v[0] = $v[0].wrapping_add($v[1]); $v[1] = $v[1].rotate_left(13); $v[1] ^= $v[0];
+        $v[0] = $v[0].rotate_left(32);
+        $v[2] = $v[2].wrapping_add($v[3]); $v[3] = $v[3].rotate_left(16); $v[3] ^= $v[2];
+        $v[0] = $v[0].wrapping_add($v[3]); $v[3] = $v[3].rotate_left(21); $v[3] ^= $v[0];
+        $v[2] = $v[2].wrapping_add($v[1]); $v[1] = $v[1].rotate_left(17); $v[1] ^= $v[2];
+        $v[2] = $v[2].rotate_left(32);
+    })
+);
+
+/// A 128-bit SipHash key
+#[derive(Copy, Clone, PartialEq, Eq, Default)]
+pub struct SipHashKey(pub [u8; 16]);
+
+impl SipHashKey {
+    /// Constructs a key from its two 64-bit halves, as BIP152 does
+    pub fn from_u64s(k0: u64, k1: u64) -> SipHashKey {
+        let mut key = [0; 16];
+        LittleEndian::write_u64(&mut key[..8], k0);
+        LittleEndian::write_u64(&mut key[8..], k1);
+        SipHashKey(key)
+    }
+}
+
+zeroize_impl!(SipHashKey);
+
+// Keys are secret, so do not print them
+impl fmt::Debug for SipHashKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("SipHashKey(..)")
+    }
+}
+
+/// Engine to compute SipHash-2-4
+#[derive(Clone)]
+pub struct SipHashEngine {
+    h: [u64; 4],
+    length: usize,
+    buffer: [u8; BLOCK_SIZE],
+}
+
+write_impl!(SipHashEngine);
+zeroize_engine_impl!(SipHashEngine);
+
+impl SipHashEngine {
+    /// Constructs an engine hashing with the given key
+    pub fn new(key: &SipHashKey) -> SipHashEngine {
+        let k0 = LittleEndian::read_u64(&key.0[..8]);
+        let k1 = LittleEndian::read_u64(&key.0[8..]);
+        SipHashEngine {
+            h: [
+                k0 ^ 0x736f6d6570736575,
+                k1 ^ 0x646f72616e646f6d,
+                k0 ^ 0x6c7967656e657261,
+                k1 ^ 0x7465646279746573,
+            ],
+            length: 0,
+            buffer: [0; BLOCK_SIZE],
+        }
+    }
+
+    fn process_block(&mut self) {
+        let m = LittleEndian::read_u64(&self.buffer);
+        self.h[3] ^= m;
+        sipround!(self.h);
+        sipround!(self.h);
+        self.h[0] ^= m;
+    }
+
+    /// Produces the SipHash of all data input so far
+    pub fn finalize(&self) -> SipHashOutput {
+        let mut v = self.h;
+
+        // The final word holds the remaining bytes and the length mod 256
+        let rem = self.length % BLOCK_SIZE;
+        let mut last = [0; BLOCK_SIZE];
+        last[..rem].copy_from_slice(&self.buffer[..rem]);
+        last[7] = self.length as u8;
+        let b = LittleEndian::read_u64(&last);
+
+        v[3] ^= b;
+        sipround!(v);
+        sipround!(v);
+        v[0] ^= b;
+        v[2] ^= 0xff;
+        sipround!(v);
+        sipround!(v);
+        sipround!(v);
+        sipround!(v);
+        SipHashOutput(v[0] ^ v[1] ^ v[2] ^ v[3])
+    }
+}
+
+impl EngineTrait for SipHashEngine {
+    type MidState = [u64; 4];
+
+    fn midstate(&self) -> [u64; 4] {
+        self.h
+    }
+
+    const BLOCK_SIZE: usize = 8;
+
+    engine_input_impl!();
+}
+
+/// The output of SipHash-2-4
+#[derive(Copy, Clone, PartialEq, Eq, Default, PartialOrd, Ord, Hash)]
+pub struct SipHashOutput(pub u64);
+
+impl SipHashOutput {
+    /// Returns the numeric value of the output
+    pub fn to_u64(self) -> u64 {
+        self.0
+    }
+
+    /// Returns the output as little-endian bytes, the order in which
+    /// BIP152 serializes short IDs
+    pub fn to_le_bytes(self) -> [u8; 8] {
+        let mut ret = [0; 8];
+        LittleEndian::write_u64(&mut ret, self.0);
+        ret
+    }
+
+    /// Returns the output as big-endian bytes
+    pub fn to_be_bytes(self) -> [u8; 8] {
+        let mut ret = [0; 8];
+        ::byteorder::BigEndian::write_u64(&mut ret, self.0);
+        ret
+    }
+}
+
+impl fmt::LowerHex for SipHashOutput {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:016x}", self.0)
+    }
+}
+
+impl fmt::UpperHex for SipHashOutput {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:016X}", self.0)
+    }
+}
+
+impl fmt::Display for SipHashOutput {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::LowerHex::fmt(self, f)
+    }
+}
+
+impl fmt::Debug for SipHashOutput {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::LowerHex::fmt(self, f)
+    }
+}
+
+/// SipHash-2-4 as a `std::hash::Hasher`, for keyed hash tables
+#[derive(Clone)]
+pub struct SipHasher24(SipHashEngine);
+
+impl SipHasher24 {
+    /// Constructs a hasher with the given key
+    pub fn new(key: &SipHashKey) -> SipHasher24 {
+        SipHasher24(SipHashEngine::new(key))
+    }
+}
+
+impl hash::Hasher for SipHasher24 {
+    fn write(&mut self, bytes: &[u8]) {
+        self.0.input(bytes)
+    }
+
+    fn finish(&self) -> u64 {
+        self.0.finalize().0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::hash::Hasher;
+    use std::io::Write;
+
+    use siphash::{SipHashEngine, SipHashKey, SipHashOutput, SipHasher24};
+    use HashEngine;
+
+    #[test]
+    fn siphash24() {
+        // Test vectors from the SipHash paper and reference implementation,
+        // hashing the bytes 0, 1, .., len - 1 with the key 0, 1, .., 15
+        let tests = vec![
+            (0, 0x726fdb47dd0e0e31),
+            (1, 0x74f839c593dc67fd),
+            (7, 0xab0200f58b01d137),
+            (8, 0x93f5f5799a932462),
+            (15, 0xa129ca6149be45e5),
+            (16, 0x3f2acc7f57c29bdb),
+            (63, 0x958a324ceb064572),
+        ];
+
+        let mut key = [0; 16];
+        for (i, b) in key.iter_mut().enumerate() {
+            *b = i as u8;
+        }
+        let key = SipHashKey(key);
+        assert_eq!(key, SipHashKey::from_u64s(0x0706050403020100, 0x0f0e0d0c0b0a0908));
+
+        for (len, output) in tests {
+            let input: Vec<u8> = (0..len).collect();
+
+            let mut engine = SipHashEngine::new(&key);
+            engine.input(&input);
+            assert_eq!(engine.finalize(), SipHashOutput(output));
+
+            // Input byte by byte through `io::Write`
+            let mut engine = SipHashEngine::new(&key);
+            for ch in &input {
+                engine.write_all(&[*ch]).expect("write to engine");
+            }
+            assert_eq!(engine.finalize().to_u64(), output);
+
+            let mut hasher = SipHasher24::new(&key);
+            hasher.write(&input);
+            assert_eq!(hasher.finish(), output);
+        }
+    }
+
+    #[test]
+    fn output_bytes() {
+        let output = SipHashOutput(0xa129ca6149be45e5);
+        assert_eq!(output.to_le_bytes(), [0xe5, 0x45, 0xbe, 0x49, 0x61, 0xca, 0x29, 0xa1]);
+        assert_eq!(output.to_be_bytes(), [0xa1, 0x29, 0xca, 0x61, 0x49, 0xbe, 0x45, 0xe5]);
+        assert_eq!(output.to_string(), "a129ca6149be45e5");
+        assert_eq!(format!("{:?}", SipHashKey::default()), "SipHashKey(..)");
+    }
+}
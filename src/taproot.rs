@@ -0,0 +1,132 @@
+// Bitcoin Hashes Library
+// Written in 2019 by
+//   The rust-bitcoin developers
+//
+// To the extent possible under law, the author(s) have dedicated all
+// copyright and related and neighboring rights to this software to
+// the public domain worldwide. This software is distributed without
+// any warranty.
+//
+// You should have received a copy of the CC0 Public Domain Dedication
+// along with this software.
+// If not, see <http://creativecommons.org/publicdomain/zero/1.0/>.
+//
+
+//! # BIP340 and BIP341 tagged hashes
+//!
+//! The tags used by Schnorr signatures and Taproot, with their midstates
+//! precomputed, and the hash types they define. Note that the BIP341 tags
+//! are `TapLeaf`, `TapBranch`, `TapTweak` and `TapSighash`, without any
+//! `BIP0341/` prefix.
+
+use sha256;
+use sha256t;
+
+macro_rules! tag_impl(
+    ($tag:ident, $hash:ident, $string:expr, $doc:expr, $midstate:expr) => (
+        #[doc = $doc]
+        #[derive(Copy, Clone, PartialEq, Eq, Default, Debug)]
+        pub struct $tag;
+
+        impl $tag {
+            /// Midstate of the SHA256 engine after the tag prefix
+            pub const MIDSTATE: [u8; 32] = $midstate;
+
+            /// The tag string
+            pub const TAG: &'static str = $string;
+        }
+
+        impl sha256t::Tag for $tag {
+            fn engine() -> sha256::HashEngine {
+                sha256::HashEngine::from_midstate(sha256::Midstate::from_byte_array($tag::MIDSTATE), 64)
+                    .expect("the tag prefix is one block")
+            }
+
+            fn name() -> &'static str {
+                stringify!($tag)
+            }
+        }
+
+        #[doc = $doc]
+        pub type $hash = sha256t::Hash<$tag>;
+    )
+);
+
+tag_impl!(ChallengeTag, ChallengeHash, "BIP0340/challenge",
+    "The BIP340 tag for the challenge of a Schnorr signature", [
+    0x9c, 0xec, 0xba, 0x11, 0x23, 0x92, 0x53, 0x81, 0x11, 0x67, 0x91, 0x12, 0xd1, 0x62, 0x7e, 0x0f,
+    0x97, 0xc8, 0x75, 0x50, 0x00, 0x3c, 0xc7, 0x65, 0x90, 0xf6, 0x11, 0x64, 0x33, 0xe9, 0xb6, 0x6a,
+]);
+
+tag_impl!(AuxTag, AuxHash, "BIP0340/aux",
+    "The BIP340 tag for the auxiliary randomness of a Schnorr signature", [
+    0x24, 0xdd, 0x32, 0x19, 0x4e, 0xba, 0x7e, 0x70, 0xca, 0x0f, 0xab, 0xb9, 0x0f, 0xa3, 0x16, 0x6d,
+    0x3a, 0xfb, 0xe4, 0xb1, 0x4c, 0x44, 0xdf, 0x97, 0x4a, 0xac, 0x27, 0x39, 0x24, 0x9e, 0x85, 0x0a,
+]);
+
+tag_impl!(NonceTag, NonceHash, "BIP0340/nonce",
+    "The BIP340 tag for the nonce of a Schnorr signature", [
+    0x46, 0x61, 0x5b, 0x35, 0xf4, 0xbf, 0xbf, 0xf7, 0x9f, 0x8d, 0xc6, 0x71, 0x83, 0x62, 0x7a, 0xb3,
+    0x60, 0x21, 0x71, 0x80, 0x57, 0x35, 0x86, 0x61, 0x21, 0xa2, 0x9e, 0x54, 0x68, 0xb0, 0x7b, 0x4c,
+]);
+
+tag_impl!(TapBranchTag, TapBranchHash, "TapBranch",
+    "The BIP341 tag for the inner nodes of a script tree", [
+    0x23, 0xa8, 0x65, 0xa9, 0xb8, 0xa4, 0x0d, 0xa7, 0x97, 0x7c, 0x1e, 0x04, 0xc4, 0x9e, 0x24, 0x6f,
+    0xb5, 0xbe, 0x13, 0x76, 0x9d, 0x24, 0xc9, 0xb7, 0xb5, 0x83, 0xb5, 0xd4, 0xa8, 0xd2, 0x26, 0xd2,
+]);
+
+tag_impl!(TapLeafTag, TapLeafHash, "TapLeaf",
+    "The BIP341 tag for the leaves of a script tree", [
+    0x9c, 0xe0, 0xe4, 0xe6, 0x7c, 0x11, 0x6c, 0x39, 0x38, 0xb3, 0xca, 0xf2, 0xc3, 0x0f, 0x50, 0x89,
+    0xd3, 0xf3, 0x93, 0x6c, 0x47, 0x63, 0x6e, 0x60, 0x7d, 0xb3, 0x3e, 0xea, 0xdd, 0xc6, 0xf0, 0xc9,
+]);
+
+tag_impl!(TapTweakTag, TapTweakHash, "TapTweak",
+    "The BIP341 tag for the tweak of an output key", [
+    0xd1, 0x29, 0xa2, 0xf3, 0x70, 0x1c, 0x65, 0x5d, 0x65, 0x83, 0xb6, 0xc3, 0xb9, 0x41, 0x97, 0x27,
+    0x95, 0xf4, 0xe2, 0x32, 0x94, 0xfd, 0x54, 0xf4, 0xa2, 0xae, 0x8d, 0x85, 0x47, 0xca, 0x59, 0x0b,
+]);
+
+tag_impl!(TapSighashTag, TapSighashHash, "TapSighash",
+    "The BIP341 tag for signature hashes", [
+    0xf5, 0x04, 0xa4, 0x25, 0xd7, 0xf8, 0x78, 0x3b, 0x13, 0x63, 0x86, 0x8a, 0xe3, 0xe5, 0x56, 0x58,
+    0x6e, 0xee, 0x94, 0x5d, 0xbc, 0x78, 0x88, 0xdd, 0x02, 0xa6, 0xe2, 0xc3, 0x18, 0x73, 0xfe, 0x9f,
+]);
+
+#[cfg(test)]
+mod tests {
+    use sha256t::{self, Tag};
+    use taproot::*;
+    use {Hash, HashEngine};
+
+    fn check_tag<T: Tag>(tag: &str, midstate: [u8; 32], name: &str) {
+        assert_eq!(&T::engine().midstate()[..], &midstate[..]);
+        assert_eq!(T::engine().midstate(), sha256t::tag_engine(tag).midstate());
+        assert_eq!(T::name(), name);
+    }
+
+    #[test]
+    fn midstates() {
+        check_tag::<ChallengeTag>(ChallengeTag::TAG, ChallengeTag::MIDSTATE, "ChallengeTag");
+        check_tag::<AuxTag>(AuxTag::TAG, AuxTag::MIDSTATE, "AuxTag");
+        check_tag::<NonceTag>(NonceTag::TAG, NonceTag::MIDSTATE, "NonceTag");
+        check_tag::<TapBranchTag>(TapBranchTag::TAG, TapBranchTag::MIDSTATE, "TapBranchTag");
+        check_tag::<TapLeafTag>(TapLeafTag::TAG, TapLeafTag::MIDSTATE, "TapLeafTag");
+        check_tag::<TapTweakTag>(TapTweakTag::TAG, TapTweakTag::MIDSTATE, "TapTweakTag");
+        check_tag::<TapSighashTag>(TapSighashTag::TAG, TapSighashTag::MIDSTATE, "TapSighashTag");
+        assert_eq!(TapLeafTag::TAG, "TapLeaf");
+    }
+
+    #[test]
+    fn tap_leaf_hash() {
+        // The leaf hash of the script `OP_TRUE` with leaf version 0xc0: the
+        // leaf version, then the script with its compact size length
+        let mut engine = TapLeafHash::engine();
+        engine.input(&[0xc0, 0x01, 0x51]);
+        let hash = TapLeafHash::from_engine(engine);
+
+        let runtime = sha256t::DynamicTag::from_tag_string("TapLeaf").hash(&[0xc0, 0x01, 0x51]);
+        assert_eq!(&hash[..], &runtime[..]);
+    }
+}
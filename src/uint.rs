@@ -0,0 +1,123 @@
+// Bitcoin Hashes Library
+// Written in 2019 by
+//   The rust-bitcoin developers
+//
+// To the extent possible under law, the author(s) have dedicated all
+// copyright and related and neighboring rights to this software to
+// the public domain worldwide. This software is distributed without
+// any warranty.
+//
+// You should have received a copy of the CC0 Public Domain Dedication
+// along with this software.
+// If not, see <http://creativecommons.org/publicdomain/zero/1.0/>.
+//
+
+//! # 256-bit integers
+//!
+//! Just enough unsigned 256-bit arithmetic to compute proof-of-work from
+//! hashes; this is not meant to be a general purpose bignum type.
+
+use byteorder::{BigEndian, ByteOrder};
+
+/// An unsigned 256-bit integer, most significant limb first
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
+struct U256([u64; 4]);
+
+impl U256 {
+    fn from_be_bytes(bytes: &[u8; 32]) -> U256 {
+        let mut ret = [0; 4];
+        BigEndian::read_u64_into(bytes, &mut ret);
+        U256(ret)
+    }
+
+    fn to_be_bytes(self) -> [u8; 32] {
+        let mut ret = [0; 32];
+        BigEndian::write_u64_into(&self.0, &mut ret);
+        ret
+    }
+
+    fn is_zero(&self) -> bool {
+        self.0.iter().all(|&limb| limb == 0)
+    }
+
+    fn not(self) -> U256 {
+        U256([!self.0[0], !self.0[1], !self.0[2], !self.0[3]])
+    }
+
+    /// Adds one, returning `None` on overflow
+    fn checked_incr(mut self) -> Option<U256> {
+        for limb in self.0.iter_mut().rev() {
+            let (sum, carry) = limb.overflowing_add(1);
+            *limb = sum;
+            if !carry {
+                return Some(self);
+            }
+        }
+        None
+    }
+
+    fn wrapping_sub(mut self, other: U256) -> U256 {
+        let mut borrow = false;
+        for (a, b) in self.0.iter_mut().rev().zip(other.0.iter().rev()) {
+            let (diff, borrow1) = a.overflowing_sub(*b);
+            let (diff, borrow2) = diff.overflowing_sub(borrow as u64);
+            *a = diff;
+            borrow = borrow1 || borrow2;
+        }
+        self
+    }
+
+    /// Shifts left by one bit, returning the bit shifted out
+    fn shl1(&mut self) -> bool {
+        let carry = self.0[0] >> 63 == 1;
+        for i in 0..3 {
+            self.0[i] = (self.0[i] << 1) | (self.0[i + 1] >> 63);
+        }
+        self.0[3] <<= 1;
+        carry
+    }
+
+    fn bit(&self, n: usize) -> bool {
+        (self.0[3 - n / 64] >> (n % 64)) & 1 == 1
+    }
+
+    fn set_bit(&mut self, n: usize) {
+        self.0[3 - n / 64] |= 1 << (n % 64);
+    }
+
+    /// Long division; `divisor` must be nonzero
+    fn div(self, divisor: U256) -> U256 {
+        let mut quot = U256([0; 4]);
+        let mut rem = U256([0; 4]);
+        for n in (0..256).rev() {
+            let carry = rem.shl1();
+            if self.bit(n) {
+                rem.0[3] |= 1;
+            }
+            // If a bit was shifted out the remainder exceeds the divisor, and
+            // the wrapping subtraction yields the correct result
+            if carry || rem >= divisor {
+                rem = rem.wrapping_sub(divisor);
+                quot.set_bit(n);
+            }
+        }
+        quot
+    }
+}
+
+/// Computes `2^256 / (target + 1)` for a big-endian 256-bit `target`, the
+/// expected number of hashes needed to find one at most `target`. Returns
+/// `None` if `target` is zero, since the result would not fit in 256 bits.
+pub fn work(target: &[u8; 32]) -> Option<[u8; 32]> {
+    let target = U256::from_be_bytes(target);
+    if target.is_zero() {
+        return None;
+    }
+    // 2^256 does not fit, so compute (2^256 - target - 1) / (target + 1) + 1
+    // instead; when `target + 1` overflows the result is exactly one.
+    let work = match target.checked_incr() {
+        Some(divisor) => target.not().div(divisor).checked_incr()?,
+        None => U256([0, 0, 0, 1]),
+    };
+    Some(work.to_be_bytes())
+}
@@ -22,9 +22,21 @@ macro_rules! circular_lshift64 (
 );
 
 macro_rules! hex_fmt_impl(
+    (UpperHex, $ty:ident) => (
+        impl ::core::fmt::UpperHex for $ty {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+                use hex::{format_hex_uppercase, format_hex_reverse_uppercase};
+                if $ty::DISPLAY_BACKWARD {
+                    format_hex_reverse_uppercase(&self.0, f)
+                } else {
+                    format_hex_uppercase(&self.0, f)
+                }
+            }
+        }
+    );
     ($imp:ident, $ty:ident) => (
-        impl ::std::fmt::$imp for $ty {
-            fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        impl ::core::fmt::$imp for $ty {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
                 use hex::{format_hex, format_hex_reverse};
                 if $ty::DISPLAY_BACKWARD {
                     format_hex_reverse(&self.0, f)
@@ -38,37 +50,37 @@ macro_rules! hex_fmt_impl(
 
 macro_rules! index_impl(
     ($ty:ty) => (
-        impl ::std::ops::Index<usize> for $ty {
+        impl ::core::ops::Index<usize> for $ty {
             type Output = u8;
             fn index(&self, index: usize) -> &u8 {
                 &self.0[index]
             }
         }
 
-        impl ::std::ops::Index<::std::ops::Range<usize>> for $ty {
+        impl ::core::ops::Index<::core::ops::Range<usize>> for $ty {
             type Output = [u8];
-            fn index(&self, index: ::std::ops::Range<usize>) -> &[u8] {
+            fn index(&self, index: ::core::ops::Range<usize>) -> &[u8] {
                 &self.0[index]
             }
         }
 
-        impl ::std::ops::Index<::std::ops::RangeFrom<usize>> for $ty {
+        impl ::core::ops::Index<::core::ops::RangeFrom<usize>> for $ty {
             type Output = [u8];
-            fn index(&self, index: ::std::ops::RangeFrom<usize>) -> &[u8] {
+            fn index(&self, index: ::core::ops::RangeFrom<usize>) -> &[u8] {
                 &self.0[index]
             }
         }
 
-        impl ::std::ops::Index<::std::ops::RangeTo<usize>> for $ty {
+        impl ::core::ops::Index<::core::ops::RangeTo<usize>> for $ty {
             type Output = [u8];
-            fn index(&self, index: ::std::ops::RangeTo<usize>) -> &[u8] {
+            fn index(&self, index: ::core::ops::RangeTo<usize>) -> &[u8] {
                 &self.0[index]
             }
         }
 
-        impl ::std::ops::Index<::std::ops::RangeFull> for $ty {
+        impl ::core::ops::Index<::core::ops::RangeFull> for $ty {
             type Output = [u8];
-            fn index(&self, index: ::std::ops::RangeFull) -> &[u8] {
+            fn index(&self, index: ::core::ops::RangeFull) -> &[u8] {
                 &self.0[index]
             }
         }
@@ -77,44 +89,409 @@ macro_rules! index_impl(
 
 macro_rules! borrow_slice_impl(
     ($ty:ty) => (
-        impl ::std::borrow::Borrow<[u8]> for $ty {
+        impl ::core::borrow::Borrow<[u8]> for $ty {
             fn borrow(&self) -> &[u8] {
                 &self[..]
             }
         }
+
+        impl ::core::convert::AsRef<[u8]> for $ty {
+            fn as_ref(&self) -> &[u8] {
+                &self.0[..]
+            }
+        }
+
+        #[cfg(feature = "as_mut")]
+        impl ::core::convert::AsMut<[u8]> for $ty {
+            fn as_mut(&mut self) -> &mut [u8] {
+                &mut self.0[..]
+            }
+        }
     )
 );
 
-macro_rules! write_impl(
-    ($ty:ty) => (
-        impl ::std::io::Write for $ty {
-            fn flush(&mut self) -> ::std::io::Result<()> {
-                Ok(())
+macro_rules! from_str_impl(
+    ($ty:ident) => (
+        impl ::core::str::FromStr for $ty {
+            type Err = ::Error;
+            fn from_str(s: &str) -> Result<$ty, ::Error> {
+                ::hex::FromHex::from_hex(s)
             }
+        }
+    )
+);
 
-            #[cfg(not(feature = "fuzztarget"))]
-            fn write(&mut self, inp: &[u8]) -> ::std::io::Result<usize> {
+macro_rules! try_from_impl(
+    ($ty:ident) => (
+        impl<'a> ::core::convert::TryFrom<&'a [u8]> for $ty {
+            type Error = ::Error;
+            fn try_from(sl: &'a [u8]) -> Result<$ty, ::Error> {
+                <$ty as ::Hash>::from_slice(sl)
+            }
+        }
+
+        #[cfg(feature = "alloc")]
+        impl ::core::convert::TryFrom<::prelude::Vec<u8>> for $ty {
+            type Error = ::Error;
+            fn try_from(v: ::prelude::Vec<u8>) -> Result<$ty, ::Error> {
+                <$ty as ::Hash>::from_slice(&v)
+            }
+        }
+    )
+);
+
+macro_rules! from_bytes_impl(
+    ($ty:ident, $len:expr) => (
+        /// Wraps bytes given in their internal order, without hashing them,
+        /// as `Hash::from_byte_array` does
+        impl From<[u8; $len]> for $ty {
+            fn from(bytes: [u8; $len]) -> $ty {
+                $ty(bytes)
+            }
+        }
+
+        impl From<$ty> for [u8; $len] {
+            fn from(hash: $ty) -> [u8; $len] {
+                hash.0
+            }
+        }
+
+        #[cfg(feature = "alloc")]
+        impl From<$ty> for ::prelude::Vec<u8> {
+            fn from(hash: $ty) -> ::prelude::Vec<u8> {
+                hash.0.to_vec()
+            }
+        }
+    )
+);
+
+macro_rules! into_iter_impl(
+    ($ty:ident) => (
+        impl IntoIterator for $ty {
+            type Item = u8;
+            type IntoIter = ::HashBytes<$ty>;
+
+            fn into_iter(self) -> ::HashBytes<$ty> {
+                ::HashBytes::new(self)
+            }
+        }
+
+        impl<'a> IntoIterator for &'a $ty {
+            type Item = &'a u8;
+            type IntoIter = ::core::slice::Iter<'a, u8>;
+
+            fn into_iter(self) -> ::core::slice::Iter<'a, u8> {
+                self.0.iter()
+            }
+        }
+    )
+);
+
+macro_rules! eq_bytes_impl(
+    ($ty:ident, $len:expr) => (
+        /// Compares the bytes in their internal order, which is not the
+        /// display order for hashes with `DISPLAY_BACKWARD` set
+        impl PartialEq<[u8; $len]> for $ty {
+            fn eq(&self, other: &[u8; $len]) -> bool {
+                self.0[..] == other[..]
+            }
+        }
+
+        /// Compares the bytes in their internal order, which is not the
+        /// display order for hashes with `DISPLAY_BACKWARD` set
+        impl PartialEq<[u8]> for $ty {
+            fn eq(&self, other: &[u8]) -> bool {
+                self.0[..] == *other
+            }
+        }
+    )
+);
+
+macro_rules! deref_impl(
+    ($ty:ident, $len:expr) => (
+        /// Dereferences to the bytes in their internal order, which is not
+        /// the display order for hashes with `DISPLAY_BACKWARD` set; hashes
+        /// are also ordered by these bytes, so comparing the arrays agrees
+        /// with comparing the hashes. Slice methods are available through
+        /// the array, but since a type has only one `Deref` target, `&hash`
+        /// coerces to `&[u8; N]` and not to `&[u8]`; use `&hash[..]` for that.
+        impl ::core::ops::Deref for $ty {
+            type Target = [u8; $len];
+            fn deref(&self) -> &[u8; $len] {
+                &self.0
+            }
+        }
+    )
+);
+
+macro_rules! inner_ref_impl(
+    ($ty:ident, $len:expr) => (
+        impl $ty {
+            /// Returns a reference to the bytes of the hash in their internal
+            /// order, keeping their length in the type unlike `as_ref`
+            pub fn inner_ref(&self) -> &[u8; $len] {
+                &self.0
+            }
+
+            /// Returns a mutable reference to the bytes of the hash. Once
+            /// they are changed the hash no longer is the hash of anything,
+            /// so like `AsMut` this needs the `as_mut` feature.
+            #[cfg(feature = "as_mut")]
+            pub fn inner_mut(&mut self) -> &mut [u8; $len] {
+                &mut self.0
+            }
+        }
+    )
+);
+
+macro_rules! ct_eq_impl(
+    ($ty:ident) => (
+        #[cfg(feature = "subtle")]
+        impl ::subtle::ConstantTimeEq for $ty {
+            fn ct_eq(&self, other: &$ty) -> ::subtle::Choice {
+                ::subtle::ConstantTimeEq::ct_eq(&self.0[..], &other.0[..])
+            }
+        }
+    )
+);
+
+macro_rules! zeroize_impl(
+    ($ty:ident) => (
+        #[cfg(feature = "zeroize")]
+        impl ::zeroize::Zeroize for $ty {
+            fn zeroize(&mut self) {
+                ::zeroize::Zeroize::zeroize(&mut self.0);
+            }
+        }
+    )
+);
+
+macro_rules! rand_impl(
+    ($ty:ident) => (
+        #[cfg(feature = "rand")]
+        impl $ty {
+            /// Constructs a hash of random bytes, such as for a nonce or as
+            /// test data
+            pub fn from_rng<R: ::rand::RngCore + ?Sized>(rng: &mut R) -> $ty {
+                let mut ret = $ty::default();
+                rng.fill_bytes(&mut ret.0);
+                ret
+            }
+        }
+
+        #[cfg(feature = "rand")]
+        impl ::rand::distributions::Distribution<$ty> for ::rand::distributions::Standard {
+            fn sample<R: ::rand::Rng + ?Sized>(&self, rng: &mut R) -> $ty {
+                $ty::from_rng(rng)
+            }
+        }
+    )
+);
+
+macro_rules! proptest_impl(
+    ($ty:ident) => (
+        /// Generates hashes of uniformly random bytes, shrinking each byte
+        /// towards zero
+        #[cfg(feature = "proptest")]
+        impl ::proptest::arbitrary::Arbitrary for $ty {
+            type Parameters = ();
+            type Strategy = ::proptest::strategy::Map<
+                <<$ty as ::Hash>::Inner as ::proptest::arbitrary::Arbitrary>::Strategy,
+                fn(<$ty as ::Hash>::Inner) -> $ty,
+            >;
+
+            fn arbitrary_with(_: ()) -> Self::Strategy {
+                ::proptest::strategy::Strategy::prop_map(
+                    ::proptest::arbitrary::any::<<$ty as ::Hash>::Inner>(),
+                    <$ty as ::Hash>::from_byte_array as fn(_) -> _,
+                )
+            }
+        }
+    )
+);
+
+macro_rules! quickcheck_impl(
+    ($ty:ident) => (
+        #[cfg(feature = "quickcheck")]
+        impl ::quickcheck::Arbitrary for $ty {
+            fn arbitrary(g: &mut ::quickcheck::Gen) -> $ty {
+                ::util::quickcheck_arbitrary(g)
+            }
+
+            fn shrink(&self) -> ::std::boxed::Box<dyn Iterator<Item = $ty>> {
+                ::util::quickcheck_shrink(*self)
+            }
+        }
+    )
+);
+
+/// Generates a hash of random bytes
+#[cfg(feature = "quickcheck")]
+pub fn quickcheck_arbitrary<H: ::Hash>(g: &mut ::quickcheck::Gen) -> H {
+    let bytes: ::std::vec::Vec<u8> = (0..H::LEN).map(|_| ::quickcheck::Arbitrary::arbitrary(g)).collect();
+    H::from_slice(&bytes).expect("right number of bytes")
+}
+
+/// Shrinks a hash by zeroing runs of its non-zero bytes, starting with all
+/// of them and halving the length of the runs, as quickcheck shrinks a `Vec`
+/// by removing runs of its elements
+#[cfg(feature = "quickcheck")]
+pub fn quickcheck_shrink<H: ::Hash + 'static>(hash: H) -> ::std::boxed::Box<dyn Iterator<Item = H>> {
+    let nonzero: ::std::vec::Vec<usize> = (0..H::LEN).filter(|&i| hash[..][i] != 0).collect();
+    let count = nonzero.len();
+    let run_lengths = ::core::iter::successors(Some(count), |len| Some(len / 2)).take_while(|&len| len > 0);
+    ::std::boxed::Box::new(run_lengths.flat_map(move |len| {
+        let nonzero = nonzero.clone();
+        (0..count).step_by(len).map(move |start| {
+            let mut bytes = hash[..].to_vec();
+            for &i in nonzero.iter().skip(start).take(len) {
+                bytes[i] = 0;
+            }
+            H::from_slice(&bytes).expect("right number of bytes")
+        })
+    }))
+}
+
+macro_rules! zeroize_engine_impl(
+    ($ty:ident $(, $field:ident)*) => (
+        #[cfg(feature = "zeroize")]
+        impl ::zeroize::Zeroize for $ty {
+            fn zeroize(&mut self) {
+                ::zeroize::Zeroize::zeroize(&mut self.h);
+                ::zeroize::Zeroize::zeroize(&mut self.buffer);
+                ::zeroize::Zeroize::zeroize(&mut self.length);
+                $(::zeroize::Zeroize::zeroize(&mut self.$field);)*
+            }
+        }
+
+        #[cfg(feature = "zeroize")]
+        impl Drop for $ty {
+            fn drop(&mut self) {
+                ::zeroize::Zeroize::zeroize(self);
+            }
+        }
+
+        #[cfg(feature = "zeroize")]
+        impl ::zeroize::ZeroizeOnDrop for $ty {}
+    )
+);
+
+macro_rules! bitop_impl(
+    ($ty:ident, $imp:ident, $method:ident, $imp_assign:ident, $method_assign:ident, $op:tt) => (
+        impl ::core::ops::$imp for $ty {
+            type Output = $ty;
+            fn $method(mut self, rhs: $ty) -> $ty {
+                ::core::ops::$imp_assign::$method_assign(&mut self, rhs);
+                self
+            }
+        }
+
+        impl ::core::ops::$imp_assign for $ty {
+            fn $method_assign(&mut self, rhs: $ty) {
+                for (a, b) in self.0.iter_mut().zip(rhs.0.iter()) {
+                    *a $op *b;
+                }
+            }
+        }
+    )
+);
+
+macro_rules! bitops_impl(
+    ($ty:ident) => (
+        bitop_impl!($ty, BitXor, bitxor, BitXorAssign, bitxor_assign, ^=);
+        bitop_impl!($ty, BitAnd, bitand, BitAndAssign, bitand_assign, &=);
+        bitop_impl!($ty, BitOr, bitor, BitOrAssign, bitor_assign, |=);
+
+        impl ::core::ops::Not for $ty {
+            type Output = $ty;
+            fn not(mut self) -> $ty {
+                for a in self.0.iter_mut() {
+                    *a = !*a;
+                }
+                self
+            }
+        }
+    )
+);
+
+macro_rules! engine_input_impl(
+    () => (
+        #[cfg(not(feature = "fuzztarget"))]
+        fn input(&mut self, mut inp: &[u8]) {
+            while !inp.is_empty() {
                 let buf_idx = self.length % <Self as ::HashEngine>::BLOCK_SIZE;
                 let rem_len = <Self as ::HashEngine>::BLOCK_SIZE - buf_idx;
-                let write_len = ::std::cmp::min(rem_len, inp.len());
+                let write_len = ::core::cmp::min(rem_len, inp.len());
 
                 self.buffer[buf_idx..buf_idx + write_len].copy_from_slice(&inp[..write_len]);
                 self.length += write_len;
                 if self.length % <Self as ::HashEngine>::BLOCK_SIZE == 0 {
                     self.process_block();
                 }
+                inp = &inp[write_len..];
+            }
+        }
 
-                Ok(write_len)
+        #[cfg(feature = "fuzztarget")]
+        fn input(&mut self, inp: &[u8]) {
+            for c in inp {
+                self.buffer[0] ^= *c;
+            }
+            self.length += inp.len();
+        }
+    )
+);
+
+macro_rules! write_impl(
+    ($ty:ty) => (
+        #[cfg(feature = "std")]
+        impl ::std::io::Write for $ty {
+            fn flush(&mut self) -> ::std::io::Result<()> {
+                Ok(())
             }
 
-            #[cfg(feature = "fuzztarget")]
             fn write(&mut self, inp: &[u8]) -> ::std::io::Result<usize> {
-                for c in inp {
-                    self.buffer[0] ^= *c;
-                }
-                self.length += inp.len();
+                ::HashEngine::input(self, inp);
                 Ok(inp.len())
             }
         }
     )
 );
+
+macro_rules! engine_default_impl(
+    ($ty:ty, $hash:ty) => (
+        impl Default for $ty {
+            fn default() -> $ty {
+                <$hash as ::Hash>::engine()
+            }
+        }
+    )
+);
+
+macro_rules! extend_impl(
+    ($ty:ty, $hash:ty) => (
+        impl ::core::iter::Extend<u8> for $ty {
+            fn extend<I: IntoIterator<Item = u8>>(&mut self, iter: I) {
+                for byte in iter {
+                    ::HashEngine::input(self, &[byte]);
+                }
+            }
+        }
+
+        impl<'a> ::core::iter::Extend<&'a u8> for $ty {
+            fn extend<I: IntoIterator<Item = &'a u8>>(&mut self, iter: I) {
+                for byte in iter {
+                    ::HashEngine::input(self, &[*byte]);
+                }
+            }
+        }
+
+        impl ::core::iter::FromIterator<u8> for $ty {
+            fn from_iter<I: IntoIterator<Item = u8>>(iter: I) -> $ty {
+                let mut engine = <$hash as ::Hash>::engine();
+                engine.extend(iter);
+                engine
+            }
+        }
+    )
+);
@@ -0,0 +1,201 @@
+// Bitcoin Hashes Library
+// Written in 2019 by
+//   The rust-bitcoin developers
+//
+// To the extent possible under law, the author(s) have dedicated all
+// copyright and related and neighboring rights to this software to
+// the public domain worldwide. This software is distributed without
+// any warranty.
+//
+// You should have received a copy of the CC0 Public Domain Dedication
+// along with this software.
+// If not, see <http://creativecommons.org/publicdomain/zero/1.0/>.
+//
+
+//! # Extendable-output functions
+//!
+//! Unlike the fixed-length functions implementing `Hash`, an XOF can produce
+//! an arbitrary amount of output. Data is input into an `XofEngine` exactly
+//! as into a `HashEngine`; the engine is then finalized into an `XofReader`
+//! from which output can be read incrementally.
+
+use keccak::{self, Sponge};
+use EngineWrite;
+
+/// An engine for an extendable-output function
+pub trait XofEngine: Clone + EngineWrite {
+    /// Reader which yields the output of the finalized engine
+    type Reader: XofReader;
+
+    /// Add data to the engine without any error return type to deal with
+    fn input(&mut self, data: &[u8]);
+
+    /// Finishes the input and returns a reader for the output
+    fn finalize(self) -> Self::Reader;
+}
+
+/// A reader for the output of a finalized `XofEngine`
+pub trait XofReader {
+    /// Fills `buf` with the next `buf.len()` bytes of output. Reading in
+    /// several calls yields the same bytes as reading all at once.
+    fn read(&mut self, buf: &mut [u8]);
+}
+
+/// Engine to compute the SHAKE128 or SHAKE256 extendable-output functions
+#[derive(Clone)]
+pub struct ShakeEngine(Sponge);
+
+impl ShakeEngine {
+    /// Construct a new SHAKE128 engine
+    pub fn shake128() -> ShakeEngine {
+        ShakeEngine(Sponge::new(168))
+    }
+
+    /// Construct a new SHAKE256 engine
+    pub fn shake256() -> ShakeEngine {
+        ShakeEngine(Sponge::new(136))
+    }
+}
+
+impl XofEngine for ShakeEngine {
+    type Reader = ShakeReader;
+
+    fn input(&mut self, data: &[u8]) {
+        self.0.absorb(data)
+    }
+
+    fn finalize(mut self) -> ShakeReader {
+        self.0.pad(keccak::SHAKE_DOMAIN);
+        ShakeReader(self.0)
+    }
+}
+
+#[cfg(feature = "std")]
+impl ::std::io::Write for ShakeEngine {
+    fn flush(&mut self) -> ::std::io::Result<()> {
+        Ok(())
+    }
+
+    fn write(&mut self, buf: &[u8]) -> ::std::io::Result<usize> {
+        self.input(buf);
+        Ok(buf.len())
+    }
+}
+
+/// Reader for the output of a SHAKE engine
+#[derive(Clone)]
+pub struct ShakeReader(Sponge);
+
+impl XofReader for ShakeReader {
+    fn read(&mut self, buf: &mut [u8]) {
+        self.0.squeeze(buf)
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl ::zeroize::Zeroize for ShakeEngine {
+    fn zeroize(&mut self) {
+        ::zeroize::Zeroize::zeroize(&mut self.0);
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl ::zeroize::Zeroize for ShakeReader {
+    fn zeroize(&mut self) {
+        ::zeroize::Zeroize::zeroize(&mut self.0);
+    }
+}
+
+// The sponge zeroizes itself when dropped
+#[cfg(feature = "zeroize")]
+impl ::zeroize::ZeroizeOnDrop for ShakeEngine {}
+#[cfg(feature = "zeroize")]
+impl ::zeroize::ZeroizeOnDrop for ShakeReader {}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use hex::ToHex;
+    use super::{ShakeEngine, XofEngine, XofReader};
+
+    #[derive(Clone)]
+    struct Test {
+        input: Vec<u8>,
+        shake128: &'static str,
+        shake256: &'static str,
+    }
+
+    #[test]
+    fn test() {
+        let tests = vec![
+            // Test vectors computed with Python's `hashlib`
+            Test {
+                input: vec![],
+                shake128: "7f9c2ba4e88f827d616045507605853ed73b8093f6efbc88eb1a6eacfa66ef26",
+                shake256: "46b9dd2b0ba88d13233b3feb743eeb243fcd52ea62b81b82b50c27646ed5762f\
+                           d75dc4ddd8c0f200cb05019d67b592f6fc821c49479ab48640292eacb3b7c4be",
+            },
+            Test {
+                input: b"abc".to_vec(),
+                shake128: "5881092dd818bf5cf8a3ddb793fbcba74097d5c526a6d35f97b83351940f2cc8",
+                shake256: "483366601360a8771c6863080cc4114d8db44530f8f1e1ee4f94ea37e78b5739\
+                           d5a15bef186a5386c75744c0527e1faa9f8726e462a12a4feb06bd8801e751e4",
+            },
+            Test {
+                input: (0..200).collect(),
+                shake128: "0c4234ca1e31801ae606f8b8d8e0665c66f42a21d601c2681858a92c79ad5d69",
+                shake256: "4ee1ca03272b05d3bfb1e1c79a967f823b9fc5e4bb3987b1ba9e9cb5afb07a5e\
+                           e3a07fbd457a94364964a841e7f466e5a022e21ab7f673c18ba98cdb1d5aecfa",
+            },
+        ];
+
+        for test in tests {
+            let mut engine = ShakeEngine::shake128();
+            engine.input(&test.input);
+            let mut out = [0; 32];
+            engine.finalize().read(&mut out);
+            assert_eq!(out.to_hex(), test.shake128);
+
+            // Input byte by byte through `io::Write`
+            let mut engine = ShakeEngine::shake256();
+            for ch in &test.input {
+                engine.write_all(&[*ch]).expect("write to engine");
+            }
+            let mut out = [0; 64];
+            engine.finalize().read(&mut out);
+            assert_eq!(out.to_hex(), test.shake256);
+        }
+    }
+
+    #[test]
+    fn long_output() {
+        // 300 bytes of SHAKE256 output spans three output blocks
+        let expected = "483366601360a8771c6863080cc4114d8db44530f8f1e1ee4f94ea37e78b5739\
+                        d5a15bef186a5386c75744c0527e1faa9f8726e462a12a4feb06bd8801e751e4\
+                        1385141204f329979fd3047a13c5657724ada64d2470157b3cdc288620944d78\
+                        dbcddbd912993f0913f164fb2ce95131a2d09a3e6d51cbfc622720d7a75c6334\
+                        e8a2d7ec71a7cc29cf0ea610eeff1a588290a53000faa79932becec0bd3cd0b3\
+                        3a7e5d397fed1ada9442b99903f4dcfd8559ed3950faf40fe6f3b5d710ed3b67\
+                        7513771af6bfe11934817e8762d9896ba579d88d84ba7aa3cdc7055f6796f195\
+                        bd9ae788f2f5bb96100d6bbaff7fbc6eea24d4449a2477d172a5507dcc931412\
+                        fc346b1bb39b878330e026b12ddf384af3334560ea1d363966caa7d8ddcbec7d\
+                        a52b42215c11d5f8ee57f341";
+
+        let mut engine = ShakeEngine::shake256();
+        engine.input(b"abc");
+        let reader = engine.finalize();
+
+        let mut out = vec![0; 300];
+        reader.clone().read(&mut out);
+        assert_eq!(out.to_hex(), expected);
+
+        // Reading in uneven pieces gives the same output
+        let mut reader = reader;
+        let mut out = vec![0; 300];
+        for chunk in out.chunks_mut(7) {
+            reader.read(chunk);
+        }
+        assert_eq!(out.to_hex(), expected);
+    }
+}
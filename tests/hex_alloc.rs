@@ -0,0 +1,66 @@
+// Bitcoin Hashes Library
+// Written in 2019 by
+//   The rust-bitcoin developers
+//
+// To the extent possible under law, the author(s) have dedicated all
+// copyright and related and neighboring rights to this software to
+// the public domain worldwide. This software is distributed without
+// any warranty.
+//
+// You should have received a copy of the CC0 Public Domain Dedication
+// along with this software.
+// If not, see <http://creativecommons.org/publicdomain/zero/1.0/>.
+//
+
+//! Checks that hex encoding into a buffer and decoding into an array do
+//! not allocate, using an allocator which counts allocations per thread.
+
+extern crate bitcoin_hashes;
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+use bitcoin_hashes::hex::{self, FromHex, HexIterator};
+
+struct CountingAlloc;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = Cell::new(0);
+}
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        // `try_with` since the thread-local may already be destroyed
+        let _ = ALLOCATIONS.try_with(|n| n.set(n.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOC: CountingAlloc = CountingAlloc;
+
+fn allocations() -> usize {
+    ALLOCATIONS.with(|n| n.get())
+}
+
+#[test]
+fn no_allocation() {
+    let data = [0xde, 0xad, 0xbe, 0xef];
+    let mut output = [0; 8];
+    let mut decoded = [0; 4];
+
+    let before = allocations();
+    hex::format_hex_into(&data, &mut output).expect("right length");
+    hex::format_hex_reverse_into(&data, &mut output).expect("right length");
+    let array = <[u8; 4]>::from_hex("deadbeef").expect("valid hex");
+    HexIterator::new("deadbeef").expect("even length").fill(&mut decoded).expect("valid hex");
+    assert_eq!(allocations(), before);
+
+    assert_eq!(&output, b"efbeadde");
+    assert_eq!(array, data);
+    assert_eq!(decoded, data);
+}